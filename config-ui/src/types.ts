@@ -29,6 +29,7 @@ export interface ServerConfig {
   serverName?: string;
   instructions?: string;
   protocolVersionDefault?: string;
+  defaultFormat?: string;
   [key: string]: any;
 }
 
@@ -5,6 +5,8 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{error, info, warn};
 
+use crate::odoo::secrets;
+
 /// Result type for config operations that may need to notify the UI
 #[derive(Debug, Clone)]
 pub struct ConfigResult {
@@ -117,13 +119,22 @@ impl ConfigManager {
         Ok(config)
     }
 
+    /// Load instances config for display: plaintext credentials are masked and
+    /// secret references shown as written, never resolved.
+    pub async fn load_instances_masked(&self) -> anyhow::Result<Value> {
+        Ok(secrets::mask_instances(&self.load_instances().await?))
+    }
+
     /// Get cached instances config
     pub async fn get_instances(&self) -> Value {
         self.instances_cache.read().await.clone()
     }
 
-    /// Save instances config to file with backup and rollback support
-    pub async fn save_instances(&self, config: Value) -> anyhow::Result<ConfigResult> {
+    /// Save instances config to file with backup and rollback support.
+    ///
+    /// Credentials sent back masked (as returned by `load_instances_masked`)
+    /// keep their stored value.
+    pub async fn save_instances(&self, mut config: Value) -> anyhow::Result<ConfigResult> {
         let path = self.config_dir.join("instances.json");
 
         // Validate JSON structure
@@ -136,6 +147,10 @@ impl ConfigManager {
         // Create backup before modifying
         let backup = self.backup_file(&path);
 
+        if let Some(stored) = backup.as_deref().and_then(|b| Self::validate_json(b).ok()) {
+            secrets::unmask_instances(&mut config, &stored);
+        }
+
         // Create parent directory if not exists
         if let Some(parent) = path.parent()
             && let Err(e) = fs::create_dir_all(parent)
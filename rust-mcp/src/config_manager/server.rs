@@ -640,7 +640,7 @@ fn update_env_var(env_file_path: &PathBuf, key: &str, value: &str) -> anyhow::Re
 // =============================================================================
 
 async fn get_instances(State(state): State<AppState>) -> impl IntoResponse {
-    match state.config_manager.load_instances().await {
+    match state.config_manager.load_instances_masked().await {
         Ok(config) => (StatusCode::OK, Json(config)).into_response(),
         Err(e) => {
             error!("Failed to load instances: {}", e);
@@ -5,4 +5,5 @@
 pub mod cleanup;
 pub mod config_manager;
 pub mod mcp;
+pub mod metrics;
 pub mod odoo;
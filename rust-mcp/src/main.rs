@@ -4,7 +4,6 @@ use std::path::PathBuf;
 use std::sync::Arc;
 
 use clap::{Parser, ValueEnum};
-use mcp_rust_sdk::transport::websocket::WebSocketTransport;
 use tokio::net::TcpListener;
 use tokio_tungstenite::accept_async;
 use tracing::{error, info, warn};
@@ -16,6 +15,7 @@ use rust_mcp::mcp::http as mcp_http;
 use rust_mcp::mcp::registry::Registry;
 use rust_mcp::mcp::runtime::ServerCompat;
 use rust_mcp::mcp::tools::OdooClientPool;
+use rust_mcp::mcp::ws::WsTransport;
 
 /// Get config directory based on context:
 /// - If running as root/systemd service: /etc/rust-mcp
@@ -611,6 +611,7 @@ async fn main() -> anyhow::Result<()> {
     // Cleanup tool gating is handled via tool guards (e.g. requiresEnvTrue=ODOO_ENABLE_CLEANUP_TOOLS).
     // We keep the CLI flag for compatibility, but it only affects the env var via clap env binding.
     let handler = Arc::new(McpOdooHandler::new(pool, registry));
+    handler.start_watchers();
 
     // Create shared HTTP auth config (supports hot-reload)
     let http_auth_config = mcp_http::AuthConfig::from_env();
@@ -728,7 +729,7 @@ async fn run_ws(handler: Arc<McpOdooHandler>, listen: &str) -> anyhow::Result<()
         tokio::spawn(async move {
             match accept_async(stream).await {
                 Ok(ws_stream) => {
-                    let transport = WebSocketTransport::from_stream(ws_stream);
+                    let transport = WsTransport::from_stream(ws_stream);
                     let server = ServerCompat::new(Arc::new(transport), handler);
                     info!("Accepted ws connection from {}", addr);
                     if let Err(e) = server.start().await {
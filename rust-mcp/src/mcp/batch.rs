@@ -0,0 +1,238 @@
+//! JSON-RPC batches: an array of messages answered with one array of responses.
+//!
+//! HTTP answers a batch within its POST. The stdio and WebSocket transports
+//! hand the messages of a batch to [`ServerCompat`](super::runtime::ServerCompat)
+//! one by one and use [`Batches`] to gather the responses back into an array.
+//! Notifications get no entry in the array, so a batch holding only
+//! notifications gets no answer at all.
+
+use std::sync::Mutex;
+
+use mcp_rust_sdk::error::ErrorCode;
+use mcp_rust_sdk::protocol::Response;
+use mcp_rust_sdk::transport::Message;
+use serde_json::{Value, json};
+
+use super::cursor_stdio::parse_jsonrpc_value;
+
+/// Most messages accepted in one batch; its requests run concurrently.
+pub const MAX_BATCH_LEN: usize = 100;
+
+/// A JSON-RPC error response; `id` is null when the request's id is unknown.
+pub fn error_response(id: Option<Value>, code: ErrorCode, message: impl Into<String>) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id.unwrap_or(Value::Null),
+        "error": {
+            "code": i32::from(code),
+            "message": message.into()
+        }
+    })
+}
+
+/// The error answering a whole batch that cannot be processed, if any.
+pub fn check(items: &[Value]) -> Result<(), Value> {
+    if items.is_empty() {
+        return Err(error_response(
+            None,
+            ErrorCode::InvalidRequest,
+            "Empty batch",
+        ));
+    }
+    if items.len() > MAX_BATCH_LEN {
+        return Err(error_response(
+            None,
+            ErrorCode::InvalidRequest,
+            format!("Batch limited to {MAX_BATCH_LEN} messages"),
+        ));
+    }
+    Ok(())
+}
+
+/// Where a response produced by the server goes.
+#[derive(Debug)]
+pub enum Collected {
+    /// Not part of a batch: send it as is.
+    Single(Response),
+    /// Part of a batch that still waits for other responses.
+    Waiting,
+    /// The last response of a batch: send the whole array.
+    Complete(Value),
+}
+
+/// Batches of one connection whose requests are still running.
+#[derive(Default)]
+pub struct Batches {
+    pending: Mutex<Vec<Pending>>,
+}
+
+struct Pending {
+    /// Ids of the requests not answered yet.
+    waiting: Vec<Value>,
+    responses: Vec<Value>,
+}
+
+impl Batches {
+    /// Split a received batch into the messages to handle, and the answer to
+    /// send right away when nothing in it waits for the server (a batch error,
+    /// or only invalid entries and notifications).
+    pub fn receive(&self, items: Vec<Value>) -> (Vec<Message>, Option<Value>) {
+        if let Err(error) = check(&items) {
+            return (Vec::new(), Some(error));
+        }
+        let mut batches = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        let mut messages = Vec::with_capacity(items.len());
+        let mut pending = Pending {
+            waiting: Vec::new(),
+            responses: Vec::new(),
+        };
+        for item in items {
+            let id = item.get("id").cloned();
+            match parse_jsonrpc_value(item) {
+                // Responses are routed back by id, so it must be unique
+                // among the requests still running.
+                Ok(Message::Request(request))
+                    if std::iter::once(&pending)
+                        .chain(batches.iter())
+                        .any(|b| b.waiting.contains(&json!(request.id))) =>
+                {
+                    pending.responses.push(error_response(
+                        id,
+                        ErrorCode::InvalidRequest,
+                        "Duplicate request id",
+                    ))
+                }
+                Ok(Message::Request(request)) => {
+                    pending.waiting.push(json!(request.id));
+                    messages.push(Message::Request(request));
+                }
+                Ok(Message::Notification(notification)) => {
+                    messages.push(Message::Notification(notification))
+                }
+                // The server sends no requests, so it expects no responses.
+                Ok(Message::Response(_)) => pending.responses.push(error_response(
+                    id,
+                    ErrorCode::InvalidRequest,
+                    "Unexpected response in batch",
+                )),
+                Err(e) => pending.responses.push(error_response(
+                    id,
+                    ErrorCode::InvalidRequest,
+                    e.to_string(),
+                )),
+            }
+        }
+        if pending.waiting.is_empty() {
+            let reply = (!pending.responses.is_empty()).then_some(Value::Array(pending.responses));
+            return (messages, reply);
+        }
+        batches.push(pending);
+        (messages, None)
+    }
+
+    /// Route a response of the server to its batch, if it belongs to one.
+    pub fn collect(&self, response: Response) -> Collected {
+        let id = json!(response.id);
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(index) = pending.iter().position(|b| b.waiting.contains(&id)) else {
+            return Collected::Single(response);
+        };
+        let batch = &mut pending[index];
+        batch.waiting.retain(|waiting| waiting != &id);
+        batch
+            .responses
+            .push(serde_json::to_value(response).unwrap_or_else(|_| json!({})));
+        if !batch.waiting.is_empty() {
+            return Collected::Waiting;
+        }
+        Collected::Complete(Value::Array(pending.swap_remove(index).responses))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_rust_sdk::protocol::RequestId;
+
+    #[test]
+    fn test_batch_answered_once_complete() {
+        let batches = Batches::default();
+        let (messages, reply) = batches.receive(vec![
+            json!({"jsonrpc": "2.0", "id": 1, "method": "tools/list"}),
+            json!({"jsonrpc": "2.0", "method": "notifications/initialized"}),
+            json!({"jsonrpc": "2.0", "id": "b", "method": "ping"}),
+            json!({"jsonrpc": "2.0", "id": 3}),
+            json!(42),
+        ]);
+        assert_eq!(messages.len(), 3);
+        assert!(reply.is_none());
+
+        let done = |id| Response::success(id, Some(json!({})));
+        assert!(matches!(
+            batches.collect(done(RequestId::Number(7))),
+            Collected::Single(_)
+        ));
+        assert!(matches!(
+            batches.collect(done(RequestId::String("b".to_string()))),
+            Collected::Waiting
+        ));
+        let Collected::Complete(array) = batches.collect(done(RequestId::Number(1))) else {
+            panic!("batch not complete");
+        };
+        let array = array.as_array().unwrap();
+        // Invalid entries, then responses in completion order; no notification.
+        assert_eq!(array.len(), 4);
+        assert_eq!(array[0]["error"]["code"], -32600);
+        assert_eq!(array[0]["id"], 3);
+        assert_eq!(array[1]["id"], Value::Null);
+        assert_eq!(array[2]["id"], "b");
+        assert_eq!(array[3]["id"], 1);
+        assert!(batches.pending.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_ids_rejected() {
+        let batches = Batches::default();
+        let (messages, _) = batches.receive(vec![
+            json!({"jsonrpc": "2.0", "id": 1, "method": "tools/list"}),
+            json!({"jsonrpc": "2.0", "id": 1, "method": "ping"}),
+        ]);
+        assert_eq!(messages.len(), 1);
+        // Nor may another batch reuse an id still running.
+        let (messages, reply) =
+            batches.receive(vec![json!({"jsonrpc": "2.0", "id": 1, "method": "ping"})]);
+        assert!(messages.is_empty());
+        assert_eq!(
+            reply.unwrap()[0]["error"]["message"],
+            "Duplicate request id"
+        );
+
+        let Collected::Complete(array) =
+            batches.collect(Response::success(RequestId::Number(1), Some(json!({}))))
+        else {
+            panic!("batch not complete");
+        };
+        let array = array.as_array().unwrap();
+        assert_eq!(array.len(), 2);
+        assert_eq!(array[0]["error"]["code"], -32600);
+        assert_eq!(array[0]["id"], 1);
+        assert!(array[1]["result"].is_object());
+    }
+
+    #[test]
+    fn test_batches_without_requests_answered_at_once() {
+        let batches = Batches::default();
+        let (_, reply) = batches.receive(vec![json!({"jsonrpc": "2.0", "method": "ping"})]);
+        assert!(reply.is_none());
+
+        let (_, reply) = batches.receive(Vec::new());
+        assert_eq!(reply.unwrap()["error"]["message"], "Empty batch");
+
+        let (messages, reply) = batches.receive(vec![json!({"jsonrpc": "2.0"})]);
+        assert!(messages.is_empty());
+        assert_eq!(reply.unwrap().as_array().unwrap().len(), 1);
+
+        let (_, reply) = batches.receive(vec![json!({}); MAX_BATCH_LEN + 1]);
+        assert!(reply.unwrap().is_object());
+    }
+}
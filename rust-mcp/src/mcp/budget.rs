@@ -0,0 +1,546 @@
+//! Response size budgeting.
+//!
+//! A `search_read` without `fields` on a wide model can produce megabytes of
+//! JSON, which is more than an LLM client can use. Results of read-type tools
+//! are measured against a character budget (set per tool in `tools.json` via
+//! `responseBudget`, or globally via `ODOO_RESPONSE_MAX_CHARS` /
+//! `ODOO_RESPONSE_MAX_TOKENS`). Over budget the payload is shrunk in steps:
+//! heavy fields (binary, html, x2many id lists) are dropped, the largest arrays
+//! are cut, then long strings are shortened. Sizes are measured in the output
+//! format of the call, so a result that fits as CSV is not cut because its
+//! pretty JSON would not. A `truncated` note explains what
+//! was removed and how to narrow the query. Base64 data is never shortened, as
+//! a cut file is useless: a result that only fits that way is refused.
+
+use std::collections::BTreeSet;
+
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
+
+use super::format::{self, OutputFormat};
+
+/// Default global budget when nothing is configured (~25k tokens).
+pub const DEFAULT_MAX_CHARS: usize = 100_000;
+
+/// Rough characters-per-token ratio used to convert token budgets.
+pub const CHARS_PER_TOKEN: usize = 4;
+
+/// Room kept free for the `truncated` note itself.
+const NOTE_RESERVE: usize = 600;
+
+/// Strings longer than this are shortened in the last shrinking step.
+const MAX_STRING_CHARS: usize = 500;
+
+const HEAVY_TYPES: &[&str] = &["binary", "html", "one2many", "many2many"];
+
+/// Size limit for a tool's response. `0` in either field disables the budget.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+pub struct ResponseBudget {
+    #[serde(rename = "maxChars", default)]
+    pub max_chars: Option<usize>,
+    #[serde(rename = "maxTokens", default)]
+    pub max_tokens: Option<usize>,
+}
+
+impl ResponseBudget {
+    /// Global budget from `ODOO_RESPONSE_MAX_CHARS` / `ODOO_RESPONSE_MAX_TOKENS`.
+    pub fn from_env() -> Self {
+        let read = |var: &str| {
+            std::env::var(var)
+                .ok()
+                .and_then(|v| v.trim().parse::<usize>().ok())
+        };
+        Self {
+            max_chars: read("ODOO_RESPONSE_MAX_CHARS"),
+            max_tokens: read("ODOO_RESPONSE_MAX_TOKENS"),
+        }
+    }
+
+    fn is_set(&self) -> bool {
+        self.max_chars.is_some() || self.max_tokens.is_some()
+    }
+
+    /// Limit in characters, or `None` when the budget is disabled.
+    pub fn limit_chars(&self) -> Option<usize> {
+        if self.max_chars == Some(0) || self.max_tokens == Some(0) {
+            return None;
+        }
+        let from_tokens = self.max_tokens.map(|t| t.saturating_mul(CHARS_PER_TOKEN));
+        match (self.max_chars, from_tokens) {
+            (Some(c), Some(t)) => Some(c.min(t)),
+            (Some(c), None) => Some(c),
+            (None, Some(t)) => Some(t),
+            (None, None) => Some(DEFAULT_MAX_CHARS),
+        }
+    }
+
+    /// Effective limit: the tool's own budget wins over the global one.
+    pub fn effective_limit(tool: Option<&ResponseBudget>) -> Option<usize> {
+        match tool {
+            Some(b) if b.is_set() => b.limit_chars(),
+            _ => Self::from_env().limit_chars(),
+        }
+    }
+}
+
+/// Apply `limit` to a tool result of the form produced by `ok_text`
+/// (`{"content": [{"type": "text", "text": "<json>"}]}`) and render it in
+/// `format`, measuring the rendered text.
+///
+/// `Err` explains why a result holding base64 data cannot be made to fit.
+pub fn apply_budget(
+    mut result: Value,
+    limit: usize,
+    field_types: Option<&Map<String, Value>>,
+    format: OutputFormat,
+) -> Result<Value, String> {
+    let Some(text) = result.pointer("/content/0/text").and_then(|v| v.as_str()) else {
+        return Ok(result);
+    };
+    let Ok(mut payload) = serde_json::from_str::<Value>(text) else {
+        return Ok(result);
+    };
+    let measure = |v: &Value| format::render(v, format).len();
+    if !payload.is_object() || measure(&payload) <= limit {
+        return Ok(format::apply_format(result, format));
+    }
+    fit_payload(&mut payload, limit, field_types, &measure)?;
+    if let Some(slot) = result.pointer_mut("/content/0/text") {
+        *slot = Value::String(format::render(&payload, format));
+    }
+    Ok(result)
+}
+
+fn size(v: &Value) -> usize {
+    serde_json::to_string_pretty(v)
+        .map(|s| s.len())
+        .unwrap_or(0)
+}
+
+/// Shrink `payload` until `measure` (the length of its rendered text) fits
+/// `limit`. Returns `true` if anything was removed, or `Err` when only base64
+/// data is left to shorten.
+pub fn fit_payload(
+    payload: &mut Value,
+    limit: usize,
+    field_types: Option<&Map<String, Value>>,
+    measure: &dyn Fn(&Value) -> usize,
+) -> Result<bool, String> {
+    let original = measure(payload);
+    if original <= limit || !payload.is_object() {
+        return Ok(false);
+    }
+    let target = limit.saturating_sub(NOTE_RESERVE);
+
+    let mut dropped = BTreeSet::new();
+    drop_heavy_fields(payload, field_types, &mut dropped);
+
+    let mut arrays = Map::new();
+    // A few passes so that a second large array (e.g. nested lines) is also cut.
+    for _ in 0..3 {
+        if measure(payload) <= target {
+            break;
+        }
+        let Some((pointer, total)) = largest_array(payload) else {
+            break;
+        };
+        let kept = truncate_array(payload, &pointer, target, measure);
+        arrays.insert(pointer, json!({ "returned": kept, "total": total }));
+    }
+
+    let mut shortened = 0;
+    if measure(payload) > target {
+        shortened = shorten_strings(payload);
+    }
+    if measure(payload) > limit && contains_base64(payload) {
+        return Err(format!(
+            "Response of {original} characters exceeds the size budget of {limit} and holds \
+             binary data that cannot be shortened. Narrow the request (fewer records or \
+             fields), or raise the tool's 'responseBudget'."
+        ));
+    }
+
+    let mut note = Map::new();
+    note.insert("original_chars".to_string(), json!(original));
+    note.insert("limit_chars".to_string(), json!(limit));
+    if !dropped.is_empty() {
+        note.insert("dropped_fields".to_string(), json!(dropped));
+    }
+    if !arrays.is_empty() {
+        note.insert("arrays".to_string(), Value::Object(arrays));
+    }
+    if shortened > 0 {
+        note.insert("shortened_strings".to_string(), json!(shortened));
+    }
+    note.insert(
+        "hint".to_string(),
+        json!(
+            "Response exceeded the size budget. Narrow the query: list only the needed 'fields', \
+             use a more selective domain, or lower 'limit' and page with 'offset'."
+        ),
+    );
+    if let Some(obj) = payload.as_object_mut() {
+        obj.insert("truncated".to_string(), Value::Object(note));
+    }
+    Ok(true)
+}
+
+/// Remove binary/html/x2many values from record-like objects (objects with an `id`).
+fn drop_heavy_fields(
+    v: &mut Value,
+    field_types: Option<&Map<String, Value>>,
+    dropped: &mut BTreeSet<String>,
+) {
+    match v {
+        Value::Array(items) => items
+            .iter_mut()
+            .for_each(|i| drop_heavy_fields(i, field_types, dropped)),
+        Value::Object(obj) => {
+            if obj.contains_key("id") {
+                let heavy: Vec<String> = obj
+                    .iter()
+                    .filter(|(k, val)| is_heavy(k, val, field_types))
+                    .map(|(k, _)| k.clone())
+                    .collect();
+                for k in heavy {
+                    obj.remove(&k);
+                    dropped.insert(k);
+                }
+            }
+            obj.values_mut()
+                .for_each(|i| drop_heavy_fields(i, field_types, dropped));
+        }
+        _ => {}
+    }
+}
+
+fn is_heavy(key: &str, value: &Value, field_types: Option<&Map<String, Value>>) -> bool {
+    if matches!(value, Value::Bool(false) | Value::Null) {
+        return false;
+    }
+    let typed = field_types
+        .and_then(|t| t.get(key))
+        .and_then(|m| m.get("type"))
+        .and_then(|t| t.as_str());
+    if let Some(ttype) = typed {
+        return HEAVY_TYPES.contains(&ttype) && value.as_array().is_none_or(|a| !a.is_empty());
+    }
+    // No metadata: recognise the same shapes by value.
+    match value {
+        Value::String(s) => (s.len() > 256 && looks_like_html(s)) || is_base64_data(s),
+        Value::Array(items) => items.len() > 10 && items.iter().all(|i| i.is_i64()),
+        _ => false,
+    }
+}
+
+fn looks_like_html(s: &str) -> bool {
+    s.contains("</") || s.contains("<br") || s.contains("<p>")
+}
+
+fn looks_like_base64(s: &str) -> bool {
+    !s.contains(' ')
+        && s.bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'/' | b'=' | b'\n'))
+}
+
+/// JSON pointer and length of the array with the largest serialized size.
+fn largest_array(v: &Value) -> Option<(String, usize)> {
+    fn walk(v: &Value, path: &str, best: &mut Option<(String, usize, usize)>) {
+        match v {
+            Value::Array(items) => {
+                if items.len() > 1 {
+                    let s = size(v);
+                    if best.as_ref().is_none_or(|b| s > b.2) {
+                        *best = Some((path.to_string(), items.len(), s));
+                    }
+                }
+                for (i, item) in items.iter().enumerate() {
+                    walk(item, &format!("{path}/{i}"), best);
+                }
+            }
+            Value::Object(obj) => {
+                for (k, item) in obj {
+                    let escaped = k.replace('~', "~0").replace('/', "~1");
+                    walk(item, &format!("{path}/{escaped}"), best);
+                }
+            }
+            _ => {}
+        }
+    }
+    let mut best = None;
+    walk(v, "", &mut best);
+    best.map(|(p, len, _)| (p, len))
+}
+
+/// Keep the longest prefix of the array at `pointer` that fits `target`
+/// (at least one element). Returns the number of elements kept.
+fn truncate_array(
+    payload: &mut Value,
+    pointer: &str,
+    target: usize,
+    measure: &dyn Fn(&Value) -> usize,
+) -> usize {
+    let Some(Value::Array(items)) = payload.pointer(pointer).cloned() else {
+        return 0;
+    };
+    let fits = |payload: &mut Value, k: usize| -> bool {
+        if let Some(slot) = payload.pointer_mut(pointer) {
+            *slot = Value::Array(items[..k].to_vec());
+        }
+        measure(payload) <= target
+    };
+
+    let (mut lo, mut hi) = (1, items.len().saturating_sub(1).max(1));
+    while lo < hi {
+        let mid = (lo + hi).div_ceil(2);
+        if fits(payload, mid) {
+            lo = mid;
+        } else {
+            hi = mid - 1;
+        }
+    }
+    let kept = lo.min(items.len());
+    if let Some(slot) = payload.pointer_mut(pointer) {
+        *slot = Value::Array(items[..kept].to_vec());
+    }
+    kept
+}
+
+/// Whether `s` looks like base64 data (a file, an image) rather than text.
+fn is_base64_data(s: &str) -> bool {
+    s.len() > 256 && looks_like_base64(s)
+}
+
+fn contains_base64(v: &Value) -> bool {
+    match v {
+        Value::String(s) => is_base64_data(s),
+        Value::Array(items) => items.iter().any(contains_base64),
+        Value::Object(obj) => obj.values().any(contains_base64),
+        _ => false,
+    }
+}
+
+/// Shorten long text strings; base64 data is left whole.
+fn shorten_strings(v: &mut Value) -> usize {
+    match v {
+        Value::String(s) if s.chars().count() > MAX_STRING_CHARS && !is_base64_data(s) => {
+            let total = s.chars().count();
+            let head: String = s.chars().take(MAX_STRING_CHARS).collect();
+            *s = format!("{head}… [{} more chars]", total - MAX_STRING_CHARS);
+            1
+        }
+        Value::Array(items) => items.iter_mut().map(shorten_strings).sum(),
+        Value::Object(obj) => obj.values_mut().map(shorten_strings).sum(),
+        _ => 0,
+    }
+}
+
+/// Names of binary fields in a `fields_get` map.
+pub fn binary_fields(fields: &Map<String, Value>) -> Vec<String> {
+    fields
+        .iter()
+        .filter(|(_, m)| m.get("type").and_then(|t| t.as_str()) == Some("binary"))
+        .map(|(k, _)| k.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wrap(payload: &Value) -> Value {
+        json!({
+            "content": [{
+                "type": "text",
+                "text": serde_json::to_string_pretty(payload).unwrap()
+            }]
+        })
+    }
+
+    fn unwrap_text(result: &Value) -> Value {
+        serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap()
+    }
+
+    fn records(n: usize) -> Value {
+        let rows: Vec<Value> = (0..n)
+            .map(|i| json!({"id": i, "name": format!("Record {i}"), "state": "draft"}))
+            .collect();
+        json!({ "records": rows, "count": n })
+    }
+
+    #[test]
+    fn test_limit_chars() {
+        assert_eq!(
+            ResponseBudget::default().limit_chars(),
+            Some(DEFAULT_MAX_CHARS)
+        );
+        let b = ResponseBudget {
+            max_chars: Some(1000),
+            max_tokens: None,
+        };
+        assert_eq!(b.limit_chars(), Some(1000));
+        let b = ResponseBudget {
+            max_chars: Some(1000),
+            max_tokens: Some(100),
+        };
+        assert_eq!(b.limit_chars(), Some(400));
+        let b = ResponseBudget {
+            max_chars: Some(0),
+            max_tokens: None,
+        };
+        assert_eq!(b.limit_chars(), None);
+    }
+
+    #[test]
+    fn test_budget_deserialize() {
+        let b: ResponseBudget = serde_json::from_str(r#"{"maxTokens": 2000}"#).unwrap();
+        assert_eq!(b.max_tokens, Some(2000));
+        assert_eq!(b.max_chars, None);
+    }
+
+    #[test]
+    fn test_under_budget_untouched() {
+        let payload = records(3);
+        let result = apply_budget(wrap(&payload), 10_000, None, OutputFormat::Json).unwrap();
+        assert_eq!(unwrap_text(&result), payload);
+    }
+
+    #[test]
+    fn test_truncates_records() {
+        let result = apply_budget(wrap(&records(500)), 5_000, None, OutputFormat::Json).unwrap();
+        let text = result["content"][0]["text"].as_str().unwrap();
+        assert!(text.len() <= 5_000);
+        let payload = unwrap_text(&result);
+        let kept = payload["records"].as_array().unwrap().len();
+        assert!(kept > 1 && kept < 500);
+        assert_eq!(payload["truncated"]["arrays"]["/records"]["total"], 500);
+        assert_eq!(payload["truncated"]["arrays"]["/records"]["returned"], kept);
+        assert!(
+            payload["truncated"]["hint"]
+                .as_str()
+                .unwrap()
+                .contains("fields")
+        );
+    }
+
+    #[test]
+    fn test_measured_in_output_format() {
+        let payload = records(200);
+        let pretty = serde_json::to_string_pretty(&payload).unwrap().len();
+        let csv = format::render(&payload, OutputFormat::Csv).len();
+        let limit = (pretty + csv) / 2;
+        assert!(csv < limit && limit < pretty);
+
+        let result = apply_budget(wrap(&payload), limit, None, OutputFormat::Csv).unwrap();
+        let text = result["content"][0]["text"].as_str().unwrap();
+        assert_eq!(text, format::render(&payload, OutputFormat::Csv));
+
+        let result = apply_budget(wrap(&payload), limit, None, OutputFormat::Json).unwrap();
+        assert!(unwrap_text(&result).get("truncated").is_some());
+    }
+
+    #[test]
+    fn test_cut_result_rendered_in_output_format() {
+        let result = apply_budget(wrap(&records(500)), 3_000, None, OutputFormat::Csv).unwrap();
+        let text = result["content"][0]["text"].as_str().unwrap();
+        assert!(text.len() <= 3_000);
+        assert!(text.starts_with("id,"));
+        assert!(text.contains("# truncated:"));
+    }
+
+    #[test]
+    fn test_drops_heavy_fields_by_type() {
+        let mut types = Map::new();
+        types.insert("image_1920".to_string(), json!({"type": "binary"}));
+        types.insert("line_ids".to_string(), json!({"type": "one2many"}));
+        let payload = json!({
+            "records": [{
+                "id": 1,
+                "name": "x",
+                "image_1920": "A".repeat(4000),
+                "line_ids": [1, 2, 3]
+            }],
+            "count": 1
+        });
+        let result = apply_budget(wrap(&payload), 1_000, Some(&types), OutputFormat::Json).unwrap();
+        let payload = unwrap_text(&result);
+        assert!(payload["records"][0].get("image_1920").is_none());
+        assert!(payload["records"][0].get("line_ids").is_none());
+        assert_eq!(payload["records"][0]["name"], "x");
+        assert_eq!(
+            payload["truncated"]["dropped_fields"],
+            json!(["image_1920", "line_ids"])
+        );
+    }
+
+    #[test]
+    fn test_drops_heavy_fields_by_shape() {
+        let ids: Vec<i64> = (0..50).collect();
+        let payload = json!({
+            "records": [{
+                "id": 1,
+                "body": format!("<p>{}</p>", "lorem ipsum ".repeat(200)),
+                "tag_ids": ids,
+                "name": "kept"
+            }]
+        });
+        let result = apply_budget(wrap(&payload), 1_000, None, OutputFormat::Json).unwrap();
+        let payload = unwrap_text(&result);
+        assert!(payload["records"][0].get("body").is_none());
+        assert!(payload["records"][0].get("tag_ids").is_none());
+        assert_eq!(payload["records"][0]["name"], "kept");
+    }
+
+    #[test]
+    fn test_shortens_long_strings() {
+        let payload = json!({ "result": { "id": 1, "note": "word ".repeat(2000) } });
+        let result = apply_budget(wrap(&payload), 2_000, None, OutputFormat::Json).unwrap();
+        let payload = unwrap_text(&result);
+        assert!(
+            payload["result"]["note"]
+                .as_str()
+                .unwrap()
+                .contains("more chars")
+        );
+        assert_eq!(payload["truncated"]["shortened_strings"], 1);
+    }
+
+    #[test]
+    fn test_base64_never_shortened() {
+        let pdf = "JVBERi0xLjQK".repeat(10_000);
+        let payload = json!({
+            "pdf_base64": pdf,
+            "report_name": "sale.report_saleorder",
+            "record_ids": [1, 2, 3]
+        });
+        let err = apply_budget(wrap(&payload), 5_000, None, OutputFormat::Json).unwrap_err();
+        assert!(err.contains("Narrow the request"));
+
+        // Text around it may still be shortened as long as the result fits.
+        let payload = json!({ "datas": "QUJD".repeat(100), "note": "word ".repeat(2000) });
+        let payload =
+            unwrap_text(&apply_budget(wrap(&payload), 2_000, None, OutputFormat::Json).unwrap());
+        assert_eq!(payload["datas"], "QUJD".repeat(100));
+    }
+
+    #[test]
+    fn test_non_json_text_untouched() {
+        let result = json!({"content": [{"type": "text", "text": "x".repeat(100)}]});
+        assert_eq!(
+            apply_budget(result.clone(), 10, None, OutputFormat::Json).unwrap(),
+            result
+        );
+    }
+
+    #[test]
+    fn test_binary_fields() {
+        let fields = json!({
+            "name": {"type": "char"},
+            "image_128": {"type": "binary"},
+            "datas": {"type": "binary"}
+        });
+        let mut names = binary_fields(fields.as_object().unwrap());
+        names.sort();
+        assert_eq!(names, vec!["datas", "image_128"]);
+    }
+}
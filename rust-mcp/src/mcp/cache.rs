@@ -4,6 +4,8 @@ use std::time::{Duration, Instant};
 use serde_json::Value;
 use tokio::sync::RwLock;
 
+use crate::metrics;
+
 /// Cache key: (instance_name, model_name)
 type CacheKey = (String, String);
 
@@ -36,8 +38,10 @@ impl MetadataCache {
         if let Some((value, expiration)) = guard.get(&key)
             && Instant::now() < *expiration
         {
+            metrics::global().record_cache_lookup(true);
             return Some(value.clone());
         }
+        metrics::global().record_cache_lookup(false);
         None
     }
 
@@ -60,6 +64,12 @@ impl MetadataCache {
         guard.retain(|_, (_, expiration)| now < *expiration);
     }
 
+    /// Clear the entries of one instance, e.g. after its configuration changed.
+    pub async fn clear_instance(&self, instance: &str) {
+        let mut guard = self.cache.write().await;
+        guard.retain(|(cached, _), _| cached != instance);
+    }
+
     /// Clear all cache entries.
     pub async fn clear_all(&self) {
         let mut guard = self.cache.write().await;
@@ -157,6 +167,23 @@ mod tests {
         assert_eq!(cache.len().await, 0);
     }
 
+    #[tokio::test]
+    async fn test_cache_clear_instance() {
+        let cache = MetadataCache::new();
+        let value = serde_json::json!({"test": "data"});
+
+        cache
+            .insert("instance1", "model1", value.clone(), 300)
+            .await;
+        cache
+            .insert("instance2", "model1", value.clone(), 300)
+            .await;
+
+        cache.clear_instance("instance1").await;
+        assert_eq!(cache.get("instance1", "model1").await, None);
+        assert_eq!(cache.get("instance2", "model1").await, Some(value));
+    }
+
     #[tokio::test]
     async fn test_cache_clear_expired() {
         let cache = MetadataCache::new();
@@ -14,6 +14,8 @@ use mcp_rust_sdk::{
     transport::{Message, Transport},
 };
 
+use super::batch::{Batches, Collected};
+
 /// Stdio transport compatible with Cursor's MCP client.
 ///
 /// Cursor speaks "plain" JSON-RPC objects over stdio (no outer `{ "type": ... }` tag).
@@ -24,10 +26,14 @@ use mcp_rust_sdk::{
 /// - SDK tagged messages: `{ "type": "request|response|notification", ... }`
 /// - Plain JSON-RPC objects: `{ "jsonrpc": "2.0", "method": "...", ... }`
 ///
+/// A line may also hold a JSON-RPC batch (an array); its responses are written
+/// back as one array line.
+///
 /// When sending messages, we always emit plain JSON-RPC objects (Cursor-friendly).
 pub struct CursorStdioTransport {
     stdout: Arc<Mutex<std::io::Stdout>>,
     receiver: broadcast::Receiver<Result<Message, Error>>,
+    batches: Arc<Batches>,
 }
 
 impl CursorStdioTransport {
@@ -36,11 +42,14 @@ impl CursorStdioTransport {
         let transport = Self {
             stdout: Arc::new(Mutex::new(std::io::stdout())),
             receiver,
+            batches: Arc::new(Batches::default()),
         };
 
         let stdin = tokio::io::stdin();
         let mut reader = TokioBufReader::new(stdin);
         let sender_clone = sender.clone();
+        let stdout = transport.stdout.clone();
+        let batches = transport.batches.clone();
         tokio::spawn(async move {
             let mut line = String::new();
             loop {
@@ -61,8 +70,24 @@ impl CursorStdioTransport {
                             continue;
                         }
 
-                        // Fallback: plain JSON-RPC object.
+                        // Fallback: plain JSON-RPC object, or a batch of them.
                         let msg = match serde_json::from_str::<serde_json::Value>(raw) {
+                            Ok(serde_json::Value::Array(items)) => {
+                                let (messages, reply) = batches.receive(items);
+                                if let Some(reply) = reply
+                                    && let Err(err) = write_line(&stdout, &reply.to_string())
+                                {
+                                    let _ = sender_clone.send(Err(err));
+                                    break;
+                                }
+                                if messages
+                                    .into_iter()
+                                    .any(|msg| sender_clone.send(Ok(msg)).is_err())
+                                {
+                                    break;
+                                }
+                                continue;
+                            }
                             Ok(v) => parse_jsonrpc_value(v),
                             Err(err) => Err(Error::Serialization(err.to_string())),
                         };
@@ -83,7 +108,15 @@ impl CursorStdioTransport {
     }
 }
 
-fn parse_jsonrpc_value(v: serde_json::Value) -> Result<Message, Error> {
+fn write_line(stdout: &Mutex<std::io::Stdout>, json: &str) -> Result<(), Error> {
+    let mut stdout = stdout
+        .lock()
+        .map_err(|_| Error::protocol(ErrorCode::InternalError, "Failed to acquire stdout lock"))?;
+    writeln!(stdout, "{json}").map_err(|e| Error::Io(e.to_string()))?;
+    stdout.flush().map_err(|e| Error::Io(e.to_string()))
+}
+
+pub(crate) fn parse_jsonrpc_value(v: serde_json::Value) -> Result<Message, Error> {
     let obj = v.as_object().ok_or_else(|| {
         Error::protocol(ErrorCode::InvalidRequest, "Expected JSON object message")
     })?;
@@ -112,19 +145,17 @@ fn parse_jsonrpc_value(v: serde_json::Value) -> Result<Message, Error> {
 #[async_trait]
 impl Transport for CursorStdioTransport {
     async fn send(&self, message: Message) -> Result<(), Error> {
-        let mut stdout = self.stdout.lock().map_err(|_| {
-            Error::protocol(ErrorCode::InternalError, "Failed to acquire stdout lock")
-        })?;
-
         // Emit plain JSON-RPC (Cursor-friendly).
         let json = match message {
             Message::Request(r) => serde_json::to_string(&r)?,
-            Message::Response(r) => serde_json::to_string(&r)?,
+            Message::Response(r) => match self.batches.collect(r) {
+                Collected::Single(r) => serde_json::to_string(&r)?,
+                Collected::Waiting => return Ok(()),
+                Collected::Complete(responses) => responses.to_string(),
+            },
             Message::Notification(n) => serde_json::to_string(&n)?,
         };
-        writeln!(stdout, "{json}").map_err(|e| Error::Io(e.to_string()))?;
-        stdout.flush().map_err(|e| Error::Io(e.to_string()))?;
-        Ok(())
+        write_line(&self.stdout, &json)
     }
 
     fn receive(&self) -> Pin<Box<dyn Stream<Item = Result<Message, Error>> + Send>> {
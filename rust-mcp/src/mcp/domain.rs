@@ -0,0 +1,795 @@
+//! Domain linting against model metadata.
+//!
+//! Odoo answers a bad domain (misspelled field, `ilike` against a many2one id,
+//! a dangling `|`) with a server traceback that is hard for an agent to act on.
+//! This module checks a domain before it is sent: prefix-operator arity, leaf
+//! shape, operator names, field paths (via `fields_get`) and operator/type
+//! compatibility. Every problem is reported with its position in the domain and,
+//! where possible, a suggestion.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use serde_json::{Map, Value, json};
+
+/// Operators accepted by Odoo's domain engine.
+const OPERATORS: &[&str] = &[
+    "=",
+    "!=",
+    "<>",
+    "<=",
+    "<",
+    ">",
+    ">=",
+    "=?",
+    "=like",
+    "=ilike",
+    "like",
+    "not like",
+    "ilike",
+    "not ilike",
+    "in",
+    "not in",
+    "child_of",
+    "parent_of",
+    "any",
+    "not any",
+];
+
+const LIKE_OPERATORS: &[&str] = &["=like", "=ilike", "like", "not like", "ilike", "not ilike"];
+const ORDER_OPERATORS: &[&str] = &["<", "<=", ">", ">="];
+const RELATIONAL_TYPES: &[&str] = &["many2one", "one2many", "many2many"];
+
+/// Source of `fields_get` metadata used to resolve field paths.
+#[async_trait]
+pub trait FieldsSource: Send + Sync {
+    /// Return the `fields_get` map for `model`, or `None` if it cannot be loaded.
+    /// When metadata is unavailable only structural checks are performed.
+    async fn fields(&self, model: &str) -> Option<Map<String, Value>>;
+}
+
+/// A single problem found in a domain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DomainIssue {
+    /// Position of the offending element in the top-level domain array.
+    pub index: Option<usize>,
+    pub message: String,
+    pub suggestion: Option<String>,
+}
+
+impl DomainIssue {
+    fn new(index: Option<usize>, message: impl Into<String>) -> Self {
+        Self {
+            index,
+            message: message.into(),
+            suggestion: None,
+        }
+    }
+
+    fn with_suggestion(mut self, suggestion: Option<String>) -> Self {
+        self.suggestion = suggestion;
+        self
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "index": self.index,
+            "message": self.message,
+            "suggestion": self.suggestion,
+        })
+    }
+}
+
+impl fmt::Display for DomainIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(i) = self.index {
+            write!(f, "domain[{i}]: ")?;
+        }
+        write!(f, "{}", self.message)?;
+        if let Some(s) = &self.suggestion {
+            write!(f, " (did you mean {s}?)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Render a list of issues as a single error message.
+pub fn format_issues(issues: &[DomainIssue]) -> String {
+    let lines: Vec<String> = issues.iter().map(|i| i.to_string()).collect();
+    format!("Invalid domain: {}", lines.join("; "))
+}
+
+/// Check the shape of a domain without any model metadata:
+/// leaf structure, operator names and `&`/`|`/`!` arity.
+pub fn check_structure(domain: &Value) -> Vec<DomainIssue> {
+    let Some(items) = domain.as_array() else {
+        return vec![DomainIssue::new(
+            None,
+            "domain must be an array of conditions",
+        )];
+    };
+
+    let mut issues = Vec::new();
+    // Pending prefix operators: (position, operator, operands still required).
+    let mut pending: Vec<(usize, &str, usize)> = Vec::new();
+
+    for (i, item) in items.iter().enumerate() {
+        match item {
+            Value::String(op) if matches!(op.as_str(), "&" | "|" | "!") => {
+                let arity = if op == "!" { 1 } else { 2 };
+                pending.push((i, op.as_str(), arity));
+                continue;
+            }
+            Value::String(other) => {
+                issues.push(
+                    DomainIssue::new(
+                        Some(i),
+                        format!("'{other}' is not a domain operator; expected '&', '|' or '!'"),
+                    )
+                    .with_suggestion(closest_logical_operator(other)),
+                );
+            }
+            Value::Array(_) => issues.extend(check_leaf_shape(i, item)),
+            other => issues.push(DomainIssue::new(
+                Some(i),
+                format!("expected a [field, operator, value] condition, got {other}"),
+            )),
+        }
+
+        // Any non-operator element completes one operand.
+        while let Some(top) = pending.last_mut() {
+            top.2 -= 1;
+            if top.2 > 0 {
+                break;
+            }
+            pending.pop();
+        }
+    }
+
+    if let Some((i, op, missing)) = pending.first() {
+        let arity = if *op == "!" { 1 } else { 2 };
+        issues.push(DomainIssue::new(
+            Some(*i),
+            format!(
+                "operator '{op}' expects {arity} operand(s) but the domain ends {missing} short"
+            ),
+        ));
+    }
+
+    issues
+}
+
+fn check_leaf_shape(i: usize, item: &Value) -> Vec<DomainIssue> {
+    let Some(leaf) = item.as_array() else {
+        return vec![];
+    };
+    if leaf.len() != 3 {
+        return vec![DomainIssue::new(
+            Some(i),
+            format!(
+                "condition must have exactly 3 elements [field, operator, value], got {}",
+                leaf.len()
+            ),
+        )];
+    }
+    if is_constant_leaf(leaf) {
+        return vec![];
+    }
+
+    let mut issues = Vec::new();
+    if !leaf[0].is_string() {
+        issues.push(DomainIssue::new(
+            Some(i),
+            format!("field name must be a string, got {}", leaf[0]),
+        ));
+    }
+    match leaf[1].as_str() {
+        Some(op) if OPERATORS.contains(&op.to_ascii_lowercase().as_str()) => {}
+        Some(op) => issues.push(
+            DomainIssue::new(Some(i), format!("unknown operator '{op}'"))
+                .with_suggestion(closest_operator(op)),
+        ),
+        None => issues.push(DomainIssue::new(
+            Some(i),
+            format!("operator must be a string, got {}", leaf[1]),
+        )),
+    }
+    issues
+}
+
+/// Odoo's TRUE_LEAF `(1, '=', 1)` and FALSE_LEAF `(0, '=', 1)`.
+fn is_constant_leaf(leaf: &[Value]) -> bool {
+    matches!(leaf[0].as_i64(), Some(0 | 1)) && leaf[1] == "=" && leaf[2] == 1
+}
+
+/// Validate a domain against `model`, resolving field paths through `source`.
+/// Each model's fields are fetched from `source` at most once.
+///
+/// Returns every issue found; an empty vector means the domain looks valid.
+pub async fn validate_domain(
+    domain: &Value,
+    model: &str,
+    source: &dyn FieldsSource,
+) -> Vec<DomainIssue> {
+    let memo = Memo {
+        source,
+        fields: Mutex::new(HashMap::new()),
+    };
+    validate_with(domain, model, &memo).await
+}
+
+/// A [`FieldsSource`] remembering what it returned, so a domain with many
+/// leaves (or paths) on one model costs a single `fields_get`, whatever the
+/// metadata cache holds.
+struct Memo<'a> {
+    source: &'a dyn FieldsSource,
+    fields: Mutex<HashMap<String, Option<Map<String, Value>>>>,
+}
+
+#[async_trait]
+impl FieldsSource for Memo<'_> {
+    async fn fields(&self, model: &str) -> Option<Map<String, Value>> {
+        let known = self
+            .fields
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(model)
+            .cloned();
+        if let Some(fields) = known {
+            return fields;
+        }
+        let fields = self.source.fields(model).await;
+        self.fields
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(model.to_string(), fields.clone());
+        fields
+    }
+}
+
+fn validate_with<'a>(
+    domain: &'a Value,
+    model: &'a str,
+    source: &'a dyn FieldsSource,
+) -> BoxFuture<'a, Vec<DomainIssue>> {
+    Box::pin(async move {
+        let mut issues = check_structure(domain);
+        let Some(items) = domain.as_array() else {
+            return issues;
+        };
+
+        for (i, item) in items.iter().enumerate() {
+            let Some(leaf) = item.as_array() else {
+                continue;
+            };
+            if leaf.len() != 3 || is_constant_leaf(leaf) {
+                continue;
+            }
+            let (Some(path), Some(op)) = (leaf[0].as_str(), leaf[1].as_str()) else {
+                continue;
+            };
+            let op = op.to_ascii_lowercase();
+            if !OPERATORS.contains(&op.as_str()) {
+                continue;
+            }
+
+            let field = match resolve_path(path, model, source).await {
+                Ok(Some(field)) => field,
+                Ok(None) => continue,
+                Err(issue) => {
+                    issues.push(DomainIssue {
+                        index: Some(i),
+                        ..issue
+                    });
+                    continue;
+                }
+            };
+
+            if let Some(issue) = check_operator(path, &field, &op, &leaf[2]) {
+                issues.push(DomainIssue {
+                    index: Some(i),
+                    ..issue
+                });
+                continue;
+            }
+
+            // `any` / `not any` take a sub-domain on the related model.
+            if matches!(op.as_str(), "any" | "not any")
+                && let Some(relation) = field.relation.as_deref()
+                && leaf[2].is_array()
+            {
+                for sub in validate_with(&leaf[2], relation, source).await {
+                    issues.push(DomainIssue {
+                        index: Some(i),
+                        message: format!("in '{op}' sub-domain on '{relation}': {sub}"),
+                        suggestion: None,
+                    });
+                }
+            }
+        }
+
+        issues
+    })
+}
+
+/// Resolved metadata for the last segment of a field path.
+#[derive(Debug, Clone)]
+struct FieldInfo {
+    ttype: String,
+    relation: Option<String>,
+}
+
+/// Walk a dotted field path. `Ok(None)` means metadata was unavailable.
+async fn resolve_path(
+    path: &str,
+    model: &str,
+    source: &dyn FieldsSource,
+) -> Result<Option<FieldInfo>, DomainIssue> {
+    let segments: Vec<&str> = path.split('.').collect();
+    let mut current_model = model.to_string();
+
+    for (pos, segment) in segments.iter().enumerate() {
+        let Some(fields) = source.fields(&current_model).await else {
+            return Ok(None);
+        };
+
+        let info = match fields.get(*segment) {
+            Some(meta) => FieldInfo {
+                ttype: meta
+                    .get("type")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("unknown")
+                    .to_string(),
+                relation: meta
+                    .get("relation")
+                    .and_then(|v| v.as_str())
+                    .filter(|s| !s.is_empty())
+                    .map(|s| s.to_string()),
+            },
+            // `id` is searchable on every model even if fields_get omits it.
+            None if *segment == "id" => FieldInfo {
+                ttype: "integer".to_string(),
+                relation: None,
+            },
+            None => {
+                return Err(DomainIssue::new(
+                    None,
+                    format!("field '{segment}' does not exist on model '{current_model}'"),
+                )
+                .with_suggestion(
+                    closest_field(segment, fields.keys()).map(|s| quote_path(&segments, pos, &s)),
+                ));
+            }
+        };
+
+        if pos + 1 == segments.len() {
+            return Ok(Some(info));
+        }
+
+        match (
+            &info.relation,
+            RELATIONAL_TYPES.contains(&info.ttype.as_str()),
+        ) {
+            (Some(relation), true) => current_model = relation.clone(),
+            _ => {
+                return Err(DomainIssue::new(
+                    None,
+                    format!(
+                        "field '{segment}' on '{current_model}' is a {} field and cannot be traversed in '{path}'",
+                        info.ttype
+                    ),
+                ));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Rebuild a dotted path with segment `pos` replaced, for suggestions.
+fn quote_path(segments: &[&str], pos: usize, replacement: &str) -> String {
+    let mut parts: Vec<&str> = segments.to_vec();
+    parts[pos] = replacement;
+    format!("'{}'", parts.join("."))
+}
+
+/// Check that `op` and `value` make sense for a field of the given type.
+fn check_operator(path: &str, field: &FieldInfo, op: &str, value: &Value) -> Option<DomainIssue> {
+    let ttype = field.ttype.as_str();
+    let relational = RELATIONAL_TYPES.contains(&ttype);
+
+    if LIKE_OPERATORS.contains(&op) {
+        if relational && !value.is_string() && !value.is_null() && value != &Value::Bool(false) {
+            return Some(
+                DomainIssue::new(
+                    None,
+                    format!(
+                        "operator '{op}' on {ttype} field '{path}' matches the record name and expects text, got {value}"
+                    ),
+                )
+                .with_suggestion(Some(format!(
+                    "['{path}', '{}', {value}]",
+                    if value.is_array() { "in" } else { "=" }
+                ))),
+            );
+        }
+        // Odoo casts other columns to text for these, e.g. ('date', 'ilike', '2024-03').
+        if ttype == "binary" {
+            return Some(DomainIssue::new(
+                None,
+                format!("operator '{op}' cannot be used on {ttype} field '{path}'"),
+            ));
+        }
+    }
+
+    if ORDER_OPERATORS.contains(&op)
+        && matches!(ttype, "boolean" | "binary" | "one2many" | "many2many")
+    {
+        return Some(DomainIssue::new(
+            None,
+            format!("operator '{op}' cannot be used on {ttype} field '{path}'"),
+        ));
+    }
+
+    if matches!(op, "in" | "not in") && !value.is_array() {
+        return Some(
+            DomainIssue::new(
+                None,
+                format!("operator '{op}' on '{path}' expects a list value, got {value}"),
+            )
+            .with_suggestion(Some(format!("['{path}', '{op}', [{value}]]"))),
+        );
+    }
+
+    if matches!(op, "child_of" | "parent_of" | "any" | "not any") && !relational && path != "id" {
+        return Some(DomainIssue::new(
+            None,
+            format!("operator '{op}' requires a relational field, but '{path}' is {ttype}"),
+        ));
+    }
+
+    if matches!(op, "any" | "not any") && !value.is_array() {
+        return Some(DomainIssue::new(
+            None,
+            format!("operator '{op}' on '{path}' expects a sub-domain list, got {value}"),
+        ));
+    }
+
+    None
+}
+
+fn closest_operator(op: &str) -> Option<String> {
+    let lowered = op.trim().to_ascii_lowercase();
+    let alias = match lowered.as_str() {
+        "==" | "eq" | "is" => Some("="),
+        "ne" | "neq" | "is not" => Some("!="),
+        "contains" | "like%" => Some("ilike"),
+        "not_in" | "notin" => Some("not in"),
+        "not_like" => Some("not like"),
+        "not_ilike" => Some("not ilike"),
+        "gt" => Some(">"),
+        "gte" | "=>" => Some(">="),
+        "lt" => Some("<"),
+        "lte" | "=<" => Some("<="),
+        _ => None,
+    };
+    if let Some(a) = alias {
+        return Some(format!("'{a}'"));
+    }
+    closest(&lowered, OPERATORS.iter().copied()).map(|s| format!("'{s}'"))
+}
+
+fn closest_logical_operator(op: &str) -> Option<String> {
+    match op.trim().to_ascii_lowercase().as_str() {
+        "and" | "&&" => Some("'&'".to_string()),
+        "or" | "||" => Some("'|'".to_string()),
+        "not" => Some("'!'".to_string()),
+        _ => None,
+    }
+}
+
+fn closest_field<'a>(name: &str, candidates: impl Iterator<Item = &'a String>) -> Option<String> {
+    closest(name, candidates.map(|s| s.as_str()))
+}
+
+/// Pick the candidate with the smallest edit distance, if it is close enough.
+/// Prefix matches (`partner` -> `partner_id`) are always considered close.
+fn closest<'a>(name: &str, candidates: impl Iterator<Item = &'a str>) -> Option<String> {
+    let threshold = (name.chars().count() / 3).max(2);
+    candidates
+        .filter_map(|c| {
+            let d = levenshtein(name, c);
+            let prefix = !name.is_empty() && (c.starts_with(name) || name.starts_with(c));
+            (d <= threshold || (prefix && d <= 4)).then_some((d, c))
+        })
+        .min_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(b.1)))
+        .map(|(_, c)| c.to_string())
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b_chars: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b_chars.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1; b_chars.len() + 1];
+        for (j, cb) in b_chars.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        prev = cur;
+    }
+    prev[b_chars.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct StaticFields(HashMap<String, Map<String, Value>>);
+
+    #[async_trait]
+    impl FieldsSource for StaticFields {
+        async fn fields(&self, model: &str) -> Option<Map<String, Value>> {
+            self.0.get(model).cloned()
+        }
+    }
+
+    fn source() -> StaticFields {
+        let mut models = HashMap::new();
+        let sale = json!({
+            "name": {"type": "char"},
+            "state": {"type": "selection"},
+            "amount_total": {"type": "monetary"},
+            "partner_id": {"type": "many2one", "relation": "res.partner"},
+            "order_line": {"type": "one2many", "relation": "sale.order.line"},
+            "is_expired": {"type": "boolean"},
+            "date_order": {"type": "datetime"},
+            "signature": {"type": "binary"}
+        });
+        let partner = json!({
+            "name": {"type": "char"},
+            "country_id": {"type": "many2one", "relation": "res.country"},
+            "email": {"type": "char"}
+        });
+        let country = json!({ "code": {"type": "char"} });
+        models.insert("sale.order".to_string(), sale.as_object().unwrap().clone());
+        models.insert(
+            "res.partner".to_string(),
+            partner.as_object().unwrap().clone(),
+        );
+        models.insert(
+            "res.country".to_string(),
+            country.as_object().unwrap().clone(),
+        );
+        StaticFields(models)
+    }
+
+    #[test]
+    fn test_structure_valid_prefix_domain() {
+        let d = json!(["|", ["state", "=", "draft"], ["state", "=", "sent"]]);
+        assert!(check_structure(&d).is_empty());
+    }
+
+    #[test]
+    fn test_structure_implicit_and() {
+        let d = json!([["state", "=", "draft"], ["name", "ilike", "S0"]]);
+        assert!(check_structure(&d).is_empty());
+    }
+
+    #[test]
+    fn test_structure_missing_operand() {
+        let d = json!(["|", ["state", "=", "draft"]]);
+        let issues = check_structure(&d);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].index, Some(0));
+        assert!(issues[0].message.contains("'|'"));
+    }
+
+    #[test]
+    fn test_structure_nested_operators() {
+        let d = json!(["!", "&", ["a", "=", 1], ["b", "=", 2]]);
+        assert!(check_structure(&d).is_empty());
+        let d = json!(["&", "!", ["a", "=", 1]]);
+        assert_eq!(check_structure(&d).len(), 1);
+    }
+
+    #[test]
+    fn test_structure_unknown_operator_suggestion() {
+        let d = json!([["state", "==", "draft"]]);
+        let issues = check_structure(&d);
+        assert_eq!(issues[0].suggestion.as_deref(), Some("'='"));
+    }
+
+    #[test]
+    fn test_structure_bad_logical_operator() {
+        let d = json!(["or", ["a", "=", 1], ["b", "=", 2]]);
+        let issues = check_structure(&d);
+        assert_eq!(issues[0].suggestion.as_deref(), Some("'|'"));
+    }
+
+    #[test]
+    fn test_structure_constant_leaves() {
+        assert!(check_structure(&json!([[1, "=", 1]])).is_empty());
+        assert!(check_structure(&json!([[0, "=", 1]])).is_empty());
+    }
+
+    #[test]
+    fn test_structure_not_array() {
+        assert_eq!(check_structure(&json!({"state": "draft"})).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_field_suggests_closest() {
+        let d = json!([["parnter_id", "=", 5]]);
+        let issues = validate_domain(&d, "sale.order", &source()).await;
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].suggestion.as_deref(), Some("'partner_id'"));
+        assert!(issues[0].to_string().contains("did you mean 'partner_id'?"));
+    }
+
+    #[tokio::test]
+    async fn test_prefix_field_suggestion() {
+        let d = json!([["partner", "=", 5]]);
+        let issues = validate_domain(&d, "sale.order", &source()).await;
+        assert_eq!(issues[0].suggestion.as_deref(), Some("'partner_id'"));
+    }
+
+    #[tokio::test]
+    async fn test_dotted_path_resolves() {
+        let d = json!([["partner_id.country_id.code", "=", "BE"]]);
+        assert!(
+            validate_domain(&d, "sale.order", &source())
+                .await
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dotted_path_unknown_leaf() {
+        let d = json!([["partner_id.country_id.cod", "=", "BE"]]);
+        let issues = validate_domain(&d, "sale.order", &source()).await;
+        assert_eq!(
+            issues[0].suggestion.as_deref(),
+            Some("'partner_id.country_id.code'")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_traverse_non_relational() {
+        let d = json!([["name.foo", "=", "x"]]);
+        let issues = validate_domain(&d, "sale.order", &source()).await;
+        assert!(issues[0].message.contains("cannot be traversed"));
+    }
+
+    #[tokio::test]
+    async fn test_ilike_on_many2one_id() {
+        let d = json!([["partner_id", "ilike", 7]]);
+        let issues = validate_domain(&d, "sale.order", &source()).await;
+        assert_eq!(issues.len(), 1);
+        assert_eq!(
+            issues[0].suggestion.as_deref(),
+            Some("['partner_id', '=', 7]")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ilike_on_many2one_text_ok() {
+        let d = json!([["partner_id", "ilike", "Azure"]]);
+        assert!(
+            validate_domain(&d, "sale.order", &source())
+                .await
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_like_on_monetary_and_date_accepted() {
+        let d = json!([
+            ["amount_total", "like", "100"],
+            ["date_order", "ilike", "2024-03"]
+        ]);
+        assert!(
+            validate_domain(&d, "sale.order", &source())
+                .await
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_like_on_binary_rejected() {
+        let d = json!([["signature", "ilike", "abc"]]);
+        assert_eq!(validate_domain(&d, "sale.order", &source()).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_order_on_boolean_rejected() {
+        let d = json!([["is_expired", ">", true]]);
+        assert_eq!(validate_domain(&d, "sale.order", &source()).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_in_requires_list() {
+        let d = json!([["state", "in", "draft"]]);
+        let issues = validate_domain(&d, "sale.order", &source()).await;
+        assert!(issues[0].message.contains("list"));
+    }
+
+    #[tokio::test]
+    async fn test_any_subdomain_validated() {
+        let d = json!([["partner_id", "any", [["emial", "=", "a@b.c"]]]]);
+        let issues = validate_domain(&d, "sale.order", &source()).await;
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].message.contains("emial"));
+    }
+
+    #[tokio::test]
+    async fn test_missing_metadata_skips_field_checks() {
+        let d = json!([["whatever", "=", 1]]);
+        assert!(
+            validate_domain(&d, "unknown.model", &source())
+                .await
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_id_always_known() {
+        let d = json!([["id", "in", [1, 2]]]);
+        assert!(
+            validate_domain(&d, "sale.order", &source())
+                .await
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fields_fetched_once_per_model() {
+        struct Counting(StaticFields, AtomicUsize);
+
+        #[async_trait]
+        impl FieldsSource for Counting {
+            async fn fields(&self, model: &str) -> Option<Map<String, Value>> {
+                self.1.fetch_add(1, Ordering::SeqCst);
+                self.0.fields(model).await
+            }
+        }
+
+        let counting = Counting(source(), AtomicUsize::new(0));
+        let d = json!([
+            ["name", "ilike", "x"],
+            ["state", "=", "sale"],
+            ["partner_id.name", "ilike", "acme"],
+            ["partner_id.country_id.code", "=", "BE"],
+            ["partner_id", "any", [["email", "!=", false]]]
+        ]);
+        assert!(
+            validate_domain(&d, "sale.order", &counting)
+                .await
+                .is_empty()
+        );
+        // sale.order, res.partner and res.country.
+        assert_eq!(counting.1.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_format_issues() {
+        let issues = vec![
+            DomainIssue::new(Some(0), "first"),
+            DomainIssue::new(None, "second").with_suggestion(Some("'x'".to_string())),
+        ];
+        assert_eq!(
+            format_issues(&issues),
+            "Invalid domain: domain[0]: first; second (did you mean 'x'?)"
+        );
+    }
+
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("", "abc"), 3);
+        assert_eq!(levenshtein("same", "same"), 0);
+    }
+}
@@ -0,0 +1,692 @@
+//! Parsing of domains written as strings.
+//!
+//! Two notations are accepted and converted to the JSON array form Odoo expects:
+//!
+//! - Odoo's Python literal notation, as found in views and server actions:
+//!   `[('state', '=', 'draft'), '|', ('amount_total', '>', 100), ('user_id', '=', False)]`
+//!   (JSON arrays encoded as a string are a subset of this and parse too).
+//! - A simplified infix notation: `state = draft and (amount_total > 100 or not active = true)`.
+//!   `and`/`or`/`not` may also be written `&`/`|`/`!` (or `&&`/`||`).
+//!
+//! Errors carry the character position of the offending input.
+
+use std::fmt;
+
+use serde_json::{Value, json};
+
+/// A parse failure with the 0-based character offset where it occurred.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DomainParseError {
+    pub position: usize,
+    pub message: String,
+}
+
+impl DomainParseError {
+    fn new(position: usize, message: impl Into<String>) -> Self {
+        Self {
+            position,
+            message: message.into(),
+        }
+    }
+
+    /// Render the error with the input and a caret under the bad character.
+    pub fn render(&self, input: &str) -> String {
+        let caret = " ".repeat(self.position);
+        format!("Invalid domain string: {self}\n  {input}\n  {caret}^")
+    }
+}
+
+impl fmt::Display for DomainParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+/// Parse a domain string in either Python literal or infix notation.
+///
+/// Input starting with `[` is treated as a Python/JSON literal list; anything
+/// else is parsed as infix. An empty string yields an empty domain.
+pub fn parse_domain(input: &str) -> Result<Value, DomainParseError> {
+    let mut scanner = Scanner::new(input);
+    scanner.skip_ws();
+    match scanner.peek() {
+        None => Ok(json!([])),
+        Some('[') => parse_literal_domain(scanner),
+        Some(_) => parse_infix_domain(scanner),
+    }
+}
+
+/// Character cursor shared by both notations.
+struct Scanner {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Scanner {
+    fn new(input: &str) -> Self {
+        Self {
+            chars: input.chars().collect(),
+            pos: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        Some(c)
+    }
+
+    fn skip_ws(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_ws();
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char, what: &str) -> Result<(), DomainParseError> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.unexpected(what))
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> DomainParseError {
+        match self.peek() {
+            Some(c) => DomainParseError::new(self.pos, format!("expected {expected}, found '{c}'")),
+            None => {
+                DomainParseError::new(self.pos, format!("expected {expected}, found end of input"))
+            }
+        }
+    }
+
+    /// Read a single- or double-quoted string starting at the current quote.
+    fn read_quoted(&mut self) -> Result<String, DomainParseError> {
+        let start = self.pos;
+        let quote = self.bump().unwrap_or('\'');
+        let mut out = String::new();
+        loop {
+            match self.bump() {
+                None => return Err(DomainParseError::new(start, "unterminated string")),
+                Some(c) if c == quote => return Ok(out),
+                Some('\\') => match self.bump() {
+                    Some('n') => out.push('\n'),
+                    Some('t') => out.push('\t'),
+                    Some('r') => out.push('\r'),
+                    Some(c) => out.push(c),
+                    None => return Err(DomainParseError::new(start, "unterminated string")),
+                },
+                Some(c) => out.push(c),
+            }
+        }
+    }
+
+    /// Read a run of characters that are not whitespace or in `stop`.
+    fn read_word(&mut self, stop: &str) -> String {
+        let mut out = String::new();
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() || stop.contains(c) {
+                break;
+            }
+            out.push(c);
+            self.pos += 1;
+        }
+        out
+    }
+}
+
+/// Interpret a bare word as a number, boolean or null; `None` if it is plain text.
+fn scalar_from_word(word: &str) -> Option<Value> {
+    match word {
+        "True" | "true" => return Some(Value::Bool(true)),
+        "False" | "false" => return Some(Value::Bool(false)),
+        "None" | "null" => return Some(Value::Null),
+        _ => {}
+    }
+    let numeric = word.chars().any(|c| c.is_ascii_digit())
+        && word
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E' | '_'));
+    if !numeric {
+        return None;
+    }
+    let cleaned = word.replace('_', "");
+    if let Ok(n) = cleaned.parse::<i64>() {
+        return Some(json!(n));
+    }
+    cleaned.parse::<f64>().ok().map(|f| json!(f))
+}
+
+// ---------------------------------------------------------------------------
+// Python literal notation
+// ---------------------------------------------------------------------------
+
+const LITERAL_STOP: &str = "[](),'\"";
+
+fn parse_literal_domain(mut s: Scanner) -> Result<Value, DomainParseError> {
+    let value = parse_literal(&mut s)?;
+    s.skip_ws();
+    if s.peek().is_some() {
+        return Err(s.unexpected("end of domain"));
+    }
+    Ok(value)
+}
+
+fn parse_literal(s: &mut Scanner) -> Result<Value, DomainParseError> {
+    s.skip_ws();
+    match s.peek() {
+        Some('[') => {
+            s.bump();
+            parse_literal_items(s, ']').map(|(items, _)| Value::Array(items))
+        }
+        Some('(') => {
+            s.bump();
+            let (items, had_comma) = parse_literal_items(s, ')')?;
+            // `('x')` is just a parenthesised value in Python; `('x',)` is a tuple.
+            if items.len() == 1 && !had_comma {
+                Ok(items.into_iter().next().unwrap_or(Value::Null))
+            } else {
+                Ok(Value::Array(items))
+            }
+        }
+        Some('\'' | '"') => s.read_quoted().map(Value::String),
+        Some(_) => {
+            let start = s.pos;
+            let word = s.read_word(LITERAL_STOP);
+            if word.is_empty() {
+                return Err(s.unexpected("a value"));
+            }
+            scalar_from_word(&word).ok_or_else(|| {
+                DomainParseError::new(
+                    start,
+                    format!("unsupported expression '{word}'; only literal values are allowed"),
+                )
+            })
+        }
+        None => Err(s.unexpected("a value")),
+    }
+}
+
+/// Parse comma-separated items up to `close`; also reports whether any comma was seen.
+fn parse_literal_items(
+    s: &mut Scanner,
+    close: char,
+) -> Result<(Vec<Value>, bool), DomainParseError> {
+    let mut items = Vec::new();
+    let mut had_comma = false;
+    loop {
+        if s.eat(close) {
+            return Ok((items, had_comma));
+        }
+        items.push(parse_literal(s)?);
+        if s.eat(',') {
+            had_comma = true;
+            continue;
+        }
+        s.expect(close, &format!("',' or '{close}'"))?;
+        return Ok((items, had_comma));
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Infix notation
+// ---------------------------------------------------------------------------
+
+const INFIX_STOP: &str = "()[],'\"=!<>&|";
+
+/// Parsed infix expression, converted to prefix form at the end.
+enum Expr {
+    Leaf(Value),
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+    Not(Box<Expr>),
+}
+
+impl Expr {
+    fn push_prefix(self, out: &mut Vec<Value>) {
+        match self {
+            Expr::Leaf(leaf) => out.push(leaf),
+            Expr::And(children) => push_nary("&", children, out),
+            Expr::Or(children) => push_nary("|", children, out),
+            Expr::Not(child) => {
+                out.push(json!("!"));
+                child.push_prefix(out);
+            }
+        }
+    }
+}
+
+fn push_nary(op: &str, children: Vec<Expr>, out: &mut Vec<Value>) {
+    for _ in 1..children.len() {
+        out.push(json!(op));
+    }
+    for child in children {
+        child.push_prefix(out);
+    }
+}
+
+fn parse_infix_domain(mut s: Scanner) -> Result<Value, DomainParseError> {
+    let expr = parse_or(&mut s)?;
+    s.skip_ws();
+    if s.peek().is_some() {
+        return Err(s.unexpected("'and', 'or' or end of domain"));
+    }
+
+    let mut out = Vec::new();
+    match expr {
+        // Top-level conjunction relies on Odoo's implicit '&'.
+        Expr::And(children) => children.into_iter().for_each(|c| c.push_prefix(&mut out)),
+        other => other.push_prefix(&mut out),
+    }
+    Ok(Value::Array(out))
+}
+
+/// Consume a keyword (`and`) or symbol (`&`, `&&`) if it is next.
+fn eat_connective(s: &mut Scanner, word: &str, symbol: char) -> bool {
+    s.skip_ws();
+    if s.peek() == Some(symbol) {
+        s.bump();
+        if s.peek() == Some(symbol) {
+            s.bump();
+        }
+        return true;
+    }
+    eat_keyword(s, word)
+}
+
+/// Consume `word` if it is next and followed by a word boundary.
+fn eat_keyword(s: &mut Scanner, word: &str) -> bool {
+    s.skip_ws();
+    let len = word.chars().count();
+    let matches = word
+        .chars()
+        .enumerate()
+        .all(|(i, c)| s.peek_at(i).is_some_and(|p| p.eq_ignore_ascii_case(&c)));
+    let boundary = s
+        .peek_at(len)
+        .is_none_or(|c| c.is_whitespace() || INFIX_STOP.contains(c));
+    if matches && boundary {
+        s.pos += len;
+        true
+    } else {
+        false
+    }
+}
+
+fn parse_or(s: &mut Scanner) -> Result<Expr, DomainParseError> {
+    let mut children = vec![parse_and(s)?];
+    while eat_connective(s, "or", '|') {
+        children.push(parse_and(s)?);
+    }
+    Ok(if children.len() == 1 {
+        children.remove(0)
+    } else {
+        Expr::Or(children)
+    })
+}
+
+fn parse_and(s: &mut Scanner) -> Result<Expr, DomainParseError> {
+    let mut children = vec![parse_not(s)?];
+    while eat_connective(s, "and", '&') {
+        children.push(parse_not(s)?);
+    }
+    Ok(if children.len() == 1 {
+        children.remove(0)
+    } else {
+        Expr::And(children)
+    })
+}
+
+fn parse_not(s: &mut Scanner) -> Result<Expr, DomainParseError> {
+    s.skip_ws();
+    // `!` alone negates; `!=` is a comparison operator and never starts a term.
+    if s.peek() == Some('!') && s.peek_at(1) != Some('=') {
+        s.bump();
+        return Ok(Expr::Not(Box::new(parse_not(s)?)));
+    }
+    if eat_keyword(s, "not") {
+        return Ok(Expr::Not(Box::new(parse_not(s)?)));
+    }
+    parse_primary(s)
+}
+
+fn parse_primary(s: &mut Scanner) -> Result<Expr, DomainParseError> {
+    if s.eat('(') {
+        let inner = parse_or(s)?;
+        s.expect(')', "')'")?;
+        return Ok(inner);
+    }
+    parse_condition(s)
+}
+
+fn parse_condition(s: &mut Scanner) -> Result<Expr, DomainParseError> {
+    s.skip_ws();
+    let start = s.pos;
+    let field = s.read_word(INFIX_STOP);
+    if field.is_empty() {
+        return Err(s.unexpected("a field name"));
+    }
+    let valid_field = field
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && field
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.');
+    if !valid_field {
+        return Err(DomainParseError::new(
+            start,
+            format!("'{field}' is not a valid field name"),
+        ));
+    }
+
+    let operator = parse_operator(s, &field)?;
+    let value = parse_infix_value(s, &operator)?;
+    Ok(Expr::Leaf(json!([field, operator, value])))
+}
+
+fn parse_operator(s: &mut Scanner, field: &str) -> Result<String, DomainParseError> {
+    s.skip_ws();
+    let symbol = match (s.peek(), s.peek_at(1)) {
+        (Some('='), Some('=')) => Some(("=", 2)),
+        (Some('='), Some('?')) => Some(("=?", 2)),
+        (Some('!'), Some('=')) => Some(("!=", 2)),
+        (Some('<'), Some('>')) => Some(("<>", 2)),
+        (Some('<'), Some('=')) => Some(("<=", 2)),
+        (Some('>'), Some('=')) => Some((">=", 2)),
+        (Some('<'), _) => Some(("<", 1)),
+        (Some('>'), _) => Some((">", 1)),
+        _ => None,
+    };
+    if let Some((op, len)) = symbol {
+        s.pos += len;
+        return Ok(op.to_string());
+    }
+    if s.peek() == Some('=') {
+        s.bump();
+        if eat_keyword_glued(s, "ilike") {
+            return Ok("=ilike".to_string());
+        }
+        if eat_keyword_glued(s, "like") {
+            return Ok("=like".to_string());
+        }
+        return Ok("=".to_string());
+    }
+
+    for op in ["like", "ilike", "in", "child_of", "parent_of", "any"] {
+        if eat_keyword(s, op) {
+            return Ok(op.to_string());
+        }
+    }
+    let not_pos = s.pos;
+    if eat_keyword(s, "not") {
+        for op in ["like", "ilike", "in", "any"] {
+            if eat_keyword(s, op) {
+                return Ok(format!("not {op}"));
+            }
+        }
+        s.pos = not_pos;
+    }
+
+    Err(s.unexpected(&format!("an operator after field '{field}'")))
+}
+
+/// Like [`eat_keyword`] but without skipping whitespace first (for `=like`).
+fn eat_keyword_glued(s: &mut Scanner, word: &str) -> bool {
+    if s.peek().is_some_and(char::is_whitespace) {
+        return false;
+    }
+    eat_keyword(s, word)
+}
+
+fn parse_infix_value(s: &mut Scanner, operator: &str) -> Result<Value, DomainParseError> {
+    s.skip_ws();
+    match s.peek() {
+        Some('[') | Some('(') if matches!(operator, "any" | "not any") => {
+            Err(DomainParseError::new(
+                s.pos,
+                format!(
+                    "'{operator}' sub-domains are not supported in infix notation; use the JSON array form"
+                ),
+            ))
+        }
+        Some(open @ ('[' | '(')) => {
+            s.bump();
+            let close = if open == '[' { ']' } else { ')' };
+            let mut items = Vec::new();
+            loop {
+                if s.eat(close) {
+                    return Ok(Value::Array(items));
+                }
+                items.push(parse_infix_scalar(s)?);
+                if s.eat(',') {
+                    continue;
+                }
+                s.expect(close, &format!("',' or '{close}'"))?;
+                return Ok(Value::Array(items));
+            }
+        }
+        _ => parse_infix_scalar(s),
+    }
+}
+
+fn parse_infix_scalar(s: &mut Scanner) -> Result<Value, DomainParseError> {
+    s.skip_ws();
+    match s.peek() {
+        Some('\'' | '"') => s.read_quoted().map(Value::String),
+        _ => {
+            let word = s.read_word(INFIX_STOP);
+            if word.is_empty() {
+                return Err(s.unexpected("a value"));
+            }
+            Ok(scalar_from_word(&word).unwrap_or(Value::String(word)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_string() {
+        assert_eq!(parse_domain("   ").unwrap(), json!([]));
+        assert_eq!(parse_domain("[]").unwrap(), json!([]));
+    }
+
+    #[test]
+    fn test_python_literal() {
+        let d = parse_domain("[('state','=','draft'),('amount_total','>',100)]").unwrap();
+        assert_eq!(
+            d,
+            json!([["state", "=", "draft"], ["amount_total", ">", 100]])
+        );
+    }
+
+    #[test]
+    fn test_python_literal_operators_and_constants() {
+        let d = parse_domain(
+            "['|', ('user_id', '=', False), '!', ('active', '=', True), ('x', 'in', [1, 2.5, None])]",
+        )
+        .unwrap();
+        assert_eq!(
+            d,
+            json!([
+                "|",
+                ["user_id", "=", false],
+                "!",
+                ["active", "=", true],
+                ["x", "in", [1, 2.5, null]]
+            ])
+        );
+    }
+
+    #[test]
+    fn test_python_literal_trailing_comma_and_tuple() {
+        let d = parse_domain("[('id', 'in', (1,)), ]").unwrap();
+        assert_eq!(d, json!([["id", "in", [1]]]));
+    }
+
+    #[test]
+    fn test_python_literal_parenthesised_string() {
+        let d = parse_domain("[('name', 'ilike', ('a,b'))]").unwrap();
+        assert_eq!(d, json!([["name", "ilike", "a,b"]]));
+    }
+
+    #[test]
+    fn test_json_string() {
+        let d = parse_domain(r#"[["name", "ilike", "azure"], ["active", "=", true]]"#).unwrap();
+        assert_eq!(
+            d,
+            json!([["name", "ilike", "azure"], ["active", "=", true]])
+        );
+    }
+
+    #[test]
+    fn test_python_literal_rejects_expressions() {
+        let err = parse_domain("[('user_id', '=', uid)]").unwrap_err();
+        assert_eq!(err.position, 18);
+        assert!(err.message.contains("uid"));
+    }
+
+    #[test]
+    fn test_python_literal_bad_quotes() {
+        let err = parse_domain("[('state', '=, 'draft')]").unwrap_err();
+        assert_eq!(err.position, 16);
+        assert!(err.message.contains("expected ',' or ')'"));
+        let err = parse_domain("[('state', '=', 'draft)]").unwrap_err();
+        assert_eq!(err.position, 16);
+        assert!(err.message.contains("unterminated"));
+    }
+
+    #[test]
+    fn test_python_literal_missing_bracket() {
+        let err = parse_domain("[('state', '=', 'draft')").unwrap_err();
+        assert_eq!(err.position, 24);
+        assert!(err.message.contains("end of input"));
+    }
+
+    #[test]
+    fn test_infix_simple_and() {
+        let d = parse_domain("state = draft and amount_total > 100").unwrap();
+        assert_eq!(
+            d,
+            json!([["state", "=", "draft"], ["amount_total", ">", 100]])
+        );
+    }
+
+    #[test]
+    fn test_infix_or_and_precedence() {
+        let d = parse_domain("a = 1 or b = 2 and c = 3").unwrap();
+        assert_eq!(
+            d,
+            json!(["|", ["a", "=", 1], "&", ["b", "=", 2], ["c", "=", 3]])
+        );
+    }
+
+    #[test]
+    fn test_infix_parentheses_and_not() {
+        let d = parse_domain("(state = draft or state = sent) and not active = false").unwrap();
+        assert_eq!(
+            d,
+            json!([
+                "|",
+                ["state", "=", "draft"],
+                ["state", "=", "sent"],
+                "!",
+                ["active", "=", false]
+            ])
+        );
+    }
+
+    #[test]
+    fn test_infix_symbols() {
+        let d = parse_domain("a == 1 || !b != 'x y' && c <= 2.5").unwrap();
+        assert_eq!(
+            d,
+            json!([
+                "|",
+                ["a", "=", 1],
+                "&",
+                "!",
+                ["b", "!=", "x y"],
+                ["c", "<=", 2.5]
+            ])
+        );
+    }
+
+    #[test]
+    fn test_infix_word_operators() {
+        let d = parse_domain(
+            "state not in (cancel, done) and name ilike 'acme' and partner_id.country_id.code =ilike be",
+        )
+        .unwrap();
+        assert_eq!(
+            d,
+            json!([
+                ["state", "not in", ["cancel", "done"]],
+                ["name", "ilike", "acme"],
+                ["partner_id.country_id.code", "=ilike", "be"]
+            ])
+        );
+    }
+
+    #[test]
+    fn test_infix_dates_and_lists() {
+        let d = parse_domain("date_order >= 2024-01-01 and id in [1, 2, 3]").unwrap();
+        assert_eq!(
+            d,
+            json!([["date_order", ">=", "2024-01-01"], ["id", "in", [1, 2, 3]]])
+        );
+    }
+
+    #[test]
+    fn test_infix_missing_operator() {
+        let err = parse_domain("state draft").unwrap_err();
+        assert_eq!(err.position, 6);
+        assert!(err.message.contains("operator after field 'state'"));
+    }
+
+    #[test]
+    fn test_infix_missing_value() {
+        let err = parse_domain("state = ").unwrap_err();
+        assert_eq!(err.position, 8);
+    }
+
+    #[test]
+    fn test_infix_unbalanced_paren() {
+        let err = parse_domain("(state = draft").unwrap_err();
+        assert_eq!(err.position, 14);
+    }
+
+    #[test]
+    fn test_infix_trailing_garbage() {
+        let err = parse_domain("state = draft amount > 1").unwrap_err();
+        assert_eq!(err.position, 14);
+    }
+
+    #[test]
+    fn test_render_points_at_position() {
+        let input = "state draft";
+        let err = parse_domain(input).unwrap_err();
+        let rendered = err.render(input);
+        assert!(rendered.ends_with("\n  state draft\n        ^"));
+    }
+}
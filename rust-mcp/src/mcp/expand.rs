@@ -0,0 +1,598 @@
+//! Relational expansion for `search_read` / `read`.
+//!
+//! Many2one values normally come back as `[id, name]`, so following a relation
+//! costs one round trip per hop. An `expand` argument names the relations to
+//! follow, either as dotted paths (`partner_id.country_id.code`) or as a nested
+//! field specification (`{"partner_id": {"fields": {"email": {}}}}`).
+//!
+//! On Odoo 17+ the plan is sent as a `web_search_read` / `web_read`
+//! specification; on older versions the related records are fetched with one
+//! batched `read` per relation and stitched back into the parent records.
+//! Either way, an expanded relation is returned as an object (or a list of
+//! objects for x2many) carrying `id`, `display_name` and the requested fields.
+
+use std::collections::{BTreeMap, HashMap};
+
+use futures::future::BoxFuture;
+use serde_json::{Map, Value, json};
+
+use crate::mcp::domain::FieldsSource;
+use crate::odoo::types::{OdooError, OdooResult};
+use crate::odoo::unified_client::OdooClient;
+
+/// Default maximum number of relations followed from the root model.
+pub const DEFAULT_MAX_DEPTH: usize = 3;
+
+const RELATIONAL_TYPES: &[&str] = &["many2one", "one2many", "many2many"];
+
+/// Read the depth limit from `ODOO_EXPAND_MAX_DEPTH` (default 3).
+pub fn max_depth_from_env() -> usize {
+    std::env::var("ODOO_EXPAND_MAX_DEPTH")
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(DEFAULT_MAX_DEPTH)
+}
+
+/// Field names requested at each level, before metadata is attached.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FieldTree(BTreeMap<String, FieldTree>);
+
+impl FieldTree {
+    fn insert_path(&mut self, path: &str) -> Result<&mut FieldTree, String> {
+        let mut node = self;
+        for segment in path.split('.') {
+            let segment = segment.trim();
+            if segment.is_empty() {
+                return Err(format!("invalid expand path '{path}'"));
+            }
+            node = node.0.entry(segment.to_string()).or_default();
+        }
+        Ok(node)
+    }
+
+    /// Number of relations followed. Every root is a relation; below it only
+    /// fields with sub-fields are, so `partner_id.country_id.code` is 2.
+    pub fn depth(&self) -> usize {
+        fn hops(node: &FieldTree) -> usize {
+            match node.0.values().map(hops).max() {
+                Some(deepest) => 1 + deepest,
+                None => 0,
+            }
+        }
+        self.0.values().map(|c| hops(c).max(1)).max().unwrap_or(0)
+    }
+}
+
+/// Parse an `expand` argument into a [`FieldTree`].
+///
+/// Accepts a dotted path string, an array of paths, or an object whose values
+/// are `{}`/`null`/`true` (expand with defaults), `{"fields": ...}` (Odoo
+/// specification style), a nested object, or a path / array of paths relative
+/// to that field.
+pub fn parse_expand(value: &Value, max_depth: usize) -> Result<FieldTree, String> {
+    let mut tree = FieldTree::default();
+    merge_value(&mut tree, value)?;
+    if tree.0.is_empty() {
+        return Err("'expand' must name at least one relational field".to_string());
+    }
+    let depth = tree.depth();
+    if depth > max_depth {
+        return Err(format!(
+            "'expand' follows {depth} relations but the limit is {max_depth}"
+        ));
+    }
+    Ok(tree)
+}
+
+fn merge_value(tree: &mut FieldTree, value: &Value) -> Result<(), String> {
+    match value {
+        Value::String(path) => tree.insert_path(path).map(|_| ()),
+        Value::Array(items) => items.iter().try_for_each(|v| merge_value(tree, v)),
+        Value::Object(map) => {
+            for (key, sub) in map {
+                let node = tree.insert_path(key)?;
+                match sub {
+                    Value::Null | Value::Bool(true) => {}
+                    Value::Object(inner) => match inner.get("fields") {
+                        Some(fields) if inner.len() == 1 => merge_value(node, fields)?,
+                        _ => merge_value(node, sub)?,
+                    },
+                    Value::String(_) | Value::Array(_) => merge_value(node, sub)?,
+                    other => {
+                        return Err(format!("invalid expand value for '{key}': {other}"));
+                    }
+                }
+            }
+            Ok(())
+        }
+        other => Err(format!(
+            "'expand' must be a path, a list of paths or an object, got {other}"
+        )),
+    }
+}
+
+/// A field in the plan, with the metadata needed to build or stitch results.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpandNode {
+    pub name: String,
+    pub ttype: String,
+    pub relation: Option<String>,
+    /// Whether the relation is followed (rendered as object) rather than left as-is.
+    pub expanded: bool,
+    pub children: Vec<ExpandNode>,
+}
+
+/// Resolved expansion for one root model.
+#[derive(Debug, Clone)]
+pub struct ExpandPlan {
+    /// Every top-level field to return, expanded or not.
+    pub nodes: Vec<ExpandNode>,
+    requested_fields: Option<Vec<String>>,
+}
+
+impl ExpandPlan {
+    /// Resolve `expand` against model metadata.
+    ///
+    /// `fields` is the caller's field list; when absent every field of the model
+    /// is returned, as plain `search_read` would.
+    pub async fn build(
+        expand: &Value,
+        model: &str,
+        fields: Option<&[String]>,
+        source: &dyn FieldsSource,
+        max_depth: usize,
+    ) -> Result<Self, OdooError> {
+        let tree = parse_expand(expand, max_depth).map_err(OdooError::InvalidResponse)?;
+        let meta = source.fields(model).await.ok_or_else(|| {
+            OdooError::InvalidResponse(format!(
+                "Cannot expand: metadata for model '{model}' is unavailable"
+            ))
+        })?;
+
+        let mut nodes = resolve(&tree, model, source, true).await?;
+        let top_names: Vec<String> = match fields {
+            Some(f) => f.to_vec(),
+            None => meta.keys().cloned().collect(),
+        };
+        for name in top_names {
+            if nodes.iter().any(|n| n.name == name) {
+                continue;
+            }
+            nodes.push(leaf_node(&name, meta.get(&name)));
+        }
+
+        Ok(Self {
+            nodes,
+            requested_fields: fields.map(|f| f.to_vec()),
+        })
+    }
+
+    /// Field list for a plain `search_read`/`read`: the caller's fields plus
+    /// every expanded root, or `None` for all fields.
+    pub fn read_fields(&self) -> Option<Vec<String>> {
+        let mut fields = self.requested_fields.clone()?;
+        for node in self.nodes.iter().filter(|n| n.expanded) {
+            if !fields.contains(&node.name) {
+                fields.push(node.name.clone());
+            }
+        }
+        Some(fields)
+    }
+
+    /// `specification` argument for `web_search_read` / `web_read`.
+    pub fn specification(&self) -> Value {
+        Value::Object(spec_for(&self.nodes))
+    }
+
+    /// Bring `web_read` output in line with `read`: unexpanded many2one
+    /// values become `[id, name]` again.
+    pub fn normalize(&self, records: &mut Value) {
+        if let Some(items) = records.as_array_mut() {
+            for record in items {
+                normalize_record(&self.nodes, record);
+            }
+        }
+    }
+
+    /// Follow expanded relations with batched `read` calls (pre-17 path).
+    pub async fn expand_records(
+        &self,
+        client: &OdooClient,
+        records: &mut Value,
+        context: Option<Value>,
+    ) -> OdooResult<()> {
+        let Some(items) = records.as_array_mut() else {
+            return Ok(());
+        };
+        expand_level(client, &self.nodes, items, context).await
+    }
+}
+
+fn leaf_node(name: &str, meta: Option<&Value>) -> ExpandNode {
+    let (ttype, relation) = field_type(name, meta);
+    ExpandNode {
+        name: name.to_string(),
+        ttype,
+        relation,
+        expanded: false,
+        children: Vec::new(),
+    }
+}
+
+fn field_type(name: &str, meta: Option<&Value>) -> (String, Option<String>) {
+    match meta {
+        Some(m) => (
+            m.get("type")
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown")
+                .to_string(),
+            m.get("relation")
+                .and_then(|v| v.as_str())
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string()),
+        ),
+        None if name == "id" => ("integer".to_string(), None),
+        None => ("unknown".to_string(), None),
+    }
+}
+
+fn resolve<'a>(
+    tree: &'a FieldTree,
+    model: &'a str,
+    source: &'a dyn FieldsSource,
+    is_root: bool,
+) -> BoxFuture<'a, Result<Vec<ExpandNode>, OdooError>> {
+    Box::pin(async move {
+        let meta = source.fields(model).await.ok_or_else(|| {
+            OdooError::InvalidResponse(format!(
+                "Cannot expand: metadata for model '{model}' is unavailable"
+            ))
+        })?;
+
+        let mut nodes = Vec::new();
+        for (name, sub) in &tree.0 {
+            let field_meta = meta.get(name);
+            if field_meta.is_none() && name != "id" {
+                return Err(OdooError::InvalidResponse(format!(
+                    "Cannot expand '{name}': field does not exist on model '{model}'"
+                )));
+            }
+            let mut node = leaf_node(name, field_meta);
+            if is_root || !sub.0.is_empty() {
+                let relation = match (
+                    &node.relation,
+                    RELATIONAL_TYPES.contains(&node.ttype.as_str()),
+                ) {
+                    (Some(r), true) => r.clone(),
+                    _ => {
+                        return Err(OdooError::InvalidResponse(format!(
+                            "Cannot expand '{name}' on '{model}': it is a {} field, not a relation",
+                            node.ttype
+                        )));
+                    }
+                };
+                node.expanded = true;
+                node.children = resolve(sub, &relation, source, false).await?;
+            }
+            nodes.push(node);
+        }
+        Ok(nodes)
+    })
+}
+
+fn spec_for(nodes: &[ExpandNode]) -> Map<String, Value> {
+    let mut spec = Map::new();
+    for node in nodes {
+        let entry = if node.expanded {
+            let mut inner = spec_for(&node.children);
+            inner
+                .entry("display_name".to_string())
+                .or_insert_with(|| json!({}));
+            json!({ "fields": inner })
+        } else if node.ttype == "many2one" {
+            json!({ "fields": { "display_name": {} } })
+        } else {
+            json!({})
+        };
+        spec.insert(node.name.clone(), entry);
+    }
+    spec
+}
+
+fn normalize_record(nodes: &[ExpandNode], record: &mut Value) {
+    let Some(obj) = record.as_object_mut() else {
+        return;
+    };
+    for node in nodes {
+        let Some(value) = obj.get_mut(&node.name) else {
+            continue;
+        };
+        if node.expanded {
+            match value {
+                Value::Array(items) => items
+                    .iter_mut()
+                    .for_each(|i| normalize_record(&node.children, i)),
+                Value::Object(_) => normalize_record(&node.children, value),
+                _ => {}
+            }
+        } else if node.ttype == "many2one"
+            && let Some(o) = value.as_object()
+        {
+            let pair = json!([o.get("id"), o.get("display_name")]);
+            *value = pair;
+        }
+    }
+}
+
+fn expand_level<'a>(
+    client: &'a OdooClient,
+    nodes: &'a [ExpandNode],
+    records: &'a mut [Value],
+    context: Option<Value>,
+) -> BoxFuture<'a, OdooResult<()>> {
+    Box::pin(async move {
+        for node in nodes.iter().filter(|n| n.expanded) {
+            let Some(relation) = node.relation.as_deref() else {
+                continue;
+            };
+
+            let mut ids: Vec<i64> = Vec::new();
+            for record in records.iter() {
+                for id in related_ids(record.get(&node.name)) {
+                    if !ids.contains(&id) {
+                        ids.push(id);
+                    }
+                }
+            }
+
+            let mut by_id: HashMap<i64, Value> = HashMap::new();
+            if !ids.is_empty() {
+                let mut fields: Vec<String> =
+                    node.children.iter().map(|c| c.name.clone()).collect();
+                if !fields.iter().any(|f| f == "display_name") {
+                    fields.push("display_name".to_string());
+                }
+                let fetched = client
+                    .read(relation, ids, Some(fields), context.clone())
+                    .await?;
+                let mut rows = match fetched {
+                    Value::Array(rows) => rows,
+                    _ => Vec::new(),
+                };
+                expand_level(client, &node.children, &mut rows, context.clone()).await?;
+                for row in rows {
+                    if let Some(id) = row.get("id").and_then(|v| v.as_i64()) {
+                        by_id.insert(id, row);
+                    }
+                }
+            }
+
+            for record in records.iter_mut() {
+                if let Some(value) = record.get_mut(&node.name) {
+                    *value = stitch(node, value, &by_id);
+                }
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Ids referenced by a many2one (`[id, name]`) or x2many (`[id, ...]`) value.
+fn related_ids(value: Option<&Value>) -> Vec<i64> {
+    match value {
+        Some(Value::Array(items)) => match items.first() {
+            // many2one: [id, "name"]
+            Some(first) if items.len() == 2 && items[1].is_string() => {
+                first.as_i64().into_iter().collect()
+            }
+            _ => items.iter().filter_map(|v| v.as_i64()).collect(),
+        },
+        Some(v) => v.as_i64().into_iter().collect(),
+        None => Vec::new(),
+    }
+}
+
+fn stitch(node: &ExpandNode, value: &Value, by_id: &HashMap<i64, Value>) -> Value {
+    if node.ttype == "many2one" {
+        let Some(id) = related_ids(Some(value)).first().copied() else {
+            return value.clone();
+        };
+        // Records we could not read (access rules) keep what `read` gave us.
+        return by_id.get(&id).cloned().unwrap_or_else(
+            || json!({ "id": id, "display_name": value.get(1).cloned().unwrap_or(Value::Null) }),
+        );
+    }
+    match value {
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .filter_map(|v| v.as_i64())
+                .filter_map(|id| by_id.get(&id).cloned())
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+
+    struct StaticFields(HashMap<String, Map<String, Value>>);
+
+    #[async_trait]
+    impl FieldsSource for StaticFields {
+        async fn fields(&self, model: &str) -> Option<Map<String, Value>> {
+            self.0.get(model).cloned()
+        }
+    }
+
+    fn source() -> StaticFields {
+        let mut models = HashMap::new();
+        let order = json!({
+            "name": {"type": "char"},
+            "partner_id": {"type": "many2one", "relation": "res.partner"},
+            "user_id": {"type": "many2one", "relation": "res.users"},
+            "order_line": {"type": "one2many", "relation": "sale.order.line"}
+        });
+        let partner = json!({
+            "email": {"type": "char"},
+            "country_id": {"type": "many2one", "relation": "res.country"}
+        });
+        let country = json!({ "code": {"type": "char"} });
+        models.insert("sale.order".to_string(), order.as_object().unwrap().clone());
+        models.insert(
+            "res.partner".to_string(),
+            partner.as_object().unwrap().clone(),
+        );
+        models.insert(
+            "res.country".to_string(),
+            country.as_object().unwrap().clone(),
+        );
+        StaticFields(models)
+    }
+
+    #[test]
+    fn test_parse_dotted_paths() {
+        let tree = parse_expand(
+            &json!(["partner_id.country_id.code", "partner_id.email"]),
+            3,
+        )
+        .unwrap();
+        let partner = &tree.0["partner_id"];
+        assert!(partner.0.contains_key("email"));
+        assert!(partner.0["country_id"].0.contains_key("code"));
+        assert_eq!(tree.depth(), 2);
+    }
+
+    #[test]
+    fn test_parse_specification_object() {
+        let tree = parse_expand(
+            &json!({"partner_id": {"fields": {"country_id": {"fields": {"code": {}}}}}}),
+            3,
+        )
+        .unwrap();
+        assert_eq!(
+            tree,
+            parse_expand(&json!("partner_id.country_id.code"), 3).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_shorthand_object() {
+        let tree = parse_expand(&json!({"partner_id": ["email", "country_id.code"]}), 3).unwrap();
+        assert_eq!(tree.depth(), 2);
+    }
+
+    #[test]
+    fn test_parse_depth_limit() {
+        let err = parse_expand(&json!("a.b.c.d.e"), 3).unwrap_err();
+        assert!(err.contains("limit is 3"));
+        assert!(parse_expand(&json!("a.b.c.d"), 3).is_ok());
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_values() {
+        assert!(parse_expand(&json!(42), 3).is_err());
+        assert!(parse_expand(&json!([]), 3).is_err());
+        assert!(parse_expand(&json!("partner_id..email"), 3).is_err());
+        assert!(parse_expand(&json!({"partner_id": 1}), 3).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_plan_specification() {
+        let fields = vec!["name".to_string(), "user_id".to_string()];
+        let plan = ExpandPlan::build(
+            &json!(["partner_id.country_id.code"]),
+            "sale.order",
+            Some(&fields),
+            &source(),
+            3,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            plan.specification(),
+            json!({
+                "name": {},
+                "user_id": {"fields": {"display_name": {}}},
+                "partner_id": {"fields": {
+                    "display_name": {},
+                    "country_id": {"fields": {"code": {}, "display_name": {}}}
+                }}
+            })
+        );
+        assert_eq!(
+            plan.read_fields(),
+            Some(vec![
+                "name".to_string(),
+                "user_id".to_string(),
+                "partner_id".to_string()
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_plan_all_fields_when_none_requested() {
+        let plan = ExpandPlan::build(&json!("partner_id"), "sale.order", None, &source(), 3)
+            .await
+            .unwrap();
+        assert_eq!(plan.nodes.len(), 4);
+        assert_eq!(plan.read_fields(), None);
+    }
+
+    #[tokio::test]
+    async fn test_plan_rejects_non_relational() {
+        let err = ExpandPlan::build(&json!("name.foo"), "sale.order", None, &source(), 3)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not a relation"));
+    }
+
+    #[tokio::test]
+    async fn test_plan_rejects_unknown_field() {
+        let err = ExpandPlan::build(&json!("partner_id.emial"), "sale.order", None, &source(), 3)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("'emial'"));
+    }
+
+    #[tokio::test]
+    async fn test_normalize_web_read_output() {
+        let fields = vec!["user_id".to_string()];
+        let plan = ExpandPlan::build(
+            &json!("partner_id"),
+            "sale.order",
+            Some(&fields),
+            &source(),
+            3,
+        )
+        .await
+        .unwrap();
+        let mut records = json!([{
+            "id": 1,
+            "user_id": {"id": 2, "display_name": "Mitchell Admin"},
+            "partner_id": {"id": 7, "display_name": "Azure Interior"}
+        }, {
+            "id": 2,
+            "user_id": false,
+            "partner_id": false
+        }]);
+        plan.normalize(&mut records);
+        assert_eq!(records[0]["user_id"], json!([2, "Mitchell Admin"]));
+        assert_eq!(
+            records[0]["partner_id"],
+            json!({"id": 7, "display_name": "Azure Interior"})
+        );
+        assert_eq!(records[1]["user_id"], json!(false));
+    }
+
+    #[test]
+    fn test_related_ids() {
+        assert_eq!(related_ids(Some(&json!([7, "Azure"]))), vec![7]);
+        assert_eq!(related_ids(Some(&json!([1, 2, 3]))), vec![1, 2, 3]);
+        assert_eq!(related_ids(Some(&json!(false))), Vec::<i64>::new());
+        assert_eq!(related_ids(None), Vec::<i64>::new());
+    }
+}
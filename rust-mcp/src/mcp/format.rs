@@ -0,0 +1,401 @@
+//! Output formats for read-type tool results.
+//!
+//! Pretty-printed JSON is the default and the most token-expensive encoding.
+//! `json-compact`, `markdown-table` and `csv` trade structure for size; in all
+//! three, many2one `[id, name]` pairs inside records are rendered as the name
+//! and expanded relations as their `display_name`.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde_json::{Map, Value};
+
+/// Keys that hold the row list in read-type payloads, in lookup order.
+const ROW_KEYS: &[&str] = &["records", "groups", "results"];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    #[default]
+    Json,
+    JsonCompact,
+    MarkdownTable,
+    Csv,
+}
+
+impl OutputFormat {
+    pub const NAMES: &'static [&'static str] = &["json", "json-compact", "markdown-table", "csv"];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OutputFormat::Json => "json",
+            OutputFormat::JsonCompact => "json-compact",
+            OutputFormat::MarkdownTable => "markdown-table",
+            OutputFormat::Csv => "csv",
+        }
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "json" => Ok(OutputFormat::Json),
+            "json-compact" => Ok(OutputFormat::JsonCompact),
+            "markdown-table" => Ok(OutputFormat::MarkdownTable),
+            "csv" => Ok(OutputFormat::Csv),
+            other => Err(format!(
+                "Unknown format '{other}'. Expected one of: {}",
+                Self::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+/// Re-render a tool result produced by `ok_text` in `format`.
+///
+/// Results whose text is not a JSON object are returned unchanged.
+pub fn apply_format(mut result: Value, format: OutputFormat) -> Value {
+    if format == OutputFormat::Json {
+        return result;
+    }
+    let Some(text) = result.pointer("/content/0/text").and_then(|v| v.as_str()) else {
+        return result;
+    };
+    let Ok(payload) = serde_json::from_str::<Value>(text) else {
+        return result;
+    };
+    if !payload.is_object() {
+        return result;
+    }
+    let rendered = render(&payload, format);
+    if let Some(slot) = result.pointer_mut("/content/0/text") {
+        *slot = Value::String(rendered);
+    }
+    result
+}
+
+/// Render a payload object in `format`.
+pub fn render(payload: &Value, format: OutputFormat) -> String {
+    match format {
+        OutputFormat::Json => {
+            serde_json::to_string_pretty(payload).unwrap_or_else(|_| "{}".to_string())
+        }
+        OutputFormat::JsonCompact => serde_json::to_string(&compact_value(payload, false))
+            .unwrap_or_else(|_| "{}".to_string()),
+        OutputFormat::MarkdownTable => render_table(payload, Table::Markdown),
+        OutputFormat::Csv => render_table(payload, Table::Csv),
+    }
+}
+
+/// Collapse relational values inside objects; `in_record` is true for field values.
+fn compact_value(v: &Value, in_record: bool) -> Value {
+    match v {
+        Value::Object(obj) => Value::Object(
+            obj.iter()
+                .map(|(k, val)| (k.clone(), compact_value(val, true)))
+                .collect(),
+        ),
+        Value::Array(items) => match many2one_name(v) {
+            Some(name) if in_record => Value::String(name),
+            _ => Value::Array(items.iter().map(|i| compact_value(i, false)).collect()),
+        },
+        other => other.clone(),
+    }
+}
+
+/// Name of a many2one `[id, "name"]` pair.
+fn many2one_name(v: &Value) -> Option<String> {
+    match v.as_array()?.as_slice() {
+        [id, Value::String(name)] if id.is_i64() => Some(name.clone()),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Table {
+    Markdown,
+    Csv,
+}
+
+fn render_table(payload: &Value, table: Table) -> String {
+    let Some(obj) = payload.as_object() else {
+        return String::new();
+    };
+    let Some((rows_key, rows)) = ROW_KEYS
+        .iter()
+        .find_map(|k| obj.get(*k).and_then(|v| v.as_array()).map(|a| (*k, a)))
+    else {
+        // Not a row-shaped result; fall back to compact JSON.
+        return render(payload, OutputFormat::JsonCompact);
+    };
+
+    let (columns, cells) = tabulate(rows);
+    let mut out = match table {
+        Table::Markdown => markdown(&columns, &cells),
+        Table::Csv => csv(&columns, &cells),
+    };
+
+    // Everything besides the rows (count, truncated, ...) goes after the table.
+    let extras: Map<String, Value> = obj
+        .iter()
+        .filter(|(k, _)| k.as_str() != rows_key)
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+    for (k, v) in extras {
+        let value = match v {
+            Value::String(s) => s,
+            other => serde_json::to_string(&other).unwrap_or_default(),
+        };
+        match table {
+            Table::Markdown => out.push_str(&format!("\n{k}: {value}")),
+            Table::Csv => out.push_str(&format!("\n# {k}: {value}")),
+        }
+    }
+    out
+}
+
+/// Columns (`id` first, then in first-seen order) and cell text for each row.
+fn tabulate(rows: &[Value]) -> (Vec<String>, Vec<Vec<String>>) {
+    let mut columns: Vec<String> = Vec::new();
+    for row in rows {
+        match row {
+            Value::Object(obj) => {
+                for k in obj.keys() {
+                    if !columns.contains(k) {
+                        columns.push(k.clone());
+                    }
+                }
+            }
+            // name_search returns [id, name] pairs.
+            Value::Array(_) if columns.is_empty() => {
+                columns = vec!["id".to_string(), "name".to_string()];
+            }
+            _ => {}
+        }
+    }
+    if columns.is_empty() && !rows.is_empty() {
+        columns.push("value".to_string());
+    }
+    // Field order is not preserved through JSON maps; keep `id` up front.
+    if let Some(pos) = columns.iter().position(|c| c == "id") {
+        let id = columns.remove(pos);
+        columns.insert(0, id);
+    }
+
+    let cells = rows
+        .iter()
+        .map(|row| match row {
+            Value::Object(obj) => columns
+                .iter()
+                .map(|c| obj.get(c).map(cell).unwrap_or_default())
+                .collect(),
+            Value::Array(items) => items.iter().take(columns.len()).map(cell).collect(),
+            other => vec![cell(other)],
+        })
+        .collect();
+    (columns, cells)
+}
+
+fn cell(v: &Value) -> String {
+    match v {
+        Value::Null | Value::Bool(false) => String::new(),
+        Value::Bool(true) => "true".to_string(),
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        Value::Array(items) => {
+            if let Some(name) = many2one_name(v) {
+                return name;
+            }
+            items.iter().map(cell).collect::<Vec<_>>().join(", ")
+        }
+        Value::Object(obj) => match obj.get("display_name").and_then(|d| d.as_str()) {
+            Some(name) => name.to_string(),
+            None => serde_json::to_string(v).unwrap_or_default(),
+        },
+    }
+}
+
+fn markdown(columns: &[String], rows: &[Vec<String>]) -> String {
+    let escape = |s: &str| s.replace('|', "\\|").replace(['\r', '\n'], " ");
+    let mut out = String::new();
+    out.push_str(&format!(
+        "| {} |\n",
+        columns
+            .iter()
+            .map(|c| escape(c))
+            .collect::<Vec<_>>()
+            .join(" | ")
+    ));
+    out.push_str(&format!(
+        "|{}|\n",
+        columns
+            .iter()
+            .map(|_| " --- ")
+            .collect::<Vec<_>>()
+            .join("|")
+    ));
+    for row in rows {
+        out.push_str(&format!(
+            "| {} |\n",
+            row.iter()
+                .map(|c| escape(c))
+                .collect::<Vec<_>>()
+                .join(" | ")
+        ));
+    }
+    out
+}
+
+fn csv(columns: &[String], rows: &[Vec<String>]) -> String {
+    let escape = |s: &str| {
+        if s.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", s.replace('"', "\"\""))
+        } else {
+            s.to_string()
+        }
+    };
+    let mut out = String::new();
+    out.push_str(
+        &columns
+            .iter()
+            .map(|c| escape(c))
+            .collect::<Vec<_>>()
+            .join(","),
+    );
+    out.push('\n');
+    for row in rows {
+        out.push_str(&row.iter().map(|c| escape(c)).collect::<Vec<_>>().join(","));
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn wrap(payload: &Value) -> Value {
+        json!({
+            "content": [{
+                "type": "text",
+                "text": serde_json::to_string_pretty(payload).unwrap()
+            }]
+        })
+    }
+
+    fn records() -> Value {
+        json!({
+            "records": [
+                {"id": 1, "name": "S00001", "partner_id": [7, "Azure Interior"], "amount_total": 150.5},
+                {"id": 2, "name": "S00002", "partner_id": false, "amount_total": 20}
+            ],
+            "count": 2
+        })
+    }
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!("csv".parse::<OutputFormat>(), Ok(OutputFormat::Csv));
+        assert_eq!(
+            "Markdown-Table".parse::<OutputFormat>(),
+            Ok(OutputFormat::MarkdownTable)
+        );
+        let err = "xml".parse::<OutputFormat>().unwrap_err();
+        assert!(err.contains("json-compact"));
+    }
+
+    #[test]
+    fn test_json_untouched() {
+        let result = wrap(&records());
+        assert_eq!(apply_format(result.clone(), OutputFormat::Json), result);
+    }
+
+    #[test]
+    fn test_json_compact_collapses_many2one() {
+        let text = render(&records(), OutputFormat::JsonCompact);
+        assert!(!text.contains('\n'));
+        let v: Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(v["records"][0]["partner_id"], "Azure Interior");
+        assert_eq!(v["records"][1]["partner_id"], false);
+        assert_eq!(v["count"], 2);
+    }
+
+    #[test]
+    fn test_json_compact_keeps_name_search_pairs() {
+        let payload = json!({"results": [[1, "Azure"], [2, "Deco"]]});
+        let text = render(&payload, OutputFormat::JsonCompact);
+        assert_eq!(text, r#"{"results":[[1,"Azure"],[2,"Deco"]]}"#);
+    }
+
+    #[test]
+    fn test_markdown_table() {
+        let text = render(&records(), OutputFormat::MarkdownTable);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "| id | amount_total | name | partner_id |");
+        assert_eq!(lines[1], "| --- | --- | --- | --- |");
+        assert_eq!(lines[2], "| 1 | 150.5 | S00001 | Azure Interior |");
+        assert_eq!(lines[3], "| 2 | 20 | S00002 |  |");
+        assert_eq!(lines.last().unwrap(), &"count: 2");
+    }
+
+    #[test]
+    fn test_csv_escaping() {
+        let payload = json!({"records": [{"id": 1, "name": "Doe, \"JJ\"", "note": "a|b"}]});
+        let text = render(&payload, OutputFormat::Csv);
+        assert_eq!(text, "id,name,note\n1,\"Doe, \"\"JJ\"\"\",a|b\n");
+    }
+
+    #[test]
+    fn test_csv_name_search_pairs() {
+        let payload = json!({"results": [[1, "Azure"], [2, "Deco"]]});
+        assert_eq!(
+            render(&payload, OutputFormat::Csv),
+            "id,name\n1,Azure\n2,Deco\n"
+        );
+    }
+
+    #[test]
+    fn test_read_group_table() {
+        let payload = json!({"groups": [
+            {"state": "draft", "state_count": 3, "amount_total": 100.0},
+            {"state": "sale", "state_count": 5, "amount_total": 250.0}
+        ]});
+        let text = render(&payload, OutputFormat::Csv);
+        assert_eq!(
+            text,
+            "amount_total,state,state_count\n100.0,draft,3\n250.0,sale,5\n"
+        );
+    }
+
+    #[test]
+    fn test_expanded_relation_uses_display_name() {
+        let payload = json!({"records": [
+            {"id": 1, "partner_id": {"id": 7, "display_name": "Azure", "email": "a@b.c"}, "tag_ids": [1, 2]}
+        ]});
+        let text = render(&payload, OutputFormat::Csv);
+        assert_eq!(text, "id,partner_id,tag_ids\n1,Azure,\"1, 2\"\n");
+    }
+
+    #[test]
+    fn test_csv_extras_as_comments() {
+        let text = render(&records(), OutputFormat::Csv);
+        assert!(text.ends_with("\n# count: 2"));
+    }
+
+    #[test]
+    fn test_non_row_payload_falls_back_to_compact() {
+        let payload = json!({"id": 5, "success": true});
+        assert_eq!(
+            render(&payload, OutputFormat::MarkdownTable),
+            r#"{"id":5,"success":true}"#
+        );
+    }
+}
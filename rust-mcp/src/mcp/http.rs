@@ -5,6 +5,7 @@
 //! - POST /mcp: Send JSON-RPC messages, receive JSON or SSE stream response
 //! - GET /mcp: Open SSE stream for server-to-client notifications
 //! - DELETE /mcp: Explicitly terminate a session
+//! - GET /metrics: Prometheus metrics
 //! - Origin validation for security
 //! - Session management with resumability support
 //! - Protocol version header handling
@@ -12,11 +13,13 @@
 use std::collections::{HashMap, VecDeque};
 use std::convert::Infallible;
 use std::net::SocketAddr;
+use std::path::Path;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use axum::extract::{Query, State};
+use axum::http::header::CONTENT_TYPE;
 use axum::http::{HeaderMap, HeaderName, HeaderValue, StatusCode};
 use axum::response::IntoResponse;
 use axum::response::sse::{Event, Sse};
@@ -24,17 +27,25 @@ use axum::routing::{get, post};
 use axum::{Json, Router};
 use mcp_rust_sdk::error::{Error as McpError, ErrorCode};
 use mcp_rust_sdk::protocol::{RequestId, Response};
-use mcp_rust_sdk::server::ServerHandler;
 use serde::Deserialize;
 use serde_json::{Value, json};
-use tokio::sync::{Mutex, RwLock, broadcast};
-use tokio_stream::wrappers::{BroadcastStream, IntervalStream};
+use tokio::sync::{Mutex, RwLock, broadcast, oneshot};
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+use tokio_stream::wrappers::{BroadcastStream, IntervalStream, UnboundedReceiverStream};
 use tokio_stream::{StreamExt, iter};
 use tower_http::cors::CorsLayer;
-use tracing::{debug, info, warn};
+use tracing::{Instrument, debug, error, info, warn};
 use uuid::Uuid;
 
 use crate::mcp::McpOdooHandler;
+use crate::mcp::batch;
+use crate::mcp::oauth::{OAuthConfig, OAuthError};
+use crate::mcp::principal::{Principal, load_principals};
+use crate::mcp::progress;
+use crate::mcp::subscriptions::ResourceUpdate;
+use crate::metrics;
+use crate::odoo::breaker::BreakerState;
+use crate::odoo::config::UserCredentials;
 
 // Header names per MCP spec
 static MCP_SESSION_ID: HeaderName = HeaderName::from_static("mcp-session-id");
@@ -42,6 +53,10 @@ static MCP_PROTOCOL_VERSION: HeaderName = HeaderName::from_static("mcp-protocol-
 static AUTHORIZATION: HeaderName = HeaderName::from_static("authorization");
 static ORIGIN: HeaderName = HeaderName::from_static("origin");
 static LAST_EVENT_ID: HeaderName = HeaderName::from_static("last-event-id");
+static ODOO_API_KEY: HeaderName = HeaderName::from_static("x-odoo-api-key");
+static ODOO_LOGIN: HeaderName = HeaderName::from_static("x-odoo-login");
+static ODOO_PASSWORD: HeaderName = HeaderName::from_static("x-odoo-password");
+static X_ACCEL_BUFFERING: HeaderName = HeaderName::from_static("x-accel-buffering");
 
 /// Default protocol version for backwards compatibility
 const DEFAULT_PROTOCOL_VERSION: &str = "2025-03-26";
@@ -49,8 +64,8 @@ const DEFAULT_PROTOCOL_VERSION: &str = "2025-03-26";
 /// Current supported protocol version
 const CURRENT_PROTOCOL_VERSION: &str = "2025-11-05";
 
-/// Maximum number of events to buffer for resumability per session
-const MAX_EVENT_BUFFER_SIZE: usize = 100;
+/// Default number of events buffered per session for resumability
+const DEFAULT_EVENT_BUFFER_SIZE: usize = 100;
 
 /// SSE keepalive interval in seconds
 const SSE_KEEPALIVE_SECS: u64 = 15;
@@ -61,19 +76,59 @@ const SSE_RETRY_MS: u64 = 3000;
 /// Stored SSE event for resumability
 #[derive(Clone, Debug)]
 struct StoredEvent {
+    /// `<session>:<seq>`; empty for streams without a session to resume.
     id: String,
     data: Value,
 }
 
+impl StoredEvent {
+    fn unnumbered(data: Value) -> Self {
+        Self {
+            id: String::new(),
+            data,
+        }
+    }
+
+    fn seq(&self) -> Option<u64> {
+        event_seq(&self.id)
+    }
+}
+
+/// Sequence number of an event id (`<session>:<seq>`).
+fn event_seq(id: &str) -> Option<u64> {
+    id.rsplit_once(':')?.1.parse().ok()
+}
+
+/// SSE channels by session id (Streamable HTTP sessions and legacy SSE streams).
+type SseChannels = HashMap<String, broadcast::Sender<StoredEvent>>;
+
+/// What a (re)connecting SSE stream sends before live events.
+struct Resume {
+    receiver: broadcast::Receiver<StoredEvent>,
+    /// Id of the last event sent on the session, to prime reconnection.
+    last_id: String,
+    events: Vec<StoredEvent>,
+    /// The requested `Last-Event-ID` is no longer (or was never) buffered:
+    /// `events` holds what is left and earlier events are lost.
+    gap: bool,
+}
+
 /// Session state with enhanced tracking for Streamable HTTP
 #[derive(Clone)]
 struct SessionState {
     initialized: bool,
     protocol_version: String,
+    /// Sequence number of the last event sent.
     event_counter: Arc<AtomicU64>,
-    /// Circular buffer of recent events for resumability (placeholder for full implementation)
-    #[allow(dead_code)]
+    /// Circular buffer of recent events for resumability
     event_buffer: Arc<RwLock<VecDeque<StoredEvent>>>,
+    event_buffer_size: usize,
+    /// Odoo credentials the client gave on initialize, if any.
+    credentials: Option<UserCredentials>,
+    /// Principal that opened the session (`None` without named tokens or OAuth).
+    principal: Option<String>,
+    created_at: Instant,
+    last_active: Instant,
 }
 
 impl Default for SessionState {
@@ -82,7 +137,12 @@ impl Default for SessionState {
             initialized: false,
             protocol_version: DEFAULT_PROTOCOL_VERSION.to_string(),
             event_counter: Arc::new(AtomicU64::new(0)),
-            event_buffer: Arc::new(RwLock::new(VecDeque::with_capacity(MAX_EVENT_BUFFER_SIZE))),
+            event_buffer: Arc::new(RwLock::new(VecDeque::new())),
+            event_buffer_size: DEFAULT_EVENT_BUFFER_SIZE,
+            credentials: None,
+            principal: None,
+            created_at: Instant::now(),
+            last_active: Instant::now(),
         }
     }
 }
@@ -93,42 +153,183 @@ impl SessionState {
             initialized: true,
             protocol_version,
             event_counter: Arc::new(AtomicU64::new(0)),
-            event_buffer: Arc::new(RwLock::new(VecDeque::with_capacity(MAX_EVENT_BUFFER_SIZE))),
+            event_buffer: Arc::new(RwLock::new(VecDeque::new())),
+            event_buffer_size: DEFAULT_EVENT_BUFFER_SIZE,
+            credentials: None,
+            principal: None,
+            created_at: Instant::now(),
+            last_active: Instant::now(),
         }
     }
 
+    /// Whether the session outlived the idle or absolute TTL of `limits`.
+    fn is_expired(&self, limits: &SessionLimits, now: Instant) -> bool {
+        limits
+            .idle_ttl
+            .is_some_and(|ttl| now.duration_since(self.last_active) >= ttl)
+            || limits
+                .max_age
+                .is_some_and(|age| now.duration_since(self.created_at) >= age)
+    }
+
     /// Generate next event ID for this session
     fn next_event_id(&self, session_id: &str) -> String {
-        let counter = self.event_counter.fetch_add(1, Ordering::SeqCst);
+        let counter = self.event_counter.fetch_add(1, Ordering::SeqCst) + 1;
         format!("{}:{}", session_id, counter)
     }
 
-    /// Store an event for potential replay (placeholder for full resumability implementation)
-    #[allow(dead_code)]
-    async fn store_event(&self, event: StoredEvent) {
+    /// Number, store and send an event. Sending under the buffer lock keeps
+    /// [`resume`](Self::resume) from seeing an event both buffered and live.
+    async fn publish(&self, session_id: &str, tx: &broadcast::Sender<StoredEvent>, data: Value) {
         let mut buffer = self.event_buffer.write().await;
-        if buffer.len() >= MAX_EVENT_BUFFER_SIZE {
-            buffer.pop_front();
+        let event = StoredEvent {
+            id: self.next_event_id(session_id),
+            data,
+        };
+        if self.event_buffer_size > 0 {
+            while buffer.len() >= self.event_buffer_size {
+                buffer.pop_front();
+            }
+            buffer.push_back(event.clone());
         }
-        buffer.push_back(event);
+        let _ = tx.send(event);
     }
 
-    /// Get events after a given event ID for replay (placeholder for full resumability implementation)
-    #[allow(dead_code)]
-    async fn get_events_after(&self, last_event_id: &str) -> Vec<StoredEvent> {
+    /// Buffered events after a given event ID. `None` when the ID is unknown
+    /// or older events were already dropped, i.e. some are lost.
+    fn events_after(
+        &self,
+        buffer: &VecDeque<StoredEvent>,
+        last_event_id: &str,
+    ) -> Option<Vec<StoredEvent>> {
+        let last = event_seq(last_event_id)?;
+        let newest = buffer
+            .back()
+            .and_then(StoredEvent::seq)
+            .unwrap_or_else(|| self.event_counter.load(Ordering::SeqCst));
+        let oldest = buffer
+            .front()
+            .and_then(StoredEvent::seq)
+            .unwrap_or(newest + 1);
+        if last > newest || oldest > last + 1 {
+            return None;
+        }
+        Some(
+            buffer
+                .iter()
+                .filter(|e| e.seq().is_some_and(|seq| seq > last))
+                .cloned()
+                .collect(),
+        )
+    }
+
+    /// Subscribe to the session's events, with those after `last_event_id`
+    /// to replay first.
+    async fn resume(
+        &self,
+        session_id: &str,
+        tx: &broadcast::Sender<StoredEvent>,
+        last_event_id: Option<&str>,
+    ) -> Resume {
         let buffer = self.event_buffer.read().await;
-        let mut found = false;
-        let mut result = Vec::new();
-
-        for event in buffer.iter() {
-            if found {
-                result.push(event.clone());
-            } else if event.id == last_event_id {
-                found = true;
+        let receiver = tx.subscribe();
+        let last_id = format!(
+            "{}:{}",
+            session_id,
+            self.event_counter.load(Ordering::SeqCst)
+        );
+        let (events, gap) = match last_event_id {
+            None => (Vec::new(), false),
+            Some(id) => match self.events_after(&buffer, id) {
+                Some(events) => (events, false),
+                None => (buffer.iter().cloned().collect(), true),
+            },
+        };
+        Resume {
+            receiver,
+            last_id,
+            events,
+            gap,
+        }
+    }
+}
+
+/// Whether MCP clients may call Odoo with their own credentials
+/// (`MCP_ODOO_USER_CREDENTIALS`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UserCredentialsMode {
+    /// Always use the configured service user.
+    #[default]
+    Off,
+    /// Use the client's credentials when given, else the service user.
+    Optional,
+    /// Refuse sessions without client credentials.
+    Required,
+}
+
+/// Lifetime and count limits of Streamable HTTP sessions. `None` disables a limit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SessionLimits {
+    /// Time without requests or open SSE stream after which a session expires.
+    pub idle_ttl: Option<Duration>,
+    /// Time after creation at which a session expires, active or not.
+    pub max_age: Option<Duration>,
+    /// Sessions kept per principal; the least recently active one is closed
+    /// when a new session would exceed it.
+    pub max_per_principal: Option<usize>,
+    /// SSE events kept per session for replay after a reconnect (0 = none).
+    pub event_buffer_size: usize,
+}
+
+impl Default for SessionLimits {
+    fn default() -> Self {
+        Self {
+            idle_ttl: Some(Duration::from_secs(30 * 60)),
+            max_age: Some(Duration::from_secs(24 * 60 * 60)),
+            max_per_principal: Some(100),
+            event_buffer_size: DEFAULT_EVENT_BUFFER_SIZE,
+        }
+    }
+}
+
+impl SessionLimits {
+    /// `MCP_SESSION_IDLE_TTL_SECS`, `MCP_SESSION_MAX_AGE_SECS` and
+    /// `MCP_SESSIONS_PER_PRINCIPAL` (`0` disables a limit), and
+    /// `MCP_SSE_BUFFER_SIZE`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str| -> Option<Option<u64>> {
+            let raw = std::env::var(name).ok()?;
+            match raw.trim().parse::<u64>() {
+                Ok(0) => Some(None),
+                Ok(v) => Some(Some(v)),
+                Err(_) => {
+                    warn!("Invalid {name} value '{raw}'; using the default");
+                    None
+                }
             }
+        };
+        Self {
+            idle_ttl: var("MCP_SESSION_IDLE_TTL_SECS")
+                .map_or(defaults.idle_ttl, |v| v.map(Duration::from_secs)),
+            max_age: var("MCP_SESSION_MAX_AGE_SECS")
+                .map_or(defaults.max_age, |v| v.map(Duration::from_secs)),
+            max_per_principal: var("MCP_SESSIONS_PER_PRINCIPAL")
+                .map_or(defaults.max_per_principal, |v| v.map(|n| n as usize)),
+            event_buffer_size: var("MCP_SSE_BUFFER_SIZE")
+                .map_or(defaults.event_buffer_size, |v| v.unwrap_or(0) as usize),
         }
+    }
 
-        result
+    /// How often the sweeper looks for expired sessions.
+    fn sweep_interval(&self) -> Duration {
+        self.idle_ttl
+            .into_iter()
+            .chain(self.max_age)
+            .map(|ttl| ttl / 4)
+            .min()
+            .unwrap_or(Duration::from_secs(60))
+            .clamp(Duration::from_secs(1), Duration::from_secs(60))
     }
 }
 
@@ -137,6 +338,10 @@ impl SessionState {
 pub struct SecurityConfig {
     /// Allowed origins. None = allow all (default), Some([]) = localhost only
     pub allowed_origins: Option<Vec<String>>,
+    /// Per-user Odoo credentials passed by clients.
+    pub user_credentials: UserCredentialsMode,
+    /// Session expiry and per-principal session cap.
+    pub sessions: SessionLimits,
 }
 
 impl SecurityConfig {
@@ -160,7 +365,29 @@ impl SecurityConfig {
             debug!("MCP Origin validation disabled (set MCP_ALLOWED_ORIGINS to enable)");
         }
 
-        Self { allowed_origins }
+        let user_credentials = match std::env::var("MCP_ODOO_USER_CREDENTIALS")
+            .unwrap_or_default()
+            .trim()
+            .to_lowercase()
+            .as_str()
+        {
+            "" | "off" | "false" | "0" => UserCredentialsMode::Off,
+            "optional" | "on" | "true" | "1" => UserCredentialsMode::Optional,
+            "required" => UserCredentialsMode::Required,
+            other => {
+                warn!("Unknown MCP_ODOO_USER_CREDENTIALS value '{other}'; using the service user");
+                UserCredentialsMode::Off
+            }
+        };
+        if user_credentials != UserCredentialsMode::Off {
+            info!("Per-user Odoo credentials: {:?}", user_credentials);
+        }
+
+        Self {
+            allowed_origins,
+            user_credentials,
+            sessions: SessionLimits::from_env(),
+        }
     }
 }
 
@@ -171,6 +398,10 @@ pub struct AuthConfigData {
     pub bearer_token: Option<String>,
     /// Whether authentication is enabled (MCP_AUTH_ENABLED)
     pub enabled: bool,
+    /// Named principals from MCP_AUTH_TOKENS_FILE, each with its own token and scope.
+    pub principals: Vec<Principal>,
+    /// OAuth resource server validating JWT access tokens (MCP_OAUTH_*).
+    pub oauth: Option<Arc<OAuthConfig>>,
 }
 
 impl AuthConfigData {
@@ -185,9 +416,31 @@ impl AuthConfigData {
             .ok()
             .filter(|s| !s.is_empty());
 
+        // An unreadable tokens file admits none of its principals (fail closed).
+        let principals = match std::env::var("MCP_AUTH_TOKENS_FILE") {
+            Ok(path) if !path.trim().is_empty() => match load_principals(Path::new(path.trim())) {
+                Ok(principals) => principals,
+                Err(e) => {
+                    error!("{e}");
+                    Vec::new()
+                }
+            },
+            _ => Vec::new(),
+        };
+
+        let oauth = match OAuthConfig::from_env() {
+            Ok(oauth) => oauth.map(Arc::new),
+            Err(e) => {
+                error!("OAuth resource server disabled: {e}");
+                None
+            }
+        };
+
         Self {
             bearer_token,
             enabled,
+            principals,
+            oauth,
         }
     }
 
@@ -196,6 +449,8 @@ impl AuthConfigData {
         Self {
             bearer_token: None,
             enabled: false,
+            principals: Vec::new(),
+            oauth: None,
         }
     }
 }
@@ -211,9 +466,25 @@ impl AuthConfig {
     pub fn from_env() -> Self {
         let data = AuthConfigData::from_env();
         if data.enabled {
-            if data.bearer_token.is_some() {
+            if let Some(oauth) = &data.oauth {
+                info!(
+                    "MCP HTTP authentication enabled (OAuth resource server, issuer {})",
+                    oauth.issuer
+                );
+            }
+            if !data.principals.is_empty() {
+                info!(
+                    "MCP HTTP authentication enabled ({} named tokens{})",
+                    data.principals.len(),
+                    if data.bearer_token.is_some() {
+                        " + MCP_AUTH_TOKEN"
+                    } else {
+                        ""
+                    }
+                );
+            } else if data.bearer_token.is_some() {
                 info!("MCP HTTP authentication enabled (Bearer token)");
-            } else {
+            } else if data.oauth.is_none() {
                 warn!("MCP HTTP authentication enabled but MCP_AUTH_TOKEN not set!");
             }
         } else {
@@ -237,6 +508,32 @@ impl AuthConfig {
             inner: Arc::new(RwLock::new(AuthConfigData {
                 enabled,
                 bearer_token,
+                principals: Vec::new(),
+                oauth: None,
+            })),
+        }
+    }
+
+    /// Create auth config with named principals (for testing)
+    pub fn with_principals(principals: Vec<Principal>) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(AuthConfigData {
+                enabled: true,
+                bearer_token: None,
+                principals,
+                oauth: None,
+            })),
+        }
+    }
+
+    /// Create auth config accepting only OAuth access tokens (for testing)
+    pub fn with_oauth(oauth: OAuthConfig) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(AuthConfigData {
+                enabled: true,
+                bearer_token: None,
+                principals: Vec::new(),
+                oauth: Some(Arc::new(oauth)),
             })),
         }
     }
@@ -247,6 +544,7 @@ impl AuthConfig {
         let mut guard = self.inner.write().await;
         let old_enabled = guard.enabled;
         let old_token = guard.bearer_token.clone();
+        let old_principals = std::mem::take(&mut guard.principals);
         *guard = new_data.clone();
 
         // Log changes
@@ -260,6 +558,12 @@ impl AuthConfig {
         if old_token != new_data.bearer_token {
             info!("MCP_AUTH_TOKEN updated (hot-reload)");
         }
+        if old_principals != new_data.principals {
+            info!(
+                "MCP named tokens updated (hot-reload): {} principals",
+                new_data.principals.len()
+            );
+        }
     }
 
     /// Get current config snapshot
@@ -272,11 +576,19 @@ impl AuthConfig {
 struct AppState {
     handler: Arc<McpOdooHandler>,
     sessions: Arc<Mutex<HashMap<String, SessionState>>>,
-    sse_channels: Arc<Mutex<HashMap<String, broadcast::Sender<Value>>>>,
+    sse_channels: Arc<Mutex<SseChannels>>,
+    session_stats: Arc<SessionStats>,
     auth: AuthConfig,
     security: SecurityConfig,
 }
 
+/// Sessions closed by the server since start, reported by `/health`.
+#[derive(Default)]
+struct SessionStats {
+    expired: AtomicU64,
+    evicted: AtomicU64,
+}
+
 pub async fn serve(handler: Arc<McpOdooHandler>, listen: &str) -> anyhow::Result<()> {
     serve_with_config(
         handler,
@@ -316,48 +628,119 @@ async fn health_check(State(state): State<AppState>) -> impl IntoResponse {
     let mut instance_health = serde_json::Map::new();
     let mut any_reachable = false;
     let mut any_unreachable = false;
+    let mut any_unknown = false;
 
+    // Report what the circuit breakers have observed instead of probing every
+    // instance, so /health stays fast while an instance hangs. An instance
+    // nothing was sent to yet is neither reachable nor unreachable.
     for instance in instances {
-        match pool.get(&instance).await {
-            Ok(client) => {
-                if client.health_check().await {
-                    instance_health.insert(instance.clone(), json!({"reachable": true}));
-                    any_reachable = true;
-                } else {
-                    instance_health.insert(
-                        instance.clone(),
-                        json!({"reachable": false, "error": "health check failed"}),
-                    );
-                    any_unreachable = true;
-                }
-            }
-            Err(e) => {
-                instance_health.insert(
-                    instance.clone(),
-                    json!({"reachable": false, "error": e.to_string()}),
-                );
-                any_unreachable = true;
-            }
+        let snapshot = pool
+            .cached(&instance)
+            .await
+            .map(|client| client.breaker().snapshot())
+            .filter(|s| s.last_success.is_some() || s.last_failure.is_some());
+        let Some(snapshot) = snapshot else {
+            any_unknown = true;
+            instance_health.insert(
+                instance.clone(),
+                json!({
+                    "reachable": null,
+                    "state": "unknown",
+                    "server_version": pool.detected_version(&instance),
+                }),
+            );
+            continue;
+        };
+        let reachable = snapshot.state == BreakerState::Closed;
+        if reachable {
+            any_reachable = true;
+        } else {
+            any_unreachable = true;
         }
+        instance_health.insert(
+            instance.clone(),
+            json!({
+                "reachable": reachable,
+                "state": snapshot.state,
+                "consecutive_failures": snapshot.consecutive_failures,
+                "last_error": snapshot.last_error,
+                "last_success": snapshot.last_success,
+                "last_failure": snapshot.last_failure,
+                "server_version": pool.detected_version(&instance),
+            }),
+        );
     }
 
-    let status = if !any_unreachable {
-        "ok"
-    } else if any_reachable {
-        "degraded"
-    } else {
-        "unhealthy"
+    let status = match (any_reachable, any_unreachable, any_unknown) {
+        (true, true, _) => "degraded",
+        (false, true, _) => "unhealthy",
+        // Nothing observed yet: no instance has answered.
+        (false, false, true) => "unknown",
+        _ => "ok",
+    };
+
+    let sessions = {
+        let sessions = state.sessions.lock().await;
+        let channels = state.sse_channels.lock().await;
+        let streaming = sessions
+            .keys()
+            .filter(|id| channels.get(*id).is_some_and(|tx| tx.receiver_count() > 0))
+            .count();
+        json!({
+            "active": sessions.len(),
+            "streaming": streaming,
+            "expired": state.session_stats.expired.load(Ordering::Relaxed),
+            "evicted": state.session_stats.evicted.load(Ordering::Relaxed),
+        })
     };
 
     let response = json!({
         "status": status,
         "version": env!("CARGO_PKG_VERSION"),
-        "instances": instance_health
+        "instances": instance_health,
+        "sessions": sessions
     });
 
     Json(response)
 }
 
+/// Prometheus metrics handler (text exposition format)
+async fn metrics_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> axum::response::Response {
+    // Validate authentication (async for hot-reload support)
+    if let Err(err) = validate_auth_async(&headers, &state.auth).await {
+        return err.into_response();
+    }
+
+    let sessions = state.sessions.lock().await.len() as u64;
+    let sse_subscribers = state
+        .sse_channels
+        .lock()
+        .await
+        .values()
+        .map(|tx| tx.receiver_count() as u64)
+        .sum();
+    let body = metrics::global().render(&[
+        (
+            "odoo_mcp_http_sessions",
+            "Active Streamable HTTP sessions.",
+            sessions,
+        ),
+        (
+            "odoo_mcp_sse_subscribers",
+            "Open SSE streams across all sessions.",
+            sse_subscribers,
+        ),
+    ]);
+    (
+        [(CONTENT_TYPE, "text/plain; version=0.0.4; charset=utf-8")],
+        body,
+    )
+        .into_response()
+}
+
 /// OpenAPI specification handler
 async fn openapi_spec() -> impl IntoResponse {
     const OPENAPI_JSON: &str = include_str!("../../openapi/openapi.json");
@@ -382,9 +765,23 @@ pub fn create_app_with_security(
         handler,
         sessions: Arc::new(Mutex::new(HashMap::new())),
         sse_channels: Arc::new(Mutex::new(HashMap::new())),
+        session_stats: Arc::new(SessionStats::default()),
         auth,
         security,
     };
+    if tokio::runtime::Handle::try_current().is_ok() {
+        tokio::spawn(forward_notifications(
+            state.handler.subscribe_notifications(),
+            state.sessions.clone(),
+            state.sse_channels.clone(),
+        ));
+        tokio::spawn(forward_resource_updates(
+            state.handler.subscribe_resource_updates(),
+            state.sessions.clone(),
+            state.sse_channels.clone(),
+        ));
+        tokio::spawn(sweep_sessions(state.clone()));
+    }
 
     Router::new()
         // Streamable HTTP (MCP 2025-11-25 spec)
@@ -392,14 +789,150 @@ pub fn create_app_with_security(
         // Legacy SSE transport (Cursor supports `SSE` transport option)
         .route("/sse", get(legacy_sse))
         .route("/messages", post(legacy_messages))
+        // OAuth protected resource metadata (RFC 9728, no auth required)
+        .route(
+            "/.well-known/oauth-protected-resource",
+            get(oauth_protected_resource),
+        )
+        .route(
+            "/.well-known/oauth-protected-resource/{*resource}",
+            get(oauth_protected_resource),
+        )
         // Health check endpoint (no auth required for monitoring)
         .route("/health", get(health_check))
+        // Prometheus metrics (auth required when enabled)
+        .route("/metrics", get(metrics_handler))
         // OpenAPI specification (no auth required)
         .route("/openapi.json", get(openapi_spec))
         .layer(CorsLayer::permissive())
         .with_state(state)
 }
 
+/// Periodically drop expired sessions and close their SSE streams, as well as
+/// channels left behind by streams without a session. The task ends with the app.
+async fn sweep_sessions(state: AppState) {
+    let sessions = Arc::downgrade(&state.sessions);
+    let channels = Arc::downgrade(&state.sse_channels);
+    let handler = Arc::downgrade(&state.handler);
+    let stats = state.session_stats.clone();
+    let limits = state.security.sessions.clone();
+    drop(state);
+
+    let mut interval = tokio::time::interval(limits.sweep_interval());
+    loop {
+        interval.tick().await;
+        let (Some(sessions), Some(channels)) = (sessions.upgrade(), channels.upgrade()) else {
+            break;
+        };
+        let now = Instant::now();
+        let mut sessions = sessions.lock().await;
+        let mut channels = channels.lock().await;
+
+        let mut expired = Vec::new();
+        for (id, session) in sessions.iter_mut() {
+            // An open SSE stream keeps its session active.
+            if channels.get(id).is_some_and(|tx| tx.receiver_count() > 0) {
+                session.last_active = now;
+            }
+            if session.is_expired(&limits, now) {
+                expired.push(id.clone());
+            }
+        }
+        for id in &expired {
+            sessions.remove(id);
+            // Dropping the sender ends the session's SSE streams.
+            channels.remove(id);
+            debug!(session = %id, "Session expired");
+        }
+        let mut ended = expired.clone();
+        channels.retain(|id, tx| {
+            let keep = sessions.contains_key(id) || tx.receiver_count() > 0;
+            if !keep {
+                ended.push(id.clone());
+            }
+            keep
+        });
+        if let Some(handler) = handler.upgrade() {
+            for id in &ended {
+                handler.drop_subscriber(id);
+            }
+        }
+        if !expired.is_empty() {
+            stats
+                .expired
+                .fetch_add(expired.len() as u64, Ordering::Relaxed);
+            info!(
+                expired = expired.len(),
+                active = sessions.len(),
+                "Expired idle MCP sessions"
+            );
+        }
+    }
+}
+
+/// Fan server notifications out to every open SSE stream.
+async fn forward_notifications(
+    mut notifications: broadcast::Receiver<Value>,
+    sessions: Arc<Mutex<HashMap<String, SessionState>>>,
+    sse_channels: Arc<Mutex<SseChannels>>,
+) {
+    loop {
+        let notification = match notifications.recv().await {
+            Ok(notification) => notification,
+            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => break,
+        };
+        let ids: Vec<String> = sse_channels.lock().await.keys().cloned().collect();
+        for id in ids {
+            send_to_session(&sessions, &sse_channels, &id, notification.clone()).await;
+        }
+    }
+}
+
+/// Send `notifications/resources/updated` to the sessions subscribed to the
+/// resource that changed.
+async fn forward_resource_updates(
+    mut updates: broadcast::Receiver<ResourceUpdate>,
+    sessions: Arc<Mutex<HashMap<String, SessionState>>>,
+    sse_channels: Arc<Mutex<SseChannels>>,
+) {
+    loop {
+        let update = match updates.recv().await {
+            Ok(update) => update,
+            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => break,
+        };
+        let notification = json!({
+            "jsonrpc": "2.0",
+            "method": "notifications/resources/updated",
+            "params": { "uri": update.uri }
+        });
+        for id in &update.subscribers {
+            send_to_session(&sessions, &sse_channels, id, notification.clone()).await;
+        }
+    }
+}
+
+/// Send a message on a session's SSE streams: numbered and kept for replay on
+/// Streamable HTTP sessions, forwarded as is on legacy SSE streams.
+async fn send_to_session(
+    sessions: &Mutex<HashMap<String, SessionState>>,
+    sse_channels: &Mutex<SseChannels>,
+    session_id: &str,
+    message: Value,
+) {
+    let session = sessions.lock().await.get(session_id).cloned();
+    let Some(tx) = sse_channels.lock().await.get(session_id).cloned() else {
+        return;
+    };
+    match session {
+        Some(session) => session.publish(session_id, &tx, message).await,
+        None => {
+            let _ = tx.send(StoredEvent::unnumbered(message));
+        }
+    }
+}
+
 fn jsonrpc_err(id: RequestId, code: ErrorCode, message: impl Into<String>) -> Response {
     Response::error(
         id,
@@ -440,7 +973,7 @@ fn cursor_initialize_result(
         "capabilities": {
             "tools": { "listChanged": true },
             "prompts": { "listChanged": true },
-            "resources": {},
+            "resources": { "listChanged": true, "subscribe": true },
             "experimental": {
                 "odooInstances": { "available": odoo_instances }
             }
@@ -507,71 +1040,202 @@ fn validate_origin(
     }
 }
 
-/// Validate Bearer token authentication
-/// Validate Bearer token authentication (async version for hot-reload support)
+/// A refused request, answered with an OAuth-style error body and a
+/// `WWW-Authenticate` challenge (RFC 6750 / RFC 9728).
+#[derive(Debug)]
+struct AuthError {
+    status: StatusCode,
+    error: &'static str,
+    description: String,
+    /// Scopes the token lacked (insufficient_scope).
+    scope: Option<String>,
+    /// Protected resource metadata URL, when OAuth is configured.
+    resource_metadata: Option<String>,
+}
+
+impl AuthError {
+    fn new(
+        status: StatusCode,
+        error: &'static str,
+        description: impl Into<String>,
+        auth: &AuthConfigData,
+    ) -> Self {
+        Self {
+            status,
+            error,
+            description: description.into(),
+            scope: None,
+            resource_metadata: auth.oauth.as_ref().map(|o| o.metadata_url()),
+        }
+    }
+
+    fn challenge(&self) -> String {
+        let mut params = Vec::new();
+        if let Some(url) = &self.resource_metadata {
+            params.push(format!("resource_metadata=\"{url}\""));
+        }
+        // A request without credentials gets a bare challenge.
+        if self.description != MISSING_AUTHORIZATION {
+            params.push(format!("error=\"{}\"", self.error));
+            params.push(format!(
+                "error_description=\"{}\"",
+                self.description.replace('"', "'")
+            ));
+        }
+        if let Some(scope) = &self.scope {
+            params.push(format!("scope=\"{scope}\""));
+        }
+        if params.is_empty() {
+            "Bearer".to_string()
+        } else {
+            format!("Bearer {}", params.join(", "))
+        }
+    }
+}
+
+impl IntoResponse for AuthError {
+    fn into_response(self) -> axum::response::Response {
+        let mut headers = HeaderMap::new();
+        if self.status != StatusCode::INTERNAL_SERVER_ERROR
+            && let Ok(value) = HeaderValue::from_str(&self.challenge())
+        {
+            headers.insert(axum::http::header::WWW_AUTHENTICATE, value);
+        }
+        let body = json!({
+            "error": self.error,
+            "error_description": self.description,
+        });
+        (self.status, headers, Json(body)).into_response()
+    }
+}
+
+const MISSING_AUTHORIZATION: &str = "Missing Authorization header";
+
+/// Outcome of checking a bearer token against the static configuration.
+enum TokenCheck {
+    /// Accepted; `None` means unrestricted access.
+    Accepted(Option<Principal>),
+    /// Not a configured token: left to the OAuth resource server.
+    Unknown(String),
+}
+
+/// Validate Bearer token authentication (async version for hot-reload support).
+/// Returns the principal the token belongs to; `None` means unrestricted access
+/// (authentication disabled or the shared MCP_AUTH_TOKEN).
 async fn validate_auth_async(
     headers: &HeaderMap,
     auth: &AuthConfig,
-) -> Result<(), (StatusCode, Json<Value>)> {
+) -> Result<Option<Principal>, AuthError> {
     let auth_data = auth.get().await;
-    validate_auth_data(headers, &auth_data)
+    let token = match validate_auth_data(headers, &auth_data)? {
+        TokenCheck::Accepted(principal) => return Ok(principal),
+        TokenCheck::Unknown(token) => token,
+    };
+    let Some(oauth) = &auth_data.oauth else {
+        return Err(AuthError::new(
+            StatusCode::UNAUTHORIZED,
+            "invalid_token",
+            "The access token is invalid",
+            &auth_data,
+        ));
+    };
+    match oauth.authenticate(&token).await {
+        Ok(principal) => {
+            debug!(principal = %principal.name, "Accepted OAuth access token");
+            Ok(Some(principal))
+        }
+        Err(OAuthError::InvalidToken(description)) => {
+            debug!("Rejected OAuth access token: {description}");
+            Err(AuthError::new(
+                StatusCode::UNAUTHORIZED,
+                "invalid_token",
+                description,
+                &auth_data,
+            ))
+        }
+        Err(OAuthError::InsufficientScope(scope)) => {
+            let mut err = AuthError::new(
+                StatusCode::FORBIDDEN,
+                "insufficient_scope",
+                "The access token lacks required scopes",
+                &auth_data,
+            );
+            err.scope = Some(scope);
+            Err(err)
+        }
+        Err(OAuthError::Unavailable(description)) => Err(AuthError::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "server_error",
+            description,
+            &auth_data,
+        )),
+    }
 }
 
 /// Validate Bearer token authentication against config data
-fn validate_auth_data(
-    headers: &HeaderMap,
-    auth: &AuthConfigData,
-) -> Result<(), (StatusCode, Json<Value>)> {
+fn validate_auth_data(headers: &HeaderMap, auth: &AuthConfigData) -> Result<TokenCheck, AuthError> {
     // Check if auth is enabled
     if !auth.enabled {
-        return Ok(());
+        return Ok(TokenCheck::Accepted(None));
     }
 
-    let Some(expected_token) = &auth.bearer_token else {
+    if auth.bearer_token.is_none() && auth.principals.is_empty() && auth.oauth.is_none() {
         // Auth enabled but no token configured - deny all
-        return Err((
+        return Err(AuthError::new(
             StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({
-                "error": "server_error",
-                "error_description": "Authentication enabled but no token configured"
-            })),
+            "server_error",
+            "Authentication enabled but no token configured",
+            auth,
         ));
-    };
+    }
 
     let auth_header = headers.get(&AUTHORIZATION).and_then(|v| v.to_str().ok());
 
     match auth_header {
         Some(header) if header.starts_with("Bearer ") => {
             let token = &header[7..];
-            if token == expected_token {
-                Ok(())
+            if let Some(principal) = auth.principals.iter().find(|p| p.matches_token(token)) {
+                if principal.is_expired(chrono::Utc::now()) {
+                    warn!(principal = %principal.name, "Rejected expired MCP token");
+                    return Err(AuthError::new(
+                        StatusCode::UNAUTHORIZED,
+                        "invalid_token",
+                        "The access token has expired",
+                        auth,
+                    ));
+                }
+                return Ok(TokenCheck::Accepted(Some(principal.clone())));
+            }
+            if auth.bearer_token.as_deref() == Some(token) {
+                Ok(TokenCheck::Accepted(None))
             } else {
-                Err((
-                    StatusCode::UNAUTHORIZED,
-                    Json(json!({
-                        "error": "invalid_token",
-                        "error_description": "The access token is invalid"
-                    })),
-                ))
+                Ok(TokenCheck::Unknown(token.to_string()))
             }
         }
-        Some(_) => Err((
+        Some(_) => Err(AuthError::new(
             StatusCode::UNAUTHORIZED,
-            Json(json!({
-                "error": "invalid_request",
-                "error_description": "Authorization header must use Bearer scheme"
-            })),
+            "invalid_request",
+            "Authorization header must use Bearer scheme",
+            auth,
         )),
-        None => Err((
+        None => Err(AuthError::new(
             StatusCode::UNAUTHORIZED,
-            Json(json!({
-                "error": "invalid_request",
-                "error_description": "Missing Authorization header"
-            })),
+            "invalid_request",
+            MISSING_AUTHORIZATION,
+            auth,
         )),
     }
 }
 
+/// GET /.well-known/oauth-protected-resource[/path] - OAuth protected resource
+/// metadata (RFC 9728); 404 when the OAuth resource server is not configured.
+async fn oauth_protected_resource(State(state): State<AppState>) -> axum::response::Response {
+    match state.auth.get().await.oauth {
+        Some(oauth) => Json(oauth.metadata()).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
 /// Validate MCP-Protocol-Version header
 fn validate_protocol_version(
     headers: &HeaderMap,
@@ -614,15 +1278,27 @@ fn validate_protocol_version(
     }
 }
 
-/// Validate session exists and is not expired
+/// Validate session exists, is not expired and belongs to `principal`, and
+/// mark it active
 fn validate_session(
     session_id: Option<&str>,
-    sessions: &HashMap<String, SessionState>,
+    principal: Option<&Principal>,
+    sessions: &mut HashMap<String, SessionState>,
+    limits: &SessionLimits,
 ) -> Result<Option<SessionState>, (StatusCode, Json<Value>)> {
+    let now = Instant::now();
     match session_id {
-        Some(id) => match sessions.get(id) {
-            Some(state) => Ok(Some(state.clone())),
-            None => {
+        // Another principal's session is as unknown as a missing one: it
+        // holds the Odoo credentials of its owner.
+        Some(id) => match sessions.get_mut(id) {
+            Some(state)
+                if !state.is_expired(limits, now)
+                    && state.principal.as_deref() == principal.map(|p| p.name.as_str()) =>
+            {
+                state.last_active = now;
+                Ok(Some(state.clone()))
+            }
+            _ => {
                 // Session not found - per spec, return 404
                 Err((
                     StatusCode::NOT_FOUND,
@@ -637,8 +1313,45 @@ fn validate_session(
     }
 }
 
+/// Close the least recently active sessions of `principal` until at most
+/// `keep` remain.
+fn evict_oldest_sessions(
+    sessions: &mut HashMap<String, SessionState>,
+    channels: &mut SseChannels,
+    principal: Option<&str>,
+    keep: usize,
+    stats: &SessionStats,
+) -> Vec<String> {
+    let mut owned: Vec<(Instant, String)> = sessions
+        .iter()
+        .filter(|(_, s)| s.principal.as_deref() == principal)
+        .map(|(id, s)| (s.last_active, id.clone()))
+        .collect();
+    if owned.len() <= keep {
+        return Vec::new();
+    }
+    owned.sort();
+    let evicted: Vec<String> = owned
+        .drain(..owned.len() - keep)
+        .map(|(_, id)| id)
+        .collect();
+    for id in &evicted {
+        sessions.remove(id);
+        channels.remove(id);
+        stats.evicted.fetch_add(1, Ordering::Relaxed);
+        info!(
+            session = %id,
+            principal = principal.unwrap_or("-"),
+            "Closed least recently used session: session limit reached"
+        );
+    }
+    evicted
+}
+
 async fn handle_jsonrpc(
     state: &AppState,
+    principal: Option<&Principal>,
+    credentials: Option<UserCredentials>,
     session_id: Option<String>,
     v: Value,
 ) -> Result<(Option<String>, Option<Value>, StatusCode, Option<String>), (StatusCode, Value)> {
@@ -666,12 +1379,31 @@ async fn handle_jsonrpc(
         let id: RequestId = serde_json::from_value(id_val)
             .map_err(|e| (StatusCode::BAD_REQUEST, json!({"error": e.to_string()})))?;
         let params = params.unwrap_or_else(|| json!({}));
+        let credentials = match credentials {
+            Some(credentials) => Some(credentials),
+            None if state.security.user_credentials != UserCredentialsMode::Off => {
+                initialize_credentials(&params)
+                    .map_err(|e| (StatusCode::BAD_REQUEST, json!({"error": e.to_string()})))?
+            }
+            None => None,
+        };
+        if state.security.user_credentials == UserCredentialsMode::Required && credentials.is_none()
+        {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                json!({"error": "Odoo user credentials required: send X-Odoo-Api-Key (or X-Odoo-Login and X-Odoo-Password) or odooCredentials in the initialize params"}),
+            ));
+        }
         let protocol_default = state.handler.protocol_version_default().await;
         let server_name = state.handler.server_name().await;
         let instructions = state.handler.instructions().await;
+        let mut instances = state.handler.instance_names();
+        if let Some(principal) = principal {
+            instances.retain(|i| principal.allows_instance(i));
+        }
         let (result, negotiated_version) = cursor_initialize_result(
             &params,
-            state.handler.instance_names(),
+            instances,
             protocol_default,
             server_name,
             instructions,
@@ -679,17 +1411,36 @@ async fn handle_jsonrpc(
         .map_err(|e| (StatusCode::BAD_REQUEST, json!({"error": e.to_string()})))?;
 
         let sess = Uuid::new_v4().to_string();
-        state
-            .sessions
-            .lock()
-            .await
-            .insert(sess.clone(), SessionState::new(negotiated_version.clone()));
-        state
-            .sse_channels
-            .lock()
-            .await
-            .entry(sess.clone())
-            .or_insert_with(|| broadcast::channel(256).0);
+        if let Some(credentials) = &credentials {
+            info!(
+                session = %sess,
+                user = credentials.label(),
+                "MCP session calls Odoo with the client's credentials"
+            );
+        }
+        let mut session = SessionState::new(negotiated_version.clone());
+        session.credentials = credentials;
+        session.principal = principal.map(|p| p.name.clone());
+        session.event_buffer_size = state.security.sessions.event_buffer_size;
+        {
+            let mut sessions = state.sessions.lock().await;
+            let mut channels = state.sse_channels.lock().await;
+            if let Some(max) = state.security.sessions.max_per_principal {
+                for evicted in evict_oldest_sessions(
+                    &mut sessions,
+                    &mut channels,
+                    session.principal.as_deref(),
+                    max.saturating_sub(1),
+                    &state.session_stats,
+                ) {
+                    state.handler.drop_subscriber(&evicted);
+                }
+            }
+            sessions.insert(sess.clone(), session);
+            channels
+                .entry(sess.clone())
+                .or_insert_with(|| broadcast::channel(256).0);
+        }
 
         let resp = Response::success(id, Some(result));
         return Ok((
@@ -734,25 +1485,74 @@ async fn handle_jsonrpc(
         ));
     }
 
+    // Credentials of the request win over those given on initialize.
+    let credentials = match credentials {
+        Some(credentials) => Some(credentials),
+        None => match &effective_session {
+            Some(sess) => state
+                .sessions
+                .lock()
+                .await
+                .get(sess)
+                .and_then(|st| st.credentials.clone()),
+            None => None,
+        },
+    };
+    if state.security.user_credentials == UserCredentialsMode::Required
+        && credentials.is_none()
+        && matches!(
+            method.as_str(),
+            "tools/call" | "resources/read" | "resources/subscribe"
+        )
+        && let Some(id) = id_val
+            .clone()
+            .and_then(|x| serde_json::from_value::<RequestId>(x).ok())
+    {
+        let resp = jsonrpc_err(
+            id,
+            ErrorCode::InvalidRequest,
+            "Odoo user credentials required for this session",
+        );
+        return Ok((
+            None,
+            Some(serde_json::to_value(resp).unwrap()),
+            StatusCode::OK,
+            None,
+        ));
+    }
+
     // Notifications: best-effort handle_method, return 202.
     if id_val.is_none() {
-        let _ = state.handler.handle_method(&method, params).await;
+        let _ = state
+            .handler
+            .handle_method_as(principal, credentials.as_ref(), &method, params)
+            .await;
         return Ok((None, None, StatusCode::ACCEPTED, None));
     }
 
     let id: RequestId = serde_json::from_value(id_val.unwrap())
         .map_err(|e| (StatusCode::BAD_REQUEST, json!({"error": e.to_string()})))?;
 
-    let result = state
-        .handler
-        .handle_method(&method, params)
-        .await
-        .map_err(|e| {
-            (
-                StatusCode::OK,
-                jsonrpc_err(id.clone(), ErrorCode::InternalError, e.to_string()).to_value(),
-            )
-        })?;
+    let result = match (&effective_session, method.as_str()) {
+        (Some(sess), "resources/subscribe" | "resources/unsubscribe") => {
+            state
+                .handler
+                .handle_subscription(sess, principal, credentials.as_ref(), &method, params)
+                .await
+        }
+        _ => {
+            state
+                .handler
+                .handle_method_as(principal, credentials.as_ref(), &method, params)
+                .await
+        }
+    }
+    .map_err(|e| {
+        (
+            StatusCode::OK,
+            jsonrpc_err(id.clone(), ErrorCode::InternalError, e.to_string()).to_value(),
+        )
+    })?;
     let resp = Response::success(id, Some(result));
     Ok((
         None,
@@ -762,11 +1562,61 @@ async fn handle_jsonrpc(
     ))
 }
 
+/// Handle a JSON-RPC batch. Its messages run concurrently and the responses to
+/// its requests come back as one array, or `None` when it held only
+/// notifications. `Err` is the error answering a batch that cannot run.
+async fn handle_batch(
+    state: &AppState,
+    principal: Option<&Principal>,
+    credentials: Option<UserCredentials>,
+    session_id: Option<String>,
+    items: Vec<Value>,
+) -> Result<Option<Value>, Value> {
+    batch::check(&items)?;
+    let responses = futures::future::join_all(items.into_iter().map(|item| {
+        let credentials = credentials.clone();
+        let session_id = session_id.clone();
+        async move {
+            let id = item.get("id").cloned();
+            // The session comes with the response headers; a batch has none of its own.
+            if item.get("method").and_then(|m| m.as_str()) == Some("initialize") {
+                return Some(batch::error_response(
+                    id,
+                    ErrorCode::InvalidRequest,
+                    "initialize cannot be part of a batch",
+                ));
+            }
+            match handle_jsonrpc(state, principal, credentials, session_id, item).await {
+                Ok((_, response, _, _)) => response,
+                Err((_, error)) => Some(jsonrpc_error_for(id, error)),
+            }
+        }
+    }))
+    .await;
+    let responses: Vec<Value> = responses.into_iter().flatten().collect();
+    Ok((!responses.is_empty()).then_some(Value::Array(responses)))
+}
+
+/// An `Err` of [`handle_jsonrpc`] as a JSON-RPC response to request `id`, for
+/// replies that cannot carry its HTTP status (batch entries, SSE events).
+fn jsonrpc_error_for(id: Option<Value>, error: Value) -> Value {
+    if error.get("jsonrpc").is_some() {
+        return error;
+    }
+    batch::error_response(
+        id,
+        ErrorCode::InvalidRequest,
+        error["error"].as_str().unwrap_or("Invalid request"),
+    )
+}
+
 /// POST /mcp - Send JSON-RPC messages
 ///
 /// Per MCP spec:
 /// - Accept header must include application/json and text/event-stream
-/// - Response can be JSON or SSE stream
+/// - Body is a message or a batch (array) of them
+/// - Response can be JSON or SSE stream; tools/call is streamed when the client
+///   accepts text/event-stream
 /// - For notifications/responses: return 202 Accepted
 /// - For requests: return response or stream
 async fn mcp_post(
@@ -780,9 +1630,15 @@ async fn mcp_post(
     }
 
     // Validate authentication (async for hot-reload support)
-    if let Err(err) = validate_auth_async(&headers, &state.auth).await {
-        return err.into_response();
-    }
+    let principal = match validate_auth_async(&headers, &state.auth).await {
+        Ok(principal) => principal,
+        Err(err) => return err.into_response(),
+    };
+
+    let credentials = match request_credentials(&headers, &state.security) {
+        Ok(credentials) => credentials,
+        Err(err) => return err.into_response(),
+    };
 
     let session_id = headers
         .get(&MCP_SESSION_ID)
@@ -796,22 +1652,50 @@ async fn mcp_post(
         .is_some_and(|m| m == "initialize");
 
     if !is_initialize && let Some(ref sid) = session_id {
-        let sessions = state.sessions.lock().await;
-        if let Err(err) = validate_session(Some(sid), &sessions) {
-            return err.into_response();
-        }
-        let session_state = sessions.get(sid);
-        if let Err(err) = validate_protocol_version(&headers, session_state) {
+        let mut sessions = state.sessions.lock().await;
+        let session_state = match validate_session(
+            Some(sid),
+            principal.as_ref(),
+            &mut sessions,
+            &state.security.sessions,
+        ) {
+            Ok(session_state) => session_state,
+            Err(err) => return err.into_response(),
+        };
+        if let Err(err) = validate_protocol_version(&headers, session_state.as_ref()) {
             return err.into_response();
         }
     }
 
-    // Handle the JSON-RPC message
-    let (new_sess, maybe_resp, status, protocol_version) =
-        match handle_jsonrpc(&state, session_id.clone(), body).await {
-            Ok(v) => v,
-            Err((sc, v)) => return (sc, Json(v)).into_response(),
+    if let Value::Array(items) = body {
+        return match handle_batch(&state, principal.as_ref(), credentials, session_id, items)
+            .instrument(request_span(principal.as_ref()))
+            .await
+        {
+            Ok(Some(responses)) => Json(responses).into_response(),
+            Ok(None) => StatusCode::ACCEPTED.into_response(),
+            Err(error) => (StatusCode::BAD_REQUEST, Json(error)).into_response(),
         };
+    }
+
+    if is_tool_call(&body) && accepts_event_stream(&headers) {
+        return stream_tool_call(state, principal, credentials, session_id, body);
+    }
+
+    // Handle the JSON-RPC message
+    let (new_sess, maybe_resp, status, protocol_version) = match handle_jsonrpc(
+        &state,
+        principal.as_ref(),
+        credentials,
+        session_id.clone(),
+        body,
+    )
+    .instrument(request_span(principal.as_ref()))
+    .await
+    {
+        Ok(v) => v,
+        Err((sc, v)) => return (sc, Json(v)).into_response(),
+    };
 
     // Build response headers
     let mut out_headers = HeaderMap::new();
@@ -831,12 +1715,76 @@ async fn mcp_post(
     }
 }
 
+fn accepts_event_stream(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(axum::http::header::ACCEPT)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .any(|v| v.contains("text/event-stream"))
+}
+
+/// A tools/call request (not a notification), worth streaming.
+fn is_tool_call(body: &Value) -> bool {
+    body.get("id").is_some_and(|id| !id.is_null())
+        && body.get("method").and_then(|m| m.as_str()) == Some("tools/call")
+}
+
+/// Answer a tools/call with an SSE stream: the progress and log notifications
+/// of the call as they happen, then its response, which ends the stream.
+/// Keepalive comments stop proxies from timing out long calls.
+fn stream_tool_call(
+    state: AppState,
+    principal: Option<Principal>,
+    credentials: Option<UserCredentials>,
+    session_id: Option<String>,
+    body: Value,
+) -> axum::response::Response {
+    let (reporter, notifications) =
+        progress::Reporter::new(progress::progress_token(body.get("params")));
+    let (response_tx, response_rx) = oneshot::channel();
+    let id = body.get("id").cloned();
+    let span = request_span(principal.as_ref());
+    // Runs to completion even if the client goes away.
+    tokio::spawn(
+        async move {
+            let result = progress::scope(
+                reporter,
+                handle_jsonrpc(&state, principal.as_ref(), credentials, session_id, body),
+            )
+            .await;
+            let response = match result {
+                Ok((_, response, _, _)) => response,
+                Err((_, error)) => Some(jsonrpc_error_for(id, error)),
+            };
+            let _ = response_tx.send(response);
+        }
+        .instrument(span),
+    );
+
+    // The notification stream ends when the call is done and its reporter dropped.
+    let events = UnboundedReceiverStream::new(notifications)
+        .map(Some)
+        .chain(futures::stream::once(response_rx).map(|r| r.ok().flatten()))
+        .filter_map(|message| message)
+        .map(|message| Ok::<Event, Infallible>(message_event(StoredEvent::unnumbered(message))));
+
+    let mut headers = HeaderMap::new();
+    headers.insert(&X_ACCEL_BUFFERING, HeaderValue::from_static("no"));
+    let sse = Sse::new(events).keep_alive(
+        axum::response::sse::KeepAlive::new()
+            .interval(Duration::from_secs(SSE_KEEPALIVE_SECS))
+            .text("keepalive"),
+    );
+    (headers, sse).into_response()
+}
+
 /// GET /mcp - Open SSE stream for server-to-client messages
 ///
 /// Per MCP spec:
 /// - Accept header must include text/event-stream
 /// - Server can send notifications/requests
 /// - Supports resumability via Last-Event-ID
+/// - Needs a live session of the caller; 404 otherwise
 async fn mcp_get(State(state): State<AppState>, headers: HeaderMap) -> axum::response::Response {
     // Validate Origin (security)
     if let Err(err) = validate_origin(&headers, &state.security) {
@@ -844,65 +1792,77 @@ async fn mcp_get(State(state): State<AppState>, headers: HeaderMap) -> axum::res
     }
 
     // Validate authentication (async for hot-reload support)
-    if let Err(err) = validate_auth_async(&headers, &state.auth).await {
-        return err.into_response();
-    }
+    let principal = match validate_auth_async(&headers, &state.auth).await {
+        Ok(principal) => principal,
+        Err(err) => return err.into_response(),
+    };
 
-    // Get session ID
-    let session_id = headers
+    let Some(session_id) = headers
         .get(&MCP_SESSION_ID)
         .and_then(|v| v.to_str().ok())
         .map(|s| s.to_string())
-        .unwrap_or_else(|| "default".to_string());
+    else {
+        return missing_session_id().into_response();
+    };
 
     // Check for Last-Event-ID for resumability
-    let _last_event_id = headers
+    let last_event_id = headers
         .get(&LAST_EVENT_ID)
         .and_then(|v| v.to_str().ok())
         .map(|s| s.to_string());
 
-    // Get or create the broadcast channel
+    // The stream replays past events: only the session's own principal gets it.
     let (tx, session_state) = {
-        let mut chans = state.sse_channels.lock().await;
-        let tx = chans
+        let mut sessions = state.sessions.lock().await;
+        let session_state = match validate_session(
+            Some(&session_id),
+            principal.as_ref(),
+            &mut sessions,
+            &state.security.sessions,
+        ) {
+            Ok(Some(session_state)) => session_state,
+            Ok(None) => return missing_session_id().into_response(),
+            Err(err) => return err.into_response(),
+        };
+        let tx = state
+            .sse_channels
+            .lock()
+            .await
             .entry(session_id.clone())
             .or_insert_with(|| broadcast::channel(256).0)
             .clone();
-
-        let sessions = state.sessions.lock().await;
-        let session_state = sessions.get(&session_id).cloned();
         (tx, session_state)
     };
 
-    // Build the SSE stream
-    let session_for_events = session_id.clone();
+    // Subscribe, and collect the events missed since Last-Event-ID
+    let resume = session_state
+        .resume(&session_id, &tx, last_event_id.as_deref())
+        .await;
+    drop(tx);
+    if resume.gap {
+        warn!(
+            session = %session_id,
+            last_event_id = last_event_id.as_deref().unwrap_or_default(),
+            "SSE resume point no longer buffered; events were lost"
+        );
+    }
 
     // Initial event with retry field to prime reconnection
-    let initial_event_id = session_state
-        .as_ref()
-        .map(|s| s.next_event_id(&session_for_events))
-        .unwrap_or_else(|| format!("{}:0", session_for_events));
-
     let initial_events = iter(vec![Ok::<Event, Infallible>(
         Event::default()
-            .id(initial_event_id)
+            .id(resume.last_id)
             .retry(Duration::from_millis(SSE_RETRY_MS))
             .comment("connected"),
     )]);
 
-    // Replay events if Last-Event-ID was provided
-    // Note: For full resumability, we'd fetch events from session_state.get_events_after(_last_event_id).
-    // This is left as a placeholder - in production, you'd spawn a task to fetch and replay events.
-    let replay_events: Vec<StoredEvent> = vec![];
-
-    let replay_stream = iter(replay_events.into_iter().map(|e: StoredEvent| {
-        Ok::<Event, Infallible>(
-            Event::default()
-                .id(e.id)
-                .event("message")
-                .data(e.data.to_string()),
-        )
-    }));
+    // Replay events missed since Last-Event-ID, after a gap event if some were lost
+    let gap = resume.gap.then(|| {
+        Ok::<Event, Infallible>(gap_event(last_event_id.as_deref(), resume.events.first()))
+    });
+    let replay_stream = iter(
+        gap.into_iter()
+            .chain(resume.events.into_iter().map(|e| Ok(message_event(e)))),
+    );
 
     // Keepalive stream
     let keepalive = IntervalStream::new(tokio::time::interval(Duration::from_secs(
@@ -911,34 +1871,48 @@ async fn mcp_get(State(state): State<AppState>, headers: HeaderMap) -> axum::res
     .map(|_| Ok::<Event, Infallible>(Event::default().comment("keepalive")));
 
     // Message stream from broadcast channel
-    let session_for_stream = session_id.clone();
-    let session_state_for_stream = session_state.clone();
-
-    let stream = BroadcastStream::new(tx.subscribe()).filter_map(move |msg| {
-        match msg {
-            Ok(v) => {
-                let event_id = session_state_for_stream
-                    .as_ref()
-                    .map(|s| s.next_event_id(&session_for_stream))
-                    .unwrap_or_else(|| Uuid::new_v4().to_string());
-
-                Some(Ok(Event::default()
-                    .id(event_id)
-                    .event("message")
-                    .data(v.to_string())))
-            }
-            Err(_) => None, // Channel lagged, skip
-        }
+    let stream = BroadcastStream::new(resume.receiver).map(|msg| match msg {
+        Ok(e) => Ok::<Event, Infallible>(message_event(e)),
+        // Too slow a reader: the skipped events are lost on this stream.
+        Err(BroadcastStreamRecvError::Lagged(_)) => Ok(gap_event(None, None)),
     });
 
+    // The stream ends, keepalives included, once the session is closed and its
+    // channel dropped.
+    let live = keepalive
+        .map(Some)
+        .merge(stream.map(Some).chain(iter([None])))
+        .take_while(Option::is_some)
+        .filter_map(|event| event);
+
     // Combine all streams
-    Sse::new(
-        initial_events
-            .chain(replay_stream)
-            .chain(keepalive.merge(stream)),
+    Sse::new(initial_events.chain(replay_stream).chain(live))
+        .keep_alive(axum::response::sse::KeepAlive::default())
+        .into_response()
+}
+
+/// SSE `message` event, with its id when the session numbers events.
+fn message_event(event: StoredEvent) -> Event {
+    let sse = Event::default()
+        .event("message")
+        .data(event.data.to_string());
+    if event.id.is_empty() {
+        sse
+    } else {
+        sse.id(event.id)
+    }
+}
+
+/// SSE `gap` event: events after `last_event_id` were lost, replay resumes at
+/// `resumed_from` (if anything is left).
+fn gap_event(last_event_id: Option<&str>, resumed_from: Option<&StoredEvent>) -> Event {
+    Event::default().event("gap").data(
+        json!({
+            "lastEventId": last_event_id,
+            "resumedFrom": resumed_from.map(|e| e.id.as_str()),
+        })
+        .to_string(),
     )
-    .keep_alive(axum::response::sse::KeepAlive::default())
-    .into_response()
 }
 
 /// DELETE /mcp - Explicitly terminate a session
@@ -946,6 +1920,7 @@ async fn mcp_get(State(state): State<AppState>, headers: HeaderMap) -> axum::res
 /// Per MCP spec:
 /// - Client can explicitly terminate a session
 /// - Server responds with 200 OK or 405 Method Not Allowed
+/// - Only the session's own principal may end it; 404 otherwise
 async fn mcp_delete(State(state): State<AppState>, headers: HeaderMap) -> impl IntoResponse {
     // Validate Origin (security)
     if let Err(err) = validate_origin(&headers, &state.security) {
@@ -953,9 +1928,10 @@ async fn mcp_delete(State(state): State<AppState>, headers: HeaderMap) -> impl I
     }
 
     // Validate authentication (async for hot-reload support)
-    if let Err(err) = validate_auth_async(&headers, &state.auth).await {
-        return err.into_response();
-    }
+    let principal = match validate_auth_async(&headers, &state.auth).await {
+        Ok(principal) => principal,
+        Err(err) => return err.into_response(),
+    };
 
     let session_id = headers
         .get(&MCP_SESSION_ID)
@@ -963,40 +1939,41 @@ async fn mcp_delete(State(state): State<AppState>, headers: HeaderMap) -> impl I
         .map(|s| s.to_string());
 
     let Some(session_id) = session_id else {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(jsonrpc_err_no_id(
-                ErrorCode::InvalidRequest,
-                "Missing MCP-Session-Id header",
-            )),
-        )
-            .into_response();
+        return missing_session_id().into_response();
     };
 
-    // Remove session and its SSE channel
-    let removed = {
+    // Remove session and its SSE channel, if the caller owns it
+    {
         let mut sessions = state.sessions.lock().await;
-        sessions.remove(&session_id).is_some()
-    };
+        if let Err(err) = validate_session(
+            Some(&session_id),
+            principal.as_ref(),
+            &mut sessions,
+            &state.security.sessions,
+        ) {
+            return err.into_response();
+        }
+        sessions.remove(&session_id);
+    }
 
     {
         let mut channels = state.sse_channels.lock().await;
         channels.remove(&session_id);
     }
+    state.handler.drop_subscriber(&session_id);
 
-    if removed {
-        info!("Session terminated: {}", session_id);
-        StatusCode::OK.into_response()
-    } else {
-        (
-            StatusCode::NOT_FOUND,
-            Json(jsonrpc_err_no_id(
-                ErrorCode::InvalidRequest,
-                "Session not found",
-            )),
-        )
-            .into_response()
-    }
+    info!("Session terminated: {}", session_id);
+    StatusCode::OK.into_response()
+}
+
+fn missing_session_id() -> (StatusCode, Json<Value>) {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(jsonrpc_err_no_id(
+            ErrorCode::InvalidRequest,
+            "Missing MCP-Session-Id header",
+        )),
+    )
 }
 
 #[derive(Deserialize)]
@@ -1033,7 +2010,9 @@ async fn legacy_sse(State(state): State<AppState>, headers: HeaderMap) -> axum::
     )]);
 
     let stream = BroadcastStream::new(tx.subscribe()).filter_map(|msg| match msg {
-        Ok(v) => Some(Ok(Event::default().event("message").data(v.to_string()))),
+        Ok(e) => Some(Ok(Event::default()
+            .event("message")
+            .data(e.data.to_string()))),
         Err(_) => None,
     });
 
@@ -1054,9 +2033,14 @@ async fn legacy_messages(
     }
 
     // Validate authentication (async for hot-reload support)
-    if let Err(err) = validate_auth_async(&headers, &state.auth).await {
-        return err.into_response();
-    }
+    let principal = match validate_auth_async(&headers, &state.auth).await {
+        Ok(principal) => principal,
+        Err(err) => return err.into_response(),
+    };
+
+    let Ok(credentials) = request_credentials(&headers, &state.security) else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
 
     let session = q.session_id.or_else(|| {
         headers
@@ -1066,21 +2050,92 @@ async fn legacy_messages(
     });
 
     // Legacy transport: responses are delivered on SSE stream, not in HTTP response.
-    let (_new_sess, maybe_resp, _status, _) =
-        match handle_jsonrpc(&state, session.clone(), body).await {
-            Ok(v) => v,
-            Err((_sc, _v)) => return StatusCode::BAD_REQUEST.into_response(),
+    if let Value::Array(items) = body {
+        let responses = match handle_batch(
+            &state,
+            principal.as_ref(),
+            credentials,
+            session.clone(),
+            items,
+        )
+        .instrument(request_span(principal.as_ref()))
+        .await
+        {
+            Ok(responses) => responses,
+            Err(_) => return StatusCode::BAD_REQUEST.into_response(),
         };
+        if let (Some(sess), Some(responses)) = (session, responses) {
+            send_to_session(&state.sessions, &state.sse_channels, &sess, responses).await;
+        }
+        return StatusCode::ACCEPTED.into_response();
+    }
 
-    if let (Some(sess), Some(resp)) = (session, maybe_resp)
-        && let Some(tx) = state.sse_channels.lock().await.get(&sess).cloned()
+    let (_new_sess, maybe_resp, _status, _) = match handle_jsonrpc(
+        &state,
+        principal.as_ref(),
+        credentials,
+        session.clone(),
+        body,
+    )
+    .instrument(request_span(principal.as_ref()))
+    .await
     {
-        let _ = tx.send(resp);
+        Ok(v) => v,
+        Err((_sc, _v)) => return StatusCode::BAD_REQUEST.into_response(),
+    };
+
+    if let (Some(sess), Some(resp)) = (session, maybe_resp) {
+        send_to_session(&state.sessions, &state.sse_channels, &sess, resp).await;
     }
 
     StatusCode::ACCEPTED.into_response()
 }
 
+/// Odoo credentials from the `X-Odoo-*` headers, when clients may pass them.
+fn request_credentials(
+    headers: &HeaderMap,
+    security: &SecurityConfig,
+) -> Result<Option<UserCredentials>, (StatusCode, Json<Value>)> {
+    if security.user_credentials == UserCredentialsMode::Off {
+        return Ok(None);
+    }
+    let header = |name: &HeaderName| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    };
+    UserCredentials::from_parts(
+        header(&ODOO_API_KEY),
+        header(&ODOO_LOGIN),
+        header(&ODOO_PASSWORD),
+    )
+    .map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": e.to_string()})),
+        )
+    })
+}
+
+/// Odoo credentials from the `odooCredentials` initialize param:
+/// `{"apiKey": ..., "login": ..., "password": ...}`.
+fn initialize_credentials(params: &Value) -> anyhow::Result<Option<UserCredentials>> {
+    let Some(given) = params.get("odooCredentials") else {
+        return Ok(None);
+    };
+    let field = |name: &str| given.get(name).and_then(|v| v.as_str()).map(str::to_string);
+    UserCredentials::from_parts(field("apiKey"), field("login"), field("password"))
+}
+
+/// Span for one JSON-RPC request, so its logs name the calling principal.
+fn request_span(principal: Option<&Principal>) -> tracing::Span {
+    match principal {
+        Some(principal) => tracing::info_span!("mcp_request", principal = %principal.name),
+        None => tracing::info_span!("mcp_request"),
+    }
+}
+
 trait ResponseExt {
     fn to_value(self) -> Value;
 }
@@ -1099,6 +2154,7 @@ mod tests {
     fn test_origin_validation_disabled() {
         let security = SecurityConfig {
             allowed_origins: None,
+            ..Default::default()
         };
         let headers = HeaderMap::new();
         assert!(validate_origin(&headers, &security).is_ok());
@@ -1108,6 +2164,7 @@ mod tests {
     fn test_origin_validation_localhost_only() {
         let security = SecurityConfig {
             allowed_origins: Some(vec![]),
+            ..Default::default()
         };
 
         // No origin - should pass
@@ -1134,6 +2191,7 @@ mod tests {
     fn test_origin_validation_with_allowed_list() {
         let security = SecurityConfig {
             allowed_origins: Some(vec!["https://example.com".to_string()]),
+            ..Default::default()
         };
 
         // Allowed origin - should pass
@@ -1188,36 +2246,35 @@ mod tests {
 
     #[tokio::test]
     async fn test_session_state_event_buffer() {
-        let state = SessionState::new("2025-03-26".to_string());
+        let mut state = SessionState::new("2025-03-26".to_string());
+        state.event_buffer_size = 3;
+        let tx = broadcast::channel(16).0;
 
-        // Store some events
-        state
-            .store_event(StoredEvent {
-                id: "s:0".to_string(),
-                data: json!({"msg": "first"}),
-            })
-            .await;
-        state
-            .store_event(StoredEvent {
-                id: "s:1".to_string(),
-                data: json!({"msg": "second"}),
-            })
-            .await;
-        state
-            .store_event(StoredEvent {
-                id: "s:2".to_string(),
-                data: json!({"msg": "third"}),
-            })
-            .await;
+        for n in 1..=4 {
+            state.publish("s", &tx, json!({"n": n})).await;
+        }
+
+        // Events after s:2 are replayed, and the stream is primed with the last id
+        let resume = state.resume("s", &tx, Some("s:2")).await;
+        assert!(!resume.gap);
+        assert_eq!(resume.last_id, "s:4");
+        let ids: Vec<_> = resume.events.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, ["s:3", "s:4"]);
+        assert_eq!(resume.events[1].data, json!({"n": 4}));
 
-        // Get events after s:0
-        let events = state.get_events_after("s:0").await;
-        assert_eq!(events.len(), 2);
-        assert_eq!(events[0].id, "s:1");
-        assert_eq!(events[1].id, "s:2");
+        // s:1 fell out of the buffer: a gap, with what is left
+        let resume = state.resume("s", &tx, Some("s:0")).await;
+        assert!(resume.gap);
+        assert_eq!(resume.events.len(), 3);
 
         // Get events after non-existent ID
-        let events = state.get_events_after("s:999").await;
-        assert!(events.is_empty());
+        assert!(state.resume("s", &tx, Some("s:999")).await.gap);
+        assert!(state.resume("s", &tx, Some("bogus")).await.gap);
+
+        // Later events reach the subscriber live
+        let mut resume = state.resume("s", &tx, Some("s:4")).await;
+        assert!(resume.events.is_empty());
+        state.publish("s", &tx, json!({"n": 5})).await;
+        assert_eq!(resume.receiver.recv().await.unwrap().id, "s:5");
     }
 }
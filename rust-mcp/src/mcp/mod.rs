@@ -1,11 +1,22 @@
+pub mod batch;
+pub mod budget;
 pub mod cache;
 pub mod cursor_stdio;
+pub mod domain;
+pub mod domain_parser;
+pub mod expand;
+pub mod format;
 pub mod http;
+pub mod oauth;
+pub mod principal;
+pub mod progress;
 pub mod prompts;
 pub mod registry;
 pub mod resources;
 pub mod runtime;
+pub mod subscriptions;
 pub mod tools;
+pub mod ws;
 
 use async_trait::async_trait;
 use mcp_rust_sdk::error::{Error, ErrorCode};
@@ -14,20 +25,155 @@ use mcp_rust_sdk::types::{ClientCapabilities, Implementation, ServerCapabilities
 use serde_json::{Value, json};
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::broadcast;
 
+use crate::mcp::principal::Principal;
 use crate::mcp::prompts::{get_prompt_result, list_prompts_result};
-use crate::mcp::registry::Registry;
-use crate::mcp::tools::{OdooClientPool, call_tool};
+use crate::mcp::registry::{Registry, RegistryChanges};
+use crate::mcp::resources::ResourceUri;
+use crate::mcp::subscriptions::{ResourceUpdate, Subscriptions};
+use crate::mcp::tools::{InstanceChanges, OdooClientPool, call_tool, tool_instance};
+use crate::metrics::{self, Outcome};
+use crate::odoo::config::UserCredentials;
+use crate::odoo::types::OdooError;
 
 #[derive(Clone)]
 pub struct McpOdooHandler {
     pool: OdooClientPool,
     registry: Arc<Registry>,
+    /// Server-initiated JSON-RPC notifications, fanned out by the transports.
+    notifications: broadcast::Sender<Value>,
+    subscriptions: Arc<Subscriptions>,
 }
 
 impl McpOdooHandler {
     pub fn new(pool: OdooClientPool, registry: Arc<Registry>) -> Self {
-        Self { pool, registry }
+        Self {
+            subscriptions: Arc::new(Subscriptions::new(pool.clone())),
+            pool,
+            registry,
+            notifications: broadcast::channel(64).0,
+        }
+    }
+
+    /// Watch the instances file and announce instance changes to clients as
+    /// `notifications/resources/list_changed` (the `odoo://instances` resource).
+    /// Reloads of the registry that change the tool or prompt list are announced
+    /// as `notifications/tools/list_changed` and `notifications/prompts/list_changed`.
+    pub fn start_watchers(self: &Arc<Self>) {
+        self.pool.start_watcher();
+        let mut changes = self.pool.subscribe_changes();
+        let handler = Arc::downgrade(self);
+        tokio::spawn(async move {
+            loop {
+                match changes.recv().await {
+                    Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+                let Some(handler) = handler.upgrade() else {
+                    break;
+                };
+                handler.notify("notifications/resources/list_changed", None);
+            }
+        });
+
+        let mut changes = self.registry.subscribe_changes();
+        let handler = Arc::downgrade(self);
+        tokio::spawn(async move {
+            loop {
+                let changes = match changes.recv().await {
+                    Ok(changes) => changes,
+                    // Some changes were missed: clients had better re-list both.
+                    Err(broadcast::error::RecvError::Lagged(_)) => RegistryChanges {
+                        tools: true,
+                        prompts: true,
+                    },
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                let Some(handler) = handler.upgrade() else {
+                    break;
+                };
+                if changes.tools {
+                    handler.notify("notifications/tools/list_changed", None);
+                }
+                if changes.prompts {
+                    handler.notify("notifications/prompts/list_changed", None);
+                }
+            }
+        });
+    }
+
+    /// Notifications sent to every connected client.
+    pub fn subscribe_notifications(&self) -> broadcast::Receiver<Value> {
+        self.notifications.subscribe()
+    }
+
+    /// Send a JSON-RPC notification to every connected client.
+    pub fn notify(&self, method: &str, params: Option<Value>) {
+        let mut notification = json!({ "jsonrpc": "2.0", "method": method });
+        if let Some(params) = params {
+            notification["params"] = params;
+        }
+        let _ = self.notifications.send(notification);
+    }
+
+    /// Changes of subscribed resources; each names the subscribers to send
+    /// `notifications/resources/updated` to.
+    pub fn subscribe_resource_updates(&self) -> broadcast::Receiver<ResourceUpdate> {
+        self.subscriptions.subscribe_updates()
+    }
+
+    /// Drop the resource subscriptions of an ended session.
+    pub fn drop_subscriber(&self, subscriber: &str) {
+        self.subscriptions.unsubscribe_all(subscriber);
+    }
+
+    /// Distinct resource URIs being polled for subscribers.
+    pub fn watched_resources(&self) -> usize {
+        self.subscriptions.watched_count()
+    }
+
+    /// `resources/subscribe` and `resources/unsubscribe` for `subscriber`, a
+    /// session of the transport. Subscribing reads the resource's fingerprint
+    /// as the caller, so unknown records and refused access fail right away.
+    pub async fn handle_subscription(
+        &self,
+        subscriber: &str,
+        principal: Option<&Principal>,
+        credentials: Option<&UserCredentials>,
+        method: &str,
+        params: Option<Value>,
+    ) -> Result<Value, Error> {
+        let uri = params
+            .as_ref()
+            .and_then(|p| p.get("uri"))
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| protocol_err(format!("{method} missing 'uri'")))?;
+        if method == "resources/unsubscribe" {
+            self.subscriptions.unsubscribe(uri, subscriber);
+            return Ok(json!({}));
+        }
+
+        let resource = ResourceUri::parse(uri).map_err(protocol_err)?;
+        if let Some(instance) = resource.instance()
+            && !allows_instance(principal, instance)
+        {
+            return Err(protocol_err(format!(
+                "Instance '{instance}' not permitted for this token"
+            )));
+        }
+        let current = resources::fingerprint(&self.pool_for(credentials), &resource).await?;
+        // Polls run as the service user, which may see the resource differently.
+        let baseline = credentials.is_none().then_some(current);
+        self.subscriptions
+            .subscribe(uri, resource, subscriber, baseline);
+        Ok(json!({}))
+    }
+
+    /// Re-read the instances configuration now (see [`OdooClientPool::reload`]).
+    pub async fn reload_instances(&self) -> anyhow::Result<InstanceChanges> {
+        self.pool.reload().await
     }
 
     pub fn instance_names(&self) -> Vec<String> {
@@ -45,43 +191,33 @@ impl McpOdooHandler {
     pub async fn protocol_version_default(&self) -> String {
         self.registry.protocol_version_default().await
     }
-}
-
-fn protocol_err(message: impl Into<String>) -> Error {
-    Error::protocol(ErrorCode::InvalidRequest, message)
-}
-
-#[async_trait]
-impl ServerHandler for McpOdooHandler {
-    async fn initialize(
-        &self,
-        _implementation: Implementation,
-        _capabilities: ClientCapabilities,
-    ) -> Result<ServerCapabilities, Error> {
-        // mcp_rust_sdk ServerCapabilities is currently "custom" only, so we advertise tools/prompts/resources in custom.
-        let mut custom = HashMap::new();
-        custom.insert("tools".to_string(), json!({}));
-        custom.insert("prompts".to_string(), json!({}));
-        custom.insert("resources".to_string(), json!({}));
-        custom.insert(
-            "odooInstances".to_string(),
-            json!({ "available": self.pool.instance_names() }),
-        );
-        Ok(ServerCapabilities {
-            custom: Some(custom),
-        })
-    }
 
-    async fn shutdown(&self) -> Result<(), Error> {
-        Ok(())
+    fn pool_for(&self, credentials: Option<&UserCredentials>) -> OdooClientPool {
+        match credentials {
+            Some(credentials) => self.pool.with_credentials(credentials.clone()),
+            None => self.pool.clone(),
+        }
     }
 
-    async fn handle_method(&self, method: &str, params: Option<Value>) -> Result<Value, Error> {
+    /// Handle a request on behalf of `principal`: tools, instances and
+    /// resources outside its scope are hidden or refused. `None` is unrestricted.
+    /// With `credentials`, Odoo is called as that user instead of the
+    /// configured service user.
+    pub async fn handle_method_as(
+        &self,
+        principal: Option<&Principal>,
+        credentials: Option<&UserCredentials>,
+        method: &str,
+        params: Option<Value>,
+    ) -> Result<Value, Error> {
         match method {
             "tools/list" => {
                 // Fully declarative: tools are served from tools.json (registry).
                 // Note: cleanup gating is handled by tool guards (e.g. requiresEnvTrue).
-                let tools = self.registry.list_tools().await;
+                let tools = self
+                    .registry
+                    .list_tools_where(|t| principal.is_none_or(|p| p.allows_tool(t)))
+                    .await;
                 Ok(json!({ "tools": tools }))
             }
             "tools/call" => {
@@ -108,15 +244,57 @@ impl ServerHandler for McpOdooHandler {
                     }));
                 };
 
-                match call_tool(&self.pool, &tool, args).await {
+                if let Some(principal) = principal {
+                    let denied = if !principal.allows_tool(&tool) {
+                        Some("Tool not permitted for this token")
+                    } else if tool_instance(&tool, &args)
+                        .is_some_and(|i| !principal.allows_instance(&i))
+                    {
+                        Some("Instance not permitted for this token")
+                    } else {
+                        None
+                    };
+                    if let Some(error) = denied {
+                        tracing::warn!(principal = %principal.name, tool = name, "{error}");
+                        return Ok(json!({
+                            "content": [{
+                                "type": "text",
+                                "text": serde_json::to_string_pretty(&json!({
+                                    "error": error,
+                                    "tool": name,
+                                })).unwrap_or_else(|_| "{\"error\":\"forbidden\"}".to_string())
+                            }],
+                            "isError": true
+                        }));
+                    }
+                }
+
+                // Label by configured instance only, so arbitrary input cannot grow
+                // the metric series.
+                let instance = args
+                    .get("instance")
+                    .and_then(|v| v.as_str())
+                    .filter(|i| self.pool.instance_names().iter().any(|n| n == i))
+                    .unwrap_or("unknown")
+                    .to_string();
+                let started = Instant::now();
+                let default_format = self.registry.default_format().await;
+                let pool = self.pool_for(credentials);
+                let result = call_tool(&pool, &tool, args, default_format).await;
+                let outcome = match &result {
+                    Ok(v) if v.get("isError").and_then(|e| e.as_bool()) != Some(true) => {
+                        Outcome::Success
+                    }
+                    _ => Outcome::Error,
+                };
+                metrics::global().record_tool_call(name, &instance, outcome, started.elapsed());
+                match result {
                     Ok(v) => Ok(v),
                     Err(e) => Ok(json!({
                         "content": [{
                             "type": "text",
-                            "text": serde_json::to_string_pretty(&json!({
-                                "error": e.to_string(),
-                                "tool": name,
-                            })).unwrap_or_else(|_| "{\"error\":\"unknown\"}".to_string())
+                            "text": serde_json::to_string_pretty(&tool_error_payload(name, &e))
+                                .unwrap_or_else(|_| "{\"error\":\"unknown\"}".to_string())
                         }],
                         "isError": true
                     })),
@@ -140,7 +318,9 @@ impl ServerHandler for McpOdooHandler {
                     .ok_or_else(|| protocol_err(format!("Unknown prompt: {name}")))?;
                 Ok(get_prompt_result(&p))
             }
-            "resources/list" => resources::list_resources(&self.pool).await,
+            "resources/list" => {
+                resources::list_resources_for(&self.pool, |i| allows_instance(principal, i)).await
+            }
             "resources/read" => {
                 let params =
                     params.ok_or_else(|| protocol_err("Missing params for resources/read"))?;
@@ -148,7 +328,13 @@ impl ServerHandler for McpOdooHandler {
                     .get("uri")
                     .and_then(|v| v.as_str())
                     .ok_or_else(|| protocol_err("resources/read missing 'uri'"))?;
-                resources::read_resource(&self.pool, uri).await
+                let pool = self.pool_for(credentials);
+                resources::read_resource_for(&pool, uri, |i| allows_instance(principal, i)).await
+            }
+            "resources/templates/list" => Ok(resources::list_resource_templates()),
+            // Updates are delivered per session: see `handle_subscription`.
+            "resources/subscribe" | "resources/unsubscribe" => {
+                Err(protocol_err("Resource subscriptions need a session"))
             }
             // MCP ping method for health check / keep-alive
             "ping" => Ok(json!({})),
@@ -157,6 +343,7 @@ impl ServerHandler for McpOdooHandler {
             "notifications/progress" => Ok(json!({})),
             "notifications/message" => Ok(json!({})),
             "notifications/resources/list_changed" => Ok(json!({})),
+            "notifications/resources/updated" => Ok(json!({})),
             "notifications/tools/list_changed" => Ok(json!({})),
             "notifications/prompts/list_changed" => Ok(json!({})),
             _ => Err(protocol_err(format!("Unknown method: {method}"))),
@@ -164,6 +351,74 @@ impl ServerHandler for McpOdooHandler {
     }
 }
 
+fn allows_instance(principal: Option<&Principal>, instance: &str) -> bool {
+    principal.is_none_or(|p| p.allows_instance(instance))
+}
+
+fn protocol_err(message: impl Into<String>) -> Error {
+    Error::protocol(ErrorCode::InvalidRequest, message)
+}
+
+/// Whether tool errors include the Odoo server traceback (ODOO_ERROR_DEBUG).
+fn error_debug_enabled() -> bool {
+    std::env::var("ODOO_ERROR_DEBUG")
+        .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+        .unwrap_or(false)
+}
+
+/// Body of an `isError` tool result: the error category, Odoo's own message and a
+/// remediation hint, plus the server traceback when debugging is enabled.
+fn tool_error_payload(tool: &str, err: &OdooError) -> Value {
+    let mut payload = json!({
+        "error": err.to_string(),
+        "tool": tool,
+        "category": err.category(),
+        "message": err.message(),
+    });
+    if let Some(hint) = err.hint() {
+        payload["hint"] = json!(hint);
+    }
+    if error_debug_enabled()
+        && let Some(traceback) = err.traceback()
+    {
+        payload["traceback"] = json!(traceback);
+    }
+    payload
+}
+
+#[async_trait]
+impl ServerHandler for McpOdooHandler {
+    async fn initialize(
+        &self,
+        _implementation: Implementation,
+        _capabilities: ClientCapabilities,
+    ) -> Result<ServerCapabilities, Error> {
+        // mcp_rust_sdk ServerCapabilities is currently "custom" only, so we advertise tools/prompts/resources in custom.
+        let mut custom = HashMap::new();
+        custom.insert("tools".to_string(), json!({}));
+        custom.insert("prompts".to_string(), json!({}));
+        custom.insert(
+            "resources".to_string(),
+            json!({ "listChanged": true, "subscribe": true }),
+        );
+        custom.insert(
+            "odooInstances".to_string(),
+            json!({ "available": self.pool.instance_names() }),
+        );
+        Ok(ServerCapabilities {
+            custom: Some(custom),
+        })
+    }
+
+    async fn shutdown(&self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    async fn handle_method(&self, method: &str, params: Option<Value>) -> Result<Value, Error> {
+        self.handle_method_as(None, None, method, params).await
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -175,6 +430,40 @@ mod tests {
         assert!(display.contains("test message"));
     }
 
+    #[test]
+    fn test_tool_error_payload_carries_category_and_hint() {
+        let err = OdooError::from_odoo(
+            403,
+            "You are not allowed to modify 'Contact' (res.partner) records.".to_string(),
+            None,
+        );
+        // No exception name: stays a generic API error.
+        let payload = tool_error_payload("odoo_update", &err);
+        assert_eq!(payload["category"], "api_error");
+        assert!(payload.get("hint").is_none());
+
+        let err = OdooError::Odoo {
+            kind: crate::odoo::types::OdooErrorKind::AccessError,
+            status: 403,
+            message: "You are not allowed to modify 'Contact' (res.partner) records.".to_string(),
+            body: None,
+        };
+        let payload = tool_error_payload("odoo_update", &err);
+        assert_eq!(payload["tool"], "odoo_update");
+        assert_eq!(payload["category"], "access_error");
+        assert_eq!(
+            payload["message"],
+            "You are not allowed to modify 'Contact' (res.partner) records."
+        );
+        assert!(
+            payload["hint"]
+                .as_str()
+                .unwrap()
+                .contains("odoo_check_access")
+        );
+        assert!(payload.get("traceback").is_none());
+    }
+
     #[test]
     fn test_protocol_err_has_invalid_request_code() {
         let err = protocol_err("test");
@@ -0,0 +1,499 @@
+//! OAuth 2.1 resource server for the HTTP transport.
+//!
+//! Access tokens are JWTs issued by `MCP_OAUTH_ISSUER` and verified against a
+//! JWKS read from `MCP_OAUTH_JWKS` (a file path or an `http(s)://` URL; URLs are
+//! re-fetched when a token names an unknown key). Audience, expiry and the
+//! scopes in `MCP_OAUTH_REQUIRED_SCOPES` are checked.
+//!
+//! Claims map onto a [`Principal`]:
+//!
+//! - the scopes `odoo:read`, `odoo:write` and `odoo:cleanup` grant tool
+//!   categories, `odoo:tool:<name>` a single tool;
+//! - the `odoo_instances` claim (array or space-separated globs; the name is set
+//!   by `MCP_OAUTH_INSTANCES_CLAIM`) limits instances, all when absent;
+//! - `sub` (or `client_id`) names the principal in logs.
+
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use jsonwebtoken::errors::ErrorKind;
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode, decode_header};
+use serde_json::{Map, Value, json};
+use tokio::sync::RwLock;
+
+use crate::mcp::principal::Principal;
+
+/// Scopes advertised in the protected resource metadata.
+pub const SCOPES_SUPPORTED: &[&str] = &["odoo:read", "odoo:write", "odoo:cleanup"];
+
+const TOOL_SCOPE_PREFIX: &str = "odoo:tool:";
+
+/// Minimum delay between two JWKS downloads triggered by unknown keys.
+const JWKS_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Clock skew tolerated on `exp` and `nbf`.
+const LEEWAY_SECS: u64 = 60;
+
+/// Why a bearer token was refused.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OAuthError {
+    /// Malformed, badly signed, expired or issued for someone else (401).
+    InvalidToken(String),
+    /// Valid token lacking required scopes (403).
+    InsufficientScope(String),
+    /// The JWKS could not be loaded (500).
+    Unavailable(String),
+}
+
+#[derive(Debug, Clone)]
+enum JwksSource {
+    File(String),
+    Url(String),
+}
+
+#[derive(Debug, Default)]
+struct JwksCache {
+    keys: Option<JwkSet>,
+    fetched_at: Option<Instant>,
+}
+
+/// Resource server settings and the verification keys.
+#[derive(Debug)]
+pub struct OAuthConfig {
+    pub issuer: String,
+    pub audience: String,
+    /// Public URL of the MCP endpoint (the protected resource).
+    pub resource: String,
+    pub required_scopes: Vec<String>,
+    pub instances_claim: String,
+    source: JwksSource,
+    cache: RwLock<JwksCache>,
+}
+
+impl OAuthConfig {
+    /// Read the settings from `MCP_OAUTH_*`; `None` when OAuth is not configured.
+    pub fn from_env() -> anyhow::Result<Option<Self>> {
+        let var = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        let Some(issuer) = var("MCP_OAUTH_ISSUER") else {
+            return Ok(None);
+        };
+        let jwks = var("MCP_OAUTH_JWKS")
+            .ok_or_else(|| anyhow::anyhow!("MCP_OAUTH_ISSUER is set but MCP_OAUTH_JWKS is not"))?;
+        let resource = var("MCP_OAUTH_RESOURCE").ok_or_else(|| {
+            anyhow::anyhow!("MCP_OAUTH_ISSUER is set but MCP_OAUTH_RESOURCE is not")
+        })?;
+        let audience = var("MCP_OAUTH_AUDIENCE").unwrap_or_else(|| resource.clone());
+        let required_scopes = var("MCP_OAUTH_REQUIRED_SCOPES")
+            .map(|s| split_list(&s))
+            .unwrap_or_default();
+        let instances_claim =
+            var("MCP_OAUTH_INSTANCES_CLAIM").unwrap_or_else(|| "odoo_instances".to_string());
+        Self::new(
+            issuer,
+            audience,
+            resource,
+            &jwks,
+            required_scopes,
+            instances_claim,
+        )
+        .map(Some)
+    }
+
+    /// `jwks` is a file path or an `http(s)://` URL. A file is read now.
+    pub fn new(
+        issuer: String,
+        audience: String,
+        resource: String,
+        jwks: &str,
+        required_scopes: Vec<String>,
+        instances_claim: String,
+    ) -> anyhow::Result<Self> {
+        url::Url::parse(&resource)
+            .map_err(|e| anyhow::anyhow!("Invalid MCP_OAUTH_RESOURCE '{resource}': {e}"))?;
+        let source = if jwks.starts_with("http://") || jwks.starts_with("https://") {
+            JwksSource::Url(jwks.to_string())
+        } else {
+            JwksSource::File(jwks.to_string())
+        };
+        let mut cache = JwksCache::default();
+        if let JwksSource::File(path) = &source {
+            cache.keys = Some(read_jwks_file(path)?);
+        }
+        Ok(Self {
+            issuer,
+            audience,
+            resource,
+            required_scopes,
+            instances_claim,
+            source,
+            cache: RwLock::new(cache),
+        })
+    }
+
+    /// URL of the protected resource metadata (RFC 9728): the well-known path
+    /// inserted between the resource's origin and its path.
+    pub fn metadata_url(&self) -> String {
+        let Ok(url) = url::Url::parse(&self.resource) else {
+            return self.resource.clone();
+        };
+        let path = url.path().trim_end_matches('/');
+        format!(
+            "{}/.well-known/oauth-protected-resource{path}",
+            url.origin().ascii_serialization()
+        )
+    }
+
+    /// Protected resource metadata document.
+    pub fn metadata(&self) -> Value {
+        json!({
+            "resource": self.resource,
+            "authorization_servers": [self.issuer],
+            "bearer_methods_supported": ["header"],
+            "scopes_supported": SCOPES_SUPPORTED,
+        })
+    }
+
+    /// Verify an access token and map its claims to a principal.
+    pub async fn authenticate(&self, token: &str) -> Result<Principal, OAuthError> {
+        let header = decode_header(token)
+            .map_err(|e| OAuthError::InvalidToken(format!("Malformed token: {e}")))?;
+        if matches!(
+            header.alg,
+            Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512
+        ) {
+            return Err(OAuthError::InvalidToken(
+                "Symmetric signatures are not accepted".to_string(),
+            ));
+        }
+        let key = self.decoding_key(header.kid.as_deref(), header.alg).await?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&self.issuer]);
+        validation.set_audience(&[&self.audience]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud"]);
+        validation.leeway = LEEWAY_SECS;
+        let claims = decode::<Map<String, Value>>(token, &key, &validation)
+            .map_err(|e| {
+                OAuthError::InvalidToken(match e.kind() {
+                    ErrorKind::ExpiredSignature => "The access token has expired".to_string(),
+                    ErrorKind::InvalidAudience => {
+                        "The access token audience is invalid".to_string()
+                    }
+                    ErrorKind::InvalidIssuer => "The access token issuer is invalid".to_string(),
+                    _ => format!("The access token is invalid: {e}"),
+                })
+            })?
+            .claims;
+
+        let scopes = scopes(&claims);
+        let missing: Vec<&str> = self
+            .required_scopes
+            .iter()
+            .filter(|s| !scopes.contains(s))
+            .map(String::as_str)
+            .collect();
+        if !missing.is_empty() {
+            return Err(OAuthError::InsufficientScope(missing.join(" ")));
+        }
+
+        Ok(self.principal(&claims, &scopes))
+    }
+
+    fn principal(&self, claims: &Map<String, Value>, scopes: &[String]) -> Principal {
+        let name = ["sub", "client_id"]
+            .iter()
+            .find_map(|c| claims.get(*c).and_then(|v| v.as_str()))
+            .unwrap_or("oauth");
+        let tools = scopes
+            .iter()
+            .filter_map(|scope| {
+                scope
+                    .strip_prefix(TOOL_SCOPE_PREFIX)
+                    .or_else(|| {
+                        SCOPES_SUPPORTED
+                            .contains(&scope.as_str())
+                            .then(|| scope.trim_start_matches("odoo:"))
+                    })
+                    .map(str::to_string)
+            })
+            .collect();
+        let instances = claims.get(&self.instances_claim).map(|v| match v {
+            Value::Array(items) => items
+                .iter()
+                .filter_map(|i| i.as_str().map(str::to_string))
+                .collect(),
+            Value::String(s) => split_list(s),
+            _ => Vec::new(),
+        });
+        let expires_at = claims
+            .get("exp")
+            .and_then(|v| v.as_i64())
+            .and_then(|exp| DateTime::<Utc>::from_timestamp(exp, 0));
+        Principal::new(name, instances, Some(tools), expires_at)
+    }
+
+    async fn decoding_key(
+        &self,
+        kid: Option<&str>,
+        alg: Algorithm,
+    ) -> Result<DecodingKey, OAuthError> {
+        if let Some(key) = self.find_key(kid, alg).await? {
+            return Ok(key);
+        }
+        // Keys rotate: fetch the set again, at most once per interval.
+        if let JwksSource::Url(url) = &self.source {
+            let stale = self
+                .cache
+                .read()
+                .await
+                .fetched_at
+                .is_none_or(|at| at.elapsed() >= JWKS_REFRESH_INTERVAL);
+            if stale {
+                self.fetch(url).await?;
+                if let Some(key) = self.find_key(kid, alg).await? {
+                    return Ok(key);
+                }
+            }
+        }
+        Err(OAuthError::InvalidToken(match kid {
+            Some(kid) => format!("Unknown signing key '{kid}'"),
+            None => "No signing key matches the token".to_string(),
+        }))
+    }
+
+    async fn find_key(
+        &self,
+        kid: Option<&str>,
+        alg: Algorithm,
+    ) -> Result<Option<DecodingKey>, OAuthError> {
+        if let JwksSource::Url(url) = &self.source
+            && self.cache.read().await.keys.is_none()
+        {
+            self.fetch(url).await?;
+        }
+        let cache = self.cache.read().await;
+        let Some(keys) = &cache.keys else {
+            return Ok(None);
+        };
+        let jwk = match kid {
+            Some(kid) => keys.find(kid),
+            // Without a kid, only an unambiguous set can be used.
+            None if keys.keys.len() == 1 => keys.keys.first(),
+            None => None,
+        };
+        let Some(jwk) = jwk else {
+            return Ok(None);
+        };
+        if let Some(key_alg) = jwk.common.key_algorithm
+            && key_alg.to_string() != format!("{alg:?}")
+        {
+            return Err(OAuthError::InvalidToken(format!(
+                "Token algorithm {alg:?} does not match its key"
+            )));
+        }
+        DecodingKey::from_jwk(jwk)
+            .map(Some)
+            .map_err(|e| OAuthError::InvalidToken(format!("Unusable signing key: {e}")))
+    }
+
+    async fn fetch(&self, url: &str) -> Result<(), OAuthError> {
+        let keys = fetch_jwks(url).await.map_err(|e| {
+            tracing::warn!("{e}");
+            OAuthError::Unavailable(e.to_string())
+        })?;
+        let mut cache = self.cache.write().await;
+        cache.keys = Some(keys);
+        cache.fetched_at = Some(Instant::now());
+        Ok(())
+    }
+}
+
+fn read_jwks_file(path: &str) -> anyhow::Result<JwkSet> {
+    let raw = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Cannot read JWKS '{path}': {e}"))?;
+    serde_json::from_str(&raw).map_err(|e| anyhow::anyhow!("Invalid JWKS '{path}': {e}"))
+}
+
+async fn fetch_jwks(url: &str) -> anyhow::Result<JwkSet> {
+    let response = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()?
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| anyhow::anyhow!("Cannot fetch JWKS '{url}': {e}"))?;
+    if !response.status().is_success() {
+        anyhow::bail!("Cannot fetch JWKS '{url}': HTTP {}", response.status());
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| anyhow::anyhow!("Invalid JWKS '{url}': {e}"))
+}
+
+/// Scopes of a token: the space-separated `scope` claim, or an `scp` array.
+fn scopes(claims: &Map<String, Value>) -> Vec<String> {
+    match (claims.get("scope"), claims.get("scp")) {
+        (Some(Value::String(scope)), _) => split_list(scope),
+        (_, Some(Value::Array(scp))) => scp
+            .iter()
+            .filter_map(|s| s.as_str().map(str::to_string))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn split_list(s: &str) -> Vec<String> {
+    s.split([' ', ','])
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::Engine;
+    use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+    use jsonwebtoken::{EncodingKey, Header, encode};
+    use ring::rand::SystemRandom;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    struct Keys {
+        encoding: EncodingKey,
+        jwk: Value,
+    }
+
+    fn keys(kid: &str) -> Keys {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        let pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        Keys {
+            encoding: EncodingKey::from_ed_der(pkcs8.as_ref()),
+            jwk: json!({
+                "kty": "OKP",
+                "crv": "Ed25519",
+                "alg": "EdDSA",
+                "kid": kid,
+                "x": URL_SAFE_NO_PAD.encode(pair.public_key().as_ref()),
+            }),
+        }
+    }
+
+    fn config(jwks: Value, required_scopes: &[&str]) -> (OAuthConfig, tempfile::TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("jwks.json");
+        std::fs::write(&path, jwks.to_string()).unwrap();
+        let config = OAuthConfig::new(
+            "https://auth.example.com".to_string(),
+            "https://mcp.example.com/mcp".to_string(),
+            "https://mcp.example.com/mcp".to_string(),
+            path.to_str().unwrap(),
+            required_scopes.iter().map(|s| s.to_string()).collect(),
+            "odoo_instances".to_string(),
+        )
+        .unwrap();
+        (config, dir)
+    }
+
+    fn token(keys: &Keys, kid: &str, claims: Value) -> String {
+        let mut header = Header::new(Algorithm::EdDSA);
+        header.kid = Some(kid.to_string());
+        encode(&header, &claims, &keys.encoding).unwrap()
+    }
+
+    fn claims(extra: Value) -> Value {
+        let mut claims = json!({
+            "iss": "https://auth.example.com",
+            "aud": "https://mcp.example.com/mcp",
+            "sub": "alice",
+            "exp": Utc::now().timestamp() + 300,
+        });
+        claims
+            .as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        claims
+    }
+
+    #[tokio::test]
+    async fn test_valid_token_maps_claims() {
+        let k = keys("k1");
+        let (config, _dir) = config(json!({"keys": [k.jwk.clone()]}), &["odoo:read"]);
+        let jwt = token(
+            &k,
+            "k1",
+            claims(json!({
+                "scope": "odoo:read odoo:tool:odoo_create",
+                "odoo_instances": ["prod-*"]
+            })),
+        );
+        let principal = config.authenticate(&jwt).await.unwrap();
+        assert_eq!(principal.name, "alice");
+        assert!(principal.allows_instance("prod-eu"));
+        assert!(!principal.allows_instance("staging"));
+        assert!(principal.expires_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_rejections() {
+        let k = keys("k1");
+        let (config, _dir) = config(json!({"keys": [k.jwk.clone()]}), &["odoo:read"]);
+
+        let expired = token(
+            &k,
+            "k1",
+            claims(json!({"exp": 1_000_000, "scope": "odoo:read"})),
+        );
+        assert_eq!(
+            config.authenticate(&expired).await.unwrap_err(),
+            OAuthError::InvalidToken("The access token has expired".to_string())
+        );
+
+        let wrong_aud = token(
+            &k,
+            "k1",
+            claims(json!({"aud": "https://other.example.com", "scope": "odoo:read"})),
+        );
+        assert!(matches!(
+            config.authenticate(&wrong_aud).await,
+            Err(OAuthError::InvalidToken(m)) if m.contains("audience")
+        ));
+
+        let no_scope = token(&k, "k1", claims(json!({"scope": "odoo:write"})));
+        assert_eq!(
+            config.authenticate(&no_scope).await.unwrap_err(),
+            OAuthError::InsufficientScope("odoo:read".to_string())
+        );
+
+        // Signed by a key that is not in the JWKS.
+        let other = keys("k1");
+        let forged = token(&other, "k1", claims(json!({"scope": "odoo:read"})));
+        assert!(matches!(
+            config.authenticate(&forged).await,
+            Err(OAuthError::InvalidToken(_))
+        ));
+
+        let unknown_kid = token(&k, "k2", claims(json!({"scope": "odoo:read"})));
+        assert!(matches!(
+            config.authenticate(&unknown_kid).await,
+            Err(OAuthError::InvalidToken(m)) if m.contains("k2")
+        ));
+    }
+
+    #[test]
+    fn test_metadata_url() {
+        let (config, _dir) = config(json!({"keys": []}), &[]);
+        assert_eq!(
+            config.metadata_url(),
+            "https://mcp.example.com/.well-known/oauth-protected-resource/mcp"
+        );
+        assert_eq!(config.metadata()["authorization_servers"][0], config.issuer);
+    }
+}
@@ -0,0 +1,302 @@
+//! Named principals for the HTTP transport.
+//!
+//! `MCP_AUTH_TOKENS_FILE` points to a JSON object of principals keyed by name:
+//!
+//! ```json
+//! {
+//!   "finance": {
+//!     "token": "env:MCP_TOKEN_FINANCE",
+//!     "instances": ["prod-*"],
+//!     "tools": ["read", "odoo_create"],
+//!     "expiresAt": "2026-12-31T23:59:59Z",
+//!     "readOnly": false
+//!   }
+//! }
+//! ```
+//!
+//! `instances` are globs (`*`, `?`); `tools` are tool names or the categories
+//! `read`, `write` and `cleanup`. Both default to everything. `readOnly` removes
+//! write and cleanup tools whatever `tools` says. Tokens accept the same secret
+//! references as instance credentials.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::mcp::registry::{ToolCategory, ToolDef};
+use crate::odoo::secrets;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct PrincipalEntry {
+    token: String,
+    #[serde(default)]
+    instances: Vec<String>,
+    #[serde(default)]
+    tools: Vec<String>,
+    #[serde(default)]
+    expires_at: Option<String>,
+    #[serde(default)]
+    read_only: bool,
+}
+
+/// A caller identified by its bearer token, and what it may use. `None` scopes
+/// are unrestricted.
+#[derive(Clone, PartialEq)]
+pub struct Principal {
+    pub name: String,
+    token: Option<String>,
+    instances: Option<Vec<String>>,
+    tools: Option<Vec<String>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub read_only: bool,
+}
+
+impl fmt::Debug for Principal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Principal")
+            .field("name", &self.name)
+            .field("instances", &self.instances)
+            .field("tools", &self.tools)
+            .field("expires_at", &self.expires_at)
+            .field("read_only", &self.read_only)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Principal {
+    /// A principal authenticated by other means than a static token (e.g. a JWT).
+    pub fn new(
+        name: impl Into<String>,
+        instances: Option<Vec<String>>,
+        tools: Option<Vec<String>>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            token: None,
+            instances,
+            tools,
+            expires_at,
+            read_only: false,
+        }
+    }
+
+    pub fn matches_token(&self, token: &str) -> bool {
+        self.token
+            .as_deref()
+            .is_some_and(|own| constant_time_eq(own.as_bytes(), token.as_bytes()))
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|at| at <= now)
+    }
+
+    pub fn allows_instance(&self, instance: &str) -> bool {
+        self.instances
+            .as_ref()
+            .is_none_or(|globs| globs.iter().any(|g| glob_match(g, instance)))
+    }
+
+    pub fn allows_tool(&self, tool: &ToolDef) -> bool {
+        let category = tool.category();
+        if self.read_only && category != ToolCategory::Read {
+            return false;
+        }
+        self.tools.as_ref().is_none_or(|tools| {
+            tools
+                .iter()
+                .any(|t| t == &tool.name || ToolCategory::parse(t) == Some(category))
+        })
+    }
+}
+
+/// Load the principals of a tokens file.
+pub fn load_principals(path: &Path) -> anyhow::Result<Vec<Principal>> {
+    let raw = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Cannot read tokens file '{}': {e}", path.display()))?;
+    let entries: HashMap<String, PrincipalEntry> = serde_json::from_str(&raw)
+        .map_err(|e| anyhow::anyhow!("Invalid tokens file '{}': {e}", path.display()))?;
+
+    let mut principals = Vec::with_capacity(entries.len());
+    for (name, entry) in entries {
+        let token = secrets::resolve(&entry.token)
+            .map_err(|e| anyhow::anyhow!("Cannot resolve token of principal '{name}': {e}"))?;
+        if token.trim().is_empty() {
+            anyhow::bail!("Principal '{name}' has an empty token");
+        }
+        for tool in &entry.tools {
+            if tool.trim().is_empty() {
+                anyhow::bail!("Principal '{name}' lists an empty tool name");
+            }
+        }
+        let expires_at = entry
+            .expires_at
+            .as_deref()
+            .map(|at| {
+                DateTime::parse_from_rfc3339(at)
+                    .map(|at| at.with_timezone(&Utc))
+                    .map_err(|e| {
+                        anyhow::anyhow!("Invalid expiresAt '{at}' of principal '{name}': {e}")
+                    })
+            })
+            .transpose()?;
+        principals.push(Principal {
+            name,
+            token: Some(token),
+            instances: (!entry.instances.is_empty()).then_some(entry.instances),
+            tools: (!entry.tools.is_empty()).then_some(entry.tools),
+            expires_at,
+            read_only: entry.read_only,
+        });
+    }
+    principals.sort_by(|a, b| a.name.cmp(&b.name));
+
+    for (i, principal) in principals.iter().enumerate() {
+        if let Some(other) = principals[i + 1..]
+            .iter()
+            .find(|p| p.token == principal.token)
+        {
+            anyhow::bail!(
+                "Principals '{}' and '{}' share the same token",
+                principal.name,
+                other.name
+            );
+        }
+    }
+    Ok(principals)
+}
+
+/// Glob match with `*` (any run) and `?` (one character).
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn tool(name: &str, op_type: &str) -> ToolDef {
+        serde_json::from_value(json!({
+            "name": name,
+            "description": "",
+            "inputSchema": {},
+            "op": {"type": op_type}
+        }))
+        .unwrap()
+    }
+
+    fn load(config: serde_json::Value) -> anyhow::Result<Vec<Principal>> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tokens.json");
+        std::fs::write(&path, config.to_string()).unwrap();
+        load_principals(&path)
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("prod-*", "prod-eu"));
+        assert!(glob_match("*", "anything"));
+        assert!(glob_match("stag?ng", "staging"));
+        assert!(glob_match("*-eu-*", "prod-eu-2"));
+        assert!(!glob_match("prod-*", "staging"));
+        assert!(!glob_match("prod", "prod-eu"));
+    }
+
+    #[test]
+    fn test_tool_scopes() {
+        let principals = load(json!({
+            "finance": {"token": "t1", "instances": ["prod-*"], "tools": ["read", "odoo_create"]},
+            "auditor": {"token": "t2", "readOnly": true},
+            "ops": {"token": "t3", "tools": ["cleanup"]}
+        }))
+        .unwrap();
+        let [auditor, finance, ops] = principals.as_slice() else {
+            panic!("expected three principals");
+        };
+
+        let search = tool("odoo_search", "search");
+        let create = tool("odoo_create", "create");
+        let update = tool("odoo_update", "write");
+        let cleanup = tool("odoo_database_cleanup", "database_cleanup");
+
+        assert!(finance.allows_tool(&search));
+        assert!(finance.allows_tool(&create));
+        assert!(!finance.allows_tool(&update));
+        assert!(finance.allows_instance("prod-eu"));
+        assert!(!finance.allows_instance("staging"));
+
+        assert!(auditor.allows_tool(&search));
+        assert!(!auditor.allows_tool(&create));
+        assert!(!auditor.allows_tool(&cleanup));
+        assert!(auditor.allows_instance("staging"));
+
+        assert!(ops.allows_tool(&cleanup));
+        assert!(!ops.allows_tool(&search));
+
+        // An op type without a known category may write.
+        let custom = tool("odoo_custom", "custom_op");
+        assert_eq!(custom.category(), ToolCategory::Write);
+        assert!(!auditor.allows_tool(&custom));
+        assert!(!finance.allows_tool(&custom));
+    }
+
+    #[test]
+    fn test_expiry_and_token_match() {
+        let principals = load(json!({
+            "temp": {"token": "t1", "expiresAt": "2020-01-01T00:00:00Z"}
+        }))
+        .unwrap();
+        assert!(principals[0].is_expired(Utc::now()));
+        assert!(principals[0].matches_token("t1"));
+        assert!(!principals[0].matches_token("t2"));
+        assert!(!format!("{:?}", principals[0]).contains("t1"));
+    }
+
+    #[test]
+    fn test_invalid_files_rejected() {
+        let err = load(json!({"a": {"token": "t", "expiresAt": "tomorrow"}})).unwrap_err();
+        assert!(err.to_string().contains("Invalid expiresAt"));
+
+        let err = load(json!({"a": {"token": "t"}, "b": {"token": "t"}})).unwrap_err();
+        assert!(err.to_string().contains("share the same token"));
+
+        let err = load(json!({"a": {"token": "t", "instance": ["x"]}})).unwrap_err();
+        assert!(err.to_string().contains("Invalid tokens file"));
+
+        let err = load(json!({"a": {"token": "env:PRINCIPAL_TEST_UNSET"}})).unwrap_err();
+        assert!(err.to_string().contains("token of principal 'a'"));
+    }
+}
@@ -0,0 +1,157 @@
+//! Progress and log notifications about the request being handled.
+//!
+//! A transport that can stream them back (POST /mcp upgraded to SSE) runs the
+//! request inside [`scope`]; ops then call [`progress`] and [`log`]. Outside a
+//! scope both do nothing, so ops need not know which transport called them.
+//! Retries of the Odoo clients are logged as warnings within a scope.
+
+use std::future::Future;
+use std::sync::Arc;
+
+use serde_json::{Value, json};
+use tokio::sync::mpsc;
+
+use crate::odoo::retry::{self, RetryEvent};
+
+tokio::task_local! {
+    static CURRENT: Reporter;
+}
+
+/// Where the notifications of one request go.
+#[derive(Clone)]
+pub struct Reporter {
+    tx: mpsc::UnboundedSender<Value>,
+    /// `params._meta.progressToken` of the request; progress is only sent
+    /// when the client asked for it.
+    progress_token: Option<Value>,
+}
+
+impl Reporter {
+    pub fn new(progress_token: Option<Value>) -> (Self, mpsc::UnboundedReceiver<Value>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        (Self { tx, progress_token }, rx)
+    }
+
+    fn send(&self, method: &str, params: Value) {
+        let _ = self.tx.send(json!({
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": params,
+        }));
+    }
+}
+
+/// Run `fut` with `reporter` receiving its notifications. The receiver ends
+/// once `fut` completes and the reporter is dropped.
+pub async fn scope<F: Future>(reporter: Reporter, fut: F) -> F::Output {
+    CURRENT
+        .scope(reporter, retry::observe(Arc::new(log_retry), fut))
+        .await
+}
+
+fn log_retry(event: &RetryEvent) {
+    log(
+        "warning",
+        json!({
+            "message": format!("Odoo request failed; retrying in {}ms", event.wait.as_millis()),
+            "instance": event.instance,
+            "attempt": event.attempt,
+            "error": event.error.map(|e| e.to_string()),
+        }),
+    );
+}
+
+/// The `_meta.progressToken` of request params, set when the client wants progress.
+pub fn progress_token(params: Option<&Value>) -> Option<Value> {
+    params?
+        .get("_meta")?
+        .get("progressToken")
+        .filter(|t| t.is_string() || t.is_number())
+        .cloned()
+}
+
+/// Report `progress` (out of `total`, if known) for the current request.
+pub fn progress(progress: u64, total: Option<u64>, message: Option<String>) {
+    let _ = CURRENT.try_with(|reporter| {
+        let Some(token) = &reporter.progress_token else {
+            return;
+        };
+        let mut params = json!({ "progressToken": token, "progress": progress });
+        if let Some(total) = total {
+            params["total"] = json!(total);
+        }
+        if let Some(message) = message {
+            params["message"] = json!(message);
+        }
+        reporter.send("notifications/progress", params);
+    });
+}
+
+/// Send a log message (`notifications/message`) about the current request.
+/// `level` is an MCP log level: `debug`, `info`, `notice`, `warning`, `error`, ...
+pub fn log(level: &str, data: Value) {
+    let _ = CURRENT.try_with(|reporter| {
+        reporter.send(
+            "notifications/message",
+            json!({ "level": level, "logger": "odoo", "data": data }),
+        );
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_notifications_reach_scope_only() {
+        // No scope: nothing to send to, nothing happens.
+        progress(1, Some(2), None);
+        log("info", json!("ignored"));
+
+        let (reporter, mut rx) = Reporter::new(Some(json!("tok")));
+        scope(reporter, async {
+            progress(1, Some(2), Some("half".to_string()));
+            log("warning", json!({"retry": 1}));
+        })
+        .await;
+
+        let first = rx.recv().await.unwrap();
+        assert_eq!(first["method"], "notifications/progress");
+        assert_eq!(
+            first["params"],
+            json!({"progressToken": "tok", "progress": 1, "total": 2, "message": "half"})
+        );
+        let second = rx.recv().await.unwrap();
+        assert_eq!(second["method"], "notifications/message");
+        assert_eq!(second["params"]["level"], "warning");
+        assert!(rx.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_retries_logged_as_warnings() {
+        let (reporter, mut rx) = Reporter::new(None);
+        scope(reporter, async {
+            retry::report("prod", None, 1, std::time::Duration::from_millis(250));
+        })
+        .await;
+        let logged = rx.recv().await.unwrap();
+        assert_eq!(logged["params"]["level"], "warning");
+        assert_eq!(logged["params"]["data"]["instance"], "prod");
+        assert_eq!(logged["params"]["data"]["attempt"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_progress_needs_token() {
+        let (reporter, mut rx) = Reporter::new(None);
+        scope(reporter, async { progress(1, None, None) }).await;
+        assert!(rx.recv().await.is_none());
+    }
+
+    #[test]
+    fn test_progress_token_from_meta() {
+        let params = json!({"name": "x", "_meta": {"progressToken": 7}});
+        assert_eq!(progress_token(Some(&params)), Some(json!(7)));
+        assert_eq!(progress_token(Some(&json!({}))), None);
+        assert_eq!(progress_token(None), None);
+    }
+}
@@ -5,10 +5,13 @@ use std::sync::{Arc, Mutex};
 use notify::{RecommendedWatcher, RecursiveMode, Watcher};
 use serde::Deserialize;
 use serde_json::Value;
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, broadcast};
 use tracing::{debug, info, warn};
 
+use crate::mcp::budget::ResponseBudget;
+use crate::mcp::format::OutputFormat;
 use crate::mcp::prompts::Prompt;
+use crate::metrics;
 
 // Embedded seed defaults (used when target files are missing).
 const DEFAULT_TOOLS_JSON: &str = include_str!("../../config-defaults/tools.json");
@@ -32,6 +35,9 @@ struct ServerConfigFile {
     instructions: String,
     #[serde(rename = "protocolVersionDefault")]
     protocol_version_default: Option<String>,
+    /// Default `format` for read-type tools (json, json-compact, markdown-table, csv).
+    #[serde(rename = "defaultFormat", default)]
+    default_format: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -43,6 +49,42 @@ pub struct ToolDef {
     pub op: OpSpec,
     #[serde(default)]
     pub guards: Option<ToolGuards>,
+    /// Per-tool response size limit; falls back to the global budget.
+    #[serde(rename = "responseBudget", default)]
+    pub response_budget: Option<ResponseBudget>,
+}
+
+/// Coarse kind of a tool, derived from its op type. Op types not known to
+/// only read count as writes, so read-only scopes never reach them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolCategory {
+    Read,
+    Write,
+    Cleanup,
+}
+
+impl ToolCategory {
+    /// Parse a category name (`read`, `write`, `cleanup`).
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "read" => Some(Self::Read),
+            "write" => Some(Self::Write),
+            "cleanup" => Some(Self::Cleanup),
+            _ => None,
+        }
+    }
+}
+
+impl ToolDef {
+    pub fn category(&self) -> ToolCategory {
+        match self.op.op_type.as_str() {
+            "search" | "search_read" | "read" | "search_count" | "read_group" | "name_search"
+            | "name_get" | "default_get" | "onchange" | "check_access" | "generate_report"
+            | "get_model_metadata" | "list_models" | "validate_domain" => ToolCategory::Read,
+            "database_cleanup" | "deep_cleanup" => ToolCategory::Cleanup,
+            _ => ToolCategory::Write,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -80,17 +122,41 @@ impl RegistryState {
                 server_name: "odoo-rust-mcp".to_string(),
                 instructions: "Odoo MCP server".to_string(),
                 protocol_version_default: Some("2025-11-05".to_string()),
+                default_format: None,
             },
         }
     }
 }
 
+/// Which lists a reload changed, as clients see them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RegistryChanges {
+    pub tools: bool,
+    pub prompts: bool,
+}
+
+impl RegistryChanges {
+    pub fn is_empty(&self) -> bool {
+        !self.tools && !self.prompts
+    }
+}
+
+/// The tools and prompts clients were last shown: enabled tools as listed, and
+/// prompts with their content.
+#[derive(Default)]
+struct Listed {
+    tools: Vec<Value>,
+    prompts: Vec<Value>,
+}
+
 pub struct Registry {
     tools_path: PathBuf,
     prompts_path: PathBuf,
     server_path: PathBuf,
     state: RwLock<RegistryState>,
     watchers: Mutex<Option<WatchGuards>>,
+    listed: Mutex<Listed>,
+    changes: broadcast::Sender<RegistryChanges>,
 }
 
 struct WatchGuards {
@@ -112,13 +178,15 @@ impl Registry {
             server_path: PathBuf::from(server_path),
             state: RwLock::new(RegistryState::empty()),
             watchers: Mutex::new(None),
+            listed: Mutex::new(Listed::default()),
+            changes: broadcast::channel(16).0,
         }
     }
 
     /// Ensure JSON files exist (seed defaults on first start), then load into memory.
     pub async fn initial_load(&self) -> anyhow::Result<()> {
         self.ensure_default_files_exist()?;
-        self.reload().await
+        self.reload().await.map(|_| ())
     }
 
     /// Start file watcher(s) that reload config automatically.
@@ -203,11 +271,27 @@ impl Registry {
             .unwrap_or_else(|| "2025-11-05".to_string())
     }
 
+    pub async fn default_format(&self) -> OutputFormat {
+        self.state
+            .read()
+            .await
+            .server
+            .default_format
+            .as_deref()
+            .and_then(|f| f.parse().ok())
+            .unwrap_or_default()
+    }
+
     pub async fn list_tools(&self) -> Vec<Value> {
+        self.list_tools_where(|_| true).await
+    }
+
+    /// Enabled tools that also pass `allow`.
+    pub async fn list_tools_where(&self, allow: impl Fn(&ToolDef) -> bool) -> Vec<Value> {
         let st = self.state.read().await;
         st.tools
             .iter()
-            .filter(|t| guards_allow(t.guards.as_ref()))
+            .filter(|t| guards_allow(t.guards.as_ref()) && allow(t))
             .map(|t| {
                 serde_json::json!({
                     "name": t.name,
@@ -241,7 +325,54 @@ impl Registry {
         st.prompts_by_name.get(name).cloned()
     }
 
-    pub async fn reload(&self) -> anyhow::Result<()> {
+    /// Re-read the config files and compare the lists clients see with those
+    /// of the previous reload: an edit that changes nothing visible is no
+    /// change, a guard variable set since then is one. Changes are returned and
+    /// sent to [`subscribe_changes`](Self::subscribe_changes).
+    pub async fn reload(&self) -> anyhow::Result<RegistryChanges> {
+        let result = self.load().await;
+        metrics::global().record_registry_reload(result.is_ok());
+        result?;
+
+        let listed = Listed {
+            tools: self.list_tools().await,
+            prompts: self.listed_prompts().await,
+        };
+        let changes = {
+            let mut previous = self.listed.lock().unwrap_or_else(|e| e.into_inner());
+            let changes = RegistryChanges {
+                tools: previous.tools != listed.tools,
+                prompts: previous.prompts != listed.prompts,
+            };
+            *previous = listed;
+            changes
+        };
+        if !changes.is_empty() {
+            info!(
+                tools = changes.tools,
+                prompts = changes.prompts,
+                "tool or prompt list changed"
+            );
+            let _ = self.changes.send(changes);
+        }
+        Ok(changes)
+    }
+
+    /// Changes of the tool and prompt lists, one per reload that changed them.
+    pub fn subscribe_changes(&self) -> broadcast::Receiver<RegistryChanges> {
+        self.changes.subscribe()
+    }
+
+    async fn listed_prompts(&self) -> Vec<Value> {
+        let st = self.state.read().await;
+        st.prompt_order
+            .iter()
+            .filter_map(|name| st.prompts_by_name.get(name))
+            .map(|p| serde_json::to_value(p).unwrap_or_default())
+            .collect()
+    }
+
+    async fn load(&self) -> anyhow::Result<()> {
         self.ensure_default_files_exist()?;
 
         let tools = load_tools_file(&self.tools_path)?;
@@ -351,6 +482,11 @@ fn load_server_file(path: &Path) -> anyhow::Result<ServerConfigFile> {
     let raw = std::fs::read_to_string(path).map_err(|e| anyhow::anyhow!(e))?;
     let parsed: ServerConfigFile =
         serde_json::from_str(&raw).map_err(|e| anyhow::anyhow!("Invalid server.json: {e}"))?;
+    if let Some(format) = &parsed.default_format {
+        format
+            .parse::<OutputFormat>()
+            .map_err(|e| anyhow::anyhow!("Invalid server.json defaultFormat: {e}"))?;
+    }
     Ok(parsed)
 }
 
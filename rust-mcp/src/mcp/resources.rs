@@ -12,13 +12,34 @@ fn resource_err(message: impl Into<String>) -> Error {
 /// - odoo://instances - List all configured instances
 /// - odoo://{instance}/models - List models for an instance
 /// - odoo://{instance}/metadata/{model} - Get model metadata
+/// - odoo://{instance}/record/{model}/{id} - Read one record (subscribable)
+/// - odoo://{instance}/search/{model}?domain=[...] - Records matching a saved
+///   search; `domain` is URL-encoded JSON (subscribable)
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ResourceUri {
     Instances,
-    Models { instance: String },
-    Metadata { instance: String, model: String },
+    Models {
+        instance: String,
+    },
+    Metadata {
+        instance: String,
+        model: String,
+    },
+    Record {
+        instance: String,
+        model: String,
+        id: i64,
+    },
+    Search {
+        instance: String,
+        model: String,
+        domain: Value,
+    },
 }
 
+/// Records returned by reading a search resource.
+const SEARCH_RESOURCE_LIMIT: i64 = 80;
+
 impl ResourceUri {
     /// Parse an odoo:// URI into a ResourceUri
     pub fn parse(uri: &str) -> Result<Self, String> {
@@ -31,6 +52,20 @@ impl ResourceUri {
 
         let path = &uri[7..]; // Remove "odoo://"
 
+        // Only searches take parameters.
+        if let Some((path, query)) = path.split_once('?') {
+            return match path.split('/').collect::<Vec<_>>().as_slice() {
+                [instance, "search", model] if !instance.is_empty() && !model.is_empty() => {
+                    Ok(ResourceUri::Search {
+                        instance: instance.to_string(),
+                        model: model.to_string(),
+                        domain: parse_search_query(query)?,
+                    })
+                }
+                _ => Err(format!("Invalid resource URI: {}", uri)),
+            };
+        }
+
         if path == "instances" {
             return Ok(ResourceUri::Instances);
         }
@@ -53,6 +88,31 @@ impl ResourceUri {
                     } else {
                         Err(format!("Invalid models URI: {}", uri))
                     }
+                } else if let Some(record) = rest.strip_prefix("record/") {
+                    let (model, id) = record
+                        .rsplit_once('/')
+                        .filter(|(model, _)| !model.is_empty())
+                        .ok_or_else(|| format!("Invalid record URI: {}", uri))?;
+                    let id = id
+                        .parse::<i64>()
+                        .ok()
+                        .filter(|id| *id > 0)
+                        .ok_or_else(|| format!("Invalid record id in URI: {}", uri))?;
+                    Ok(ResourceUri::Record {
+                        instance: instance.to_string(),
+                        model: model.to_string(),
+                        id,
+                    })
+                } else if let Some(model) = rest.strip_prefix("search/") {
+                    if model.is_empty() || model.contains('/') {
+                        Err(format!("Invalid search URI: {}", uri))
+                    } else {
+                        Ok(ResourceUri::Search {
+                            instance: instance.to_string(),
+                            model: model.to_string(),
+                            domain: json!([]),
+                        })
+                    }
                 } else if let Some(model) = rest.strip_prefix("metadata/") {
                     if model.is_empty() {
                         Err("Invalid metadata URI: missing model name".to_string())
@@ -78,12 +138,83 @@ impl ResourceUri {
             ResourceUri::Metadata { instance, model } => {
                 format!("odoo://{}/metadata/{}", instance, model)
             }
+            ResourceUri::Record {
+                instance,
+                model,
+                id,
+            } => format!("odoo://{}/record/{}/{}", instance, model, id),
+            ResourceUri::Search {
+                instance,
+                model,
+                domain,
+            } => {
+                let query: String = url::form_urlencoded::Serializer::new(String::new())
+                    .append_pair("domain", &domain.to_string())
+                    .finish();
+                format!("odoo://{}/search/{}?{}", instance, model, query)
+            }
+        }
+    }
+
+    /// The instance the resource belongs to (`None` for `odoo://instances`).
+    pub fn instance(&self) -> Option<&str> {
+        match self {
+            ResourceUri::Instances => None,
+            ResourceUri::Models { instance }
+            | ResourceUri::Metadata { instance, .. }
+            | ResourceUri::Record { instance, .. }
+            | ResourceUri::Search { instance, .. } => Some(instance),
+        }
+    }
+}
+
+/// The `domain` of a search URI query (`domain=<URL-encoded JSON list>`).
+fn parse_search_query(query: &str) -> Result<Value, String> {
+    let mut domain = json!([]);
+    for (key, value) in url::form_urlencoded::parse(query.as_bytes()) {
+        match key.as_ref() {
+            "domain" => {
+                domain = serde_json::from_str(&value)
+                    .ok()
+                    .filter(Value::is_array)
+                    .ok_or_else(|| format!("Invalid search domain: {}", value))?;
+            }
+            other => return Err(format!("Unknown search URI parameter: {}", other)),
         }
     }
+    Ok(domain)
+}
+
+/// URI templates of the resources addressed by model and id or domain.
+pub fn list_resource_templates() -> Value {
+    json!({
+        "resourceTemplates": [
+            {
+                "uriTemplate": "odoo://{instance}/record/{model}/{id}",
+                "name": "Odoo record",
+                "description": "One record with its non-binary fields. Subscribe to be told when it is written.",
+                "mimeType": "application/json"
+            },
+            {
+                "uriTemplate": "odoo://{instance}/search/{model}?domain={domain}",
+                "name": "Odoo saved search",
+                "description": "Records matching a domain (URL-encoded JSON list). Subscribe to be told when a match is added, removed or written.",
+                "mimeType": "application/json"
+            }
+        ]
+    })
 }
 
 /// List all available resources
 pub async fn list_resources(pool: &OdooClientPool) -> Result<Value, Error> {
+    list_resources_for(pool, |_| true).await
+}
+
+/// List the resources of the instances `allow` accepts.
+pub async fn list_resources_for(
+    pool: &OdooClientPool,
+    allow: impl Fn(&str) -> bool,
+) -> Result<Value, Error> {
     let mut resources = vec![];
 
     // Root resource: list of instances
@@ -95,7 +226,7 @@ pub async fn list_resources(pool: &OdooClientPool) -> Result<Value, Error> {
     }));
 
     // Per-instance resources: models
-    for instance in pool.instance_names() {
+    for instance in pool.instance_names().into_iter().filter(|i| allow(i)) {
         resources.push(json!({
             "uri": format!("odoo://{}/models", instance),
             "name": format!("Models in {}", instance),
@@ -111,26 +242,120 @@ pub async fn list_resources(pool: &OdooClientPool) -> Result<Value, Error> {
 
 /// Read a specific resource by URI
 pub async fn read_resource(pool: &OdooClientPool, uri: &str) -> Result<Value, Error> {
+    read_resource_for(pool, uri, |_| true).await
+}
+
+/// Read a resource, restricted to the instances `allow` accepts.
+pub async fn read_resource_for(
+    pool: &OdooClientPool,
+    uri: &str,
+    allow: impl Fn(&str) -> bool,
+) -> Result<Value, Error> {
     let resource = ResourceUri::parse(uri).map_err(resource_err)?;
+    if let Some(instance) = resource.instance()
+        && !allow(instance)
+    {
+        return Err(resource_err(format!(
+            "Access to instance '{instance}' is not permitted"
+        )));
+    }
 
     match resource {
-        ResourceUri::Instances => read_instances(pool).await,
+        ResourceUri::Instances => read_instances(pool, &allow).await,
         ResourceUri::Models { instance } => read_models(pool, &instance).await,
         ResourceUri::Metadata { instance, model } => read_metadata(pool, &instance, &model).await,
+        ResourceUri::Record {
+            instance,
+            model,
+            id,
+        } => read_record(pool, uri, &instance, &model, id).await,
+        ResourceUri::Search {
+            instance,
+            model,
+            domain,
+        } => read_search(pool, uri, &instance, &model, domain).await,
     }
 }
 
+/// What changes when a subscribable resource does: the `write_date` of a
+/// record (null once deleted), or the count and latest `write_date` of the
+/// records a search matches. Other resources cannot be watched.
+pub async fn fingerprint(pool: &OdooClientPool, resource: &ResourceUri) -> Result<Value, Error> {
+    let (instance, model, domain, context) = match resource {
+        ResourceUri::Record {
+            instance,
+            model,
+            id,
+        } => (
+            instance,
+            model,
+            json!([["id", "=", id]]),
+            // Archiving a record is a change too.
+            Some(json!({"active_test": false})),
+        ),
+        ResourceUri::Search {
+            instance,
+            model,
+            domain,
+        } => (instance, model, domain.clone(), None),
+        _ => {
+            return Err(resource_err(format!(
+                "Resource '{}' does not support subscriptions",
+                resource.to_uri()
+            )));
+        }
+    };
+    let client = pool
+        .get(instance)
+        .await
+        .map_err(|e| resource_err(e.to_string()))?;
+    let latest = client
+        .search_read(
+            model,
+            Some(domain.clone()),
+            Some(vec!["write_date".to_string()]),
+            Some(1),
+            None,
+            Some("write_date desc".to_string()),
+            context.clone(),
+        )
+        .await
+        .map_err(|e| resource_err(e.to_string()))?;
+    let write_date = latest
+        .get(0)
+        .and_then(|r| r.get("write_date"))
+        .cloned()
+        .unwrap_or(Value::Null);
+    if let ResourceUri::Record { .. } = resource {
+        return Ok(write_date);
+    }
+    let count = client
+        .search_count(model, Some(domain), context)
+        .await
+        .map_err(|e| resource_err(e.to_string()))?;
+    Ok(json!({ "count": count, "writeDate": write_date }))
+}
+
 /// Read the list of instances
-async fn read_instances(pool: &OdooClientPool) -> Result<Value, Error> {
-    let instances = pool.instance_names();
-    let instance_list: Vec<Value> = instances
-        .iter()
-        .map(|name| {
-            json!({
-                "name": name,
-            })
-        })
-        .collect();
+/// Version and API are reported once the instance has been used (detection runs
+/// when its client is created).
+async fn read_instances(
+    pool: &OdooClientPool,
+    allow: &impl Fn(&str) -> bool,
+) -> Result<Value, Error> {
+    let mut instances = pool.instance_names();
+    instances.retain(|i| allow(i));
+    instances.sort();
+    let mut instance_list = Vec::with_capacity(instances.len());
+    for name in &instances {
+        let api = pool.cached(name).await.map(|client| client.api());
+        instance_list.push(json!({
+            "name": name,
+            "configured_version": pool.configured_version(name),
+            "server_version": pool.detected_version(name),
+            "api": api,
+        }));
+    }
 
     Ok(json!({
         "contents": [{
@@ -226,10 +451,131 @@ async fn read_metadata(pool: &OdooClientPool, instance: &str, model: &str) -> Re
     }))
 }
 
+/// Read one record, without its binary fields (images and attachments)
+async fn read_record(
+    pool: &OdooClientPool,
+    uri: &str,
+    instance: &str,
+    model: &str,
+    id: i64,
+) -> Result<Value, Error> {
+    let client = pool
+        .get(instance)
+        .await
+        .map_err(|e| resource_err(e.to_string()))?;
+
+    let fields = client
+        .fields_get(model, None)
+        .await
+        .map_err(|e| resource_err(e.to_string()))?;
+    let names = fields
+        .as_object()
+        .map(|fields| {
+            fields
+                .iter()
+                .filter(|(_, f)| f.get("type").and_then(|t| t.as_str()) != Some("binary"))
+                .map(|(name, _)| name.clone())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    let records = client
+        .read(model, vec![id], Some(names), None)
+        .await
+        .map_err(|e| resource_err(e.to_string()))?;
+    let record = records
+        .get(0)
+        .cloned()
+        .ok_or_else(|| resource_err(format!("Record {model},{id} not found")))?;
+
+    Ok(json!({
+        "contents": [{
+            "uri": uri,
+            "mimeType": "application/json",
+            "text": serde_json::to_string_pretty(&record)
+                .unwrap_or_else(|_| "{}".to_string())
+        }]
+    }))
+}
+
+/// Read the records matching a saved search
+async fn read_search(
+    pool: &OdooClientPool,
+    uri: &str,
+    instance: &str,
+    model: &str,
+    domain: Value,
+) -> Result<Value, Error> {
+    let client = pool
+        .get(instance)
+        .await
+        .map_err(|e| resource_err(e.to_string()))?;
+
+    let records = client
+        .search_read(
+            model,
+            Some(domain),
+            Some(vec!["display_name".to_string(), "write_date".to_string()]),
+            Some(SEARCH_RESOURCE_LIMIT),
+            None,
+            None,
+            None,
+        )
+        .await
+        .map_err(|e| resource_err(e.to_string()))?;
+
+    Ok(json!({
+        "contents": [{
+            "uri": uri,
+            "mimeType": "application/json",
+            "text": serde_json::to_string_pretty(&records)
+                .unwrap_or_else(|_| "[]".to_string())
+        }]
+    }))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_record_and_search_uris() {
+        let uri = ResourceUri::parse("odoo://prod/record/sale.order/42").unwrap();
+        assert_eq!(
+            uri,
+            ResourceUri::Record {
+                instance: "prod".to_string(),
+                model: "sale.order".to_string(),
+                id: 42
+            }
+        );
+        assert_eq!(uri.to_uri(), "odoo://prod/record/sale.order/42");
+
+        let uri = ResourceUri::parse(
+            "odoo://prod/search/sale.order?domain=%5B%5B%22state%22%2C%22%3D%22%2C%22sale%22%5D%5D",
+        )
+        .unwrap();
+        let ResourceUri::Search { domain, .. } = &uri else {
+            panic!("expected a search");
+        };
+        assert_eq!(domain, &json!([["state", "=", "sale"]]));
+        assert_eq!(ResourceUri::parse(&uri.to_uri()).unwrap(), uri);
+
+        let uri = ResourceUri::parse("odoo://prod/search/res.partner").unwrap();
+        assert_eq!(uri.instance(), Some("prod"));
+
+        for invalid in [
+            "odoo://prod/record/sale.order",
+            "odoo://prod/record/sale.order/abc",
+            "odoo://prod/record/sale.order/0",
+            "odoo://prod/search/sale.order?domain=nope",
+            "odoo://prod/search/sale.order?limit=5",
+            "odoo://prod/models?domain=[]",
+        ] {
+            assert!(ResourceUri::parse(invalid).is_err(), "{invalid}");
+        }
+    }
+
     #[test]
     fn test_parse_instances_uri() {
         let uri = ResourceUri::parse("odoo://instances").unwrap();
@@ -1,11 +1,12 @@
 use std::sync::Arc;
 
 use futures::StreamExt;
+use futures::stream::FuturesUnordered;
 use serde_json::json;
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, broadcast};
 
 use mcp_rust_sdk::error::{Error, ErrorCode};
-use mcp_rust_sdk::protocol::{Request, Response, ResponseError};
+use mcp_rust_sdk::protocol::{Notification, Request, Response, ResponseError};
 use mcp_rust_sdk::server::ServerHandler;
 use mcp_rust_sdk::transport::{Message, Transport};
 
@@ -15,6 +16,8 @@ pub struct ServerCompat {
     transport: Arc<dyn Transport>,
     handler: Arc<McpOdooHandler>,
     initialized: Arc<RwLock<bool>>,
+    /// Identifies the connection's resource subscriptions.
+    subscriber: String,
 }
 
 impl ServerCompat {
@@ -23,22 +26,66 @@ impl ServerCompat {
             transport,
             handler,
             initialized: Arc::new(RwLock::new(false)),
+            subscriber: uuid::Uuid::new_v4().to_string(),
         }
     }
 
+    /// Serve until the client goes away or sends `exit`. Requests run
+    /// concurrently (those of a batch in particular); each response is sent as
+    /// soon as it is ready.
     pub async fn start(&self) -> Result<(), Error> {
+        let result = self.serve().await;
+        self.handler.drop_subscriber(&self.subscriber);
+        result
+    }
+
+    async fn serve(&self) -> Result<(), Error> {
         let mut stream = self.transport.receive();
-        while let Some(message) = stream.next().await {
-            match message? {
-                Message::Request(request) => {
-                    let response = match self.handle_request(request.clone()).await {
-                        Ok(resp) => resp,
-                        Err(err) => Response::error(request.id, ResponseError::from(err)),
-                    };
+        let mut notifications = self.handler.subscribe_notifications();
+        let mut updates = self.handler.subscribe_resource_updates();
+        let mut in_flight = FuturesUnordered::new();
+        loop {
+            let message = tokio::select! {
+                message = stream.next() => match message {
+                    Some(message) => message,
+                    None => break,
+                },
+                Some(response) = in_flight.next(), if !in_flight.is_empty() => {
                     self.transport.send(Message::Response(response)).await?;
+                    continue;
+                }
+                notification = notifications.recv() => {
+                    match notification {
+                        Ok(notification) => self.forward(notification).await?,
+                        Err(broadcast::error::RecvError::Lagged(_)) => {}
+                        Err(broadcast::error::RecvError::Closed) => {
+                            notifications = self.handler.subscribe_notifications();
+                        }
+                    }
+                    continue;
+                }
+                update = updates.recv() => {
+                    match update {
+                        Ok(update) if update.subscribers.contains(&self.subscriber) => {
+                            self.forward(json!({
+                                "jsonrpc": "2.0",
+                                "method": "notifications/resources/updated",
+                                "params": { "uri": update.uri }
+                            }))
+                            .await?
+                        }
+                        Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+                        Err(broadcast::error::RecvError::Closed) => {
+                            updates = self.handler.subscribe_resource_updates();
+                        }
+                    }
+                    continue;
                 }
+            };
+            match message? {
+                Message::Request(request) => in_flight.push(self.respond(request)),
                 Message::Notification(notification) => match notification.method.as_str() {
-                    "exit" => break,
+                    "exit" => return Ok(()),
                     "initialized" | "notifications/initialized" => {
                         *self.initialized.write().await = true;
                     }
@@ -52,9 +99,39 @@ impl ServerCompat {
                 }
             }
         }
+        // The client stopped sending; answer what it already asked.
+        while let Some(response) = in_flight.next().await {
+            self.transport.send(Message::Response(response)).await?;
+        }
         Ok(())
     }
 
+    async fn respond(&self, request: Request) -> Response {
+        let id = request.id.clone();
+        match self.handle_request(request).await {
+            Ok(resp) => resp,
+            Err(err) => Response::error(id, ResponseError::from(err)),
+        }
+    }
+
+    /// Send a server notification once the client completed initialization.
+    async fn forward(&self, notification: serde_json::Value) -> Result<(), Error> {
+        if !*self.initialized.read().await {
+            return Ok(());
+        }
+        match serde_json::from_value::<Notification>(notification) {
+            Ok(notification) => {
+                self.transport
+                    .send(Message::Notification(notification))
+                    .await
+            }
+            Err(e) => {
+                tracing::warn!("Dropping malformed server notification: {e}");
+                Ok(())
+            }
+        }
+    }
+
     async fn handle_request(&self, request: Request) -> Result<Response, Error> {
         let initialized = *self.initialized.read().await;
 
@@ -87,7 +164,7 @@ impl ServerCompat {
                     "capabilities": {
                         "tools": { "listChanged": true },
                         "prompts": { "listChanged": true },
-                        "resources": {},
+                        "resources": { "listChanged": true, "subscribe": true },
                         "experimental": {
                             "odooInstances": { "available": odoo_instances }
                         }
@@ -124,10 +201,24 @@ impl ServerCompat {
                         "Server not initialized",
                     ));
                 }
-                let result = self
-                    .handler
-                    .handle_method(&request.method, request.params)
-                    .await?;
+                let result = match request.method.as_str() {
+                    "resources/subscribe" | "resources/unsubscribe" => {
+                        self.handler
+                            .handle_subscription(
+                                &self.subscriber,
+                                None,
+                                None,
+                                &request.method,
+                                request.params,
+                            )
+                            .await?
+                    }
+                    _ => {
+                        self.handler
+                            .handle_method(&request.method, request.params)
+                            .await?
+                    }
+                };
                 Ok(Response::success(request.id, Some(result)))
             }
         }
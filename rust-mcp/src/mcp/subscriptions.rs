@@ -0,0 +1,289 @@
+//! Resource subscriptions (`resources/subscribe`).
+//!
+//! Odoo does not push changes, so each instance with watched resources gets a
+//! poller that fetches the [fingerprint](super::resources::fingerprint) of
+//! every watched URI at `MCP_RESOURCE_POLL_SECS` intervals. A URI is polled
+//! once however many sessions subscribed to it; when its fingerprint changes,
+//! a [`ResourceUpdate`] names the sessions to send
+//! `notifications/resources/updated` to. The poller ends once its instance
+//! has nothing left to watch.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+
+use serde_json::Value;
+use tokio::sync::broadcast;
+
+use super::resources::{ResourceUri, fingerprint};
+use super::tools::OdooClientPool;
+
+const DEFAULT_POLL_SECS: u64 = 30;
+
+/// A watched resource changed.
+#[derive(Debug, Clone)]
+pub struct ResourceUpdate {
+    pub uri: String,
+    /// Sessions subscribed to `uri` when the change was seen.
+    pub subscribers: Vec<String>,
+}
+
+pub struct Subscriptions {
+    /// Polls with the configured service user, whoever subscribed.
+    pool: OdooClientPool,
+    interval: Duration,
+    state: Arc<Mutex<State>>,
+    updates: broadcast::Sender<ResourceUpdate>,
+}
+
+#[derive(Default)]
+struct State {
+    /// By URI as the client wrote it, which it expects back in notifications.
+    watched: HashMap<String, Watched>,
+    /// Instances with a running poller.
+    pollers: HashSet<String>,
+}
+
+struct Watched {
+    resource: ResourceUri,
+    subscribers: HashSet<String>,
+    /// Last fingerprint seen; `None` until the first successful poll.
+    fingerprint: Option<Value>,
+}
+
+impl State {
+    /// Add a subscriber; returns its instance when that has no poller yet.
+    fn add(
+        &mut self,
+        uri: &str,
+        resource: ResourceUri,
+        subscriber: &str,
+        baseline: Option<Value>,
+    ) -> Option<String> {
+        let instance = resource.instance().unwrap_or_default().to_string();
+        let watched = self.watched.entry(uri.to_string()).or_insert(Watched {
+            resource,
+            subscribers: HashSet::new(),
+            fingerprint: None,
+        });
+        watched.subscribers.insert(subscriber.to_string());
+        if watched.fingerprint.is_none() {
+            watched.fingerprint = baseline;
+        }
+        self.pollers.insert(instance.clone()).then_some(instance)
+    }
+
+    fn remove(&mut self, uri: &str, subscriber: &str) {
+        if let Some(watched) = self.watched.get_mut(uri) {
+            watched.subscribers.remove(subscriber);
+            if watched.subscribers.is_empty() {
+                self.watched.remove(uri);
+            }
+        }
+    }
+
+    fn remove_subscriber(&mut self, subscriber: &str) {
+        self.watched.retain(|_, watched| {
+            watched.subscribers.remove(subscriber);
+            !watched.subscribers.is_empty()
+        });
+    }
+
+    /// URIs of `instance` to poll, or `None` (and the poller deregistered)
+    /// when there are none left.
+    fn due(&mut self, instance: &str) -> Option<Vec<(String, ResourceUri)>> {
+        let uris: Vec<_> = self
+            .watched
+            .iter()
+            .filter(|(_, w)| w.resource.instance() == Some(instance))
+            .map(|(uri, w)| (uri.clone(), w.resource.clone()))
+            .collect();
+        if uris.is_empty() {
+            self.pollers.remove(instance);
+            return None;
+        }
+        Some(uris)
+    }
+
+    /// Record a fingerprint; returns the update to send when it changed.
+    fn record(&mut self, uri: &str, fingerprint: Value) -> Option<ResourceUpdate> {
+        let watched = self.watched.get_mut(uri)?;
+        let previous = watched.fingerprint.replace(fingerprint);
+        if previous.is_none() || previous == watched.fingerprint {
+            return None;
+        }
+        Some(ResourceUpdate {
+            uri: uri.to_string(),
+            subscribers: watched.subscribers.iter().cloned().collect(),
+        })
+    }
+}
+
+impl Subscriptions {
+    pub fn new(pool: OdooClientPool) -> Self {
+        let secs = std::env::var("MCP_RESOURCE_POLL_SECS")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .unwrap_or(DEFAULT_POLL_SECS)
+            .max(1);
+        Self {
+            pool,
+            interval: Duration::from_secs(secs),
+            state: Arc::new(Mutex::new(State::default())),
+            updates: broadcast::channel(64).0,
+        }
+    }
+
+    /// Changes of watched resources, for the transports to deliver.
+    pub fn subscribe_updates(&self) -> broadcast::Receiver<ResourceUpdate> {
+        self.updates.subscribe()
+    }
+
+    /// Watch `uri` for `subscriber` (a session). `baseline` is its current
+    /// fingerprint when known, so a change before the first poll is not missed.
+    pub fn subscribe(
+        &self,
+        uri: &str,
+        resource: ResourceUri,
+        subscriber: &str,
+        baseline: Option<Value>,
+    ) {
+        let instance = self
+            .state
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .add(uri, resource, subscriber, baseline);
+        if let Some(instance) = instance {
+            tokio::spawn(poll(
+                instance,
+                self.pool.clone(),
+                self.interval,
+                Arc::downgrade(&self.state),
+                self.updates.clone(),
+            ));
+        }
+    }
+
+    pub fn unsubscribe(&self, uri: &str, subscriber: &str) {
+        self.state
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(uri, subscriber);
+    }
+
+    /// Forget every subscription of an ended session.
+    pub fn unsubscribe_all(&self, subscriber: &str) {
+        self.state
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove_subscriber(subscriber);
+    }
+
+    /// Distinct URIs being polled.
+    pub fn watched_count(&self) -> usize {
+        self.state
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .watched
+            .len()
+    }
+}
+
+/// Poll the watched resources of one instance until none are left.
+async fn poll(
+    instance: String,
+    pool: OdooClientPool,
+    period: Duration,
+    state: Weak<Mutex<State>>,
+    updates: broadcast::Sender<ResourceUpdate>,
+) {
+    let mut interval = tokio::time::interval(period);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        interval.tick().await;
+        let Some(uris) = state
+            .upgrade()
+            .and_then(|s| s.lock().unwrap_or_else(|e| e.into_inner()).due(&instance))
+        else {
+            break;
+        };
+        for (uri, resource) in uris {
+            let current = match fingerprint(&pool, &resource).await {
+                Ok(current) => current,
+                Err(e) => {
+                    tracing::debug!(%uri, error = %e, "Resource poll failed");
+                    continue;
+                }
+            };
+            let Some(state) = state.upgrade() else {
+                return;
+            };
+            let update = state
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .record(&uri, current);
+            if let Some(update) = update {
+                let _ = updates.send(update);
+            }
+        }
+    }
+    tracing::debug!(%instance, "Resource poller stopped");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record(id: i64) -> (String, ResourceUri) {
+        let resource = ResourceUri::Record {
+            instance: "prod".to_string(),
+            model: "res.partner".to_string(),
+            id,
+        };
+        (resource.to_uri(), resource)
+    }
+
+    #[test]
+    fn test_sessions_share_one_watched_uri() {
+        let mut state = State::default();
+        let (uri, resource) = record(7);
+        assert_eq!(
+            state.add(&uri, resource.clone(), "a", None),
+            Some("prod".to_string())
+        );
+        // Same URI, same instance: no second entry, no second poller.
+        assert_eq!(state.add(&uri, resource, "b", None), None);
+        let (other, resource) = record(8);
+        assert_eq!(state.add(&other, resource, "a", None), None);
+        assert_eq!(state.watched.len(), 2);
+
+        state.remove_subscriber("a");
+        assert_eq!(state.watched.len(), 1);
+        state.remove(&uri, "b");
+        assert!(state.watched.is_empty());
+        assert!(state.due("prod").is_none());
+        assert!(state.pollers.is_empty());
+    }
+
+    #[test]
+    fn test_update_only_on_change_from_known_fingerprint() {
+        let mut state = State::default();
+        let (uri, resource) = record(7);
+        state.add(&uri, resource, "a", None);
+
+        // First poll sets the baseline.
+        assert!(state.record(&uri, json!("2024-01-01 10:00:00")).is_none());
+        assert!(state.record(&uri, json!("2024-01-01 10:00:00")).is_none());
+        let update = state.record(&uri, json!("2024-01-01 10:05:00")).unwrap();
+        assert_eq!(update.uri, uri);
+        assert_eq!(update.subscribers, vec!["a".to_string()]);
+        // Deleted records read as null: also a change.
+        assert!(state.record(&uri, Value::Null).is_some());
+        assert!(
+            state
+                .record("odoo://prod/record/res.partner/9", json!(1))
+                .is_none()
+        );
+    }
+}
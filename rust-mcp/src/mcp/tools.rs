@@ -1,37 +1,145 @@
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use base64::Engine;
+use serde::Serialize;
 use serde_json::{Map, Value, json};
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, broadcast};
+
+use async_trait::async_trait;
 
 use crate::cleanup;
+use crate::config_manager::ConfigWatcher;
+use crate::mcp::budget::{self, ResponseBudget};
 use crate::mcp::cache::MetadataCache;
+use crate::mcp::domain::{self, FieldsSource};
+use crate::mcp::domain_parser;
+use crate::mcp::expand::{self, ExpandPlan};
+use crate::mcp::format::{self, OutputFormat};
+use crate::mcp::progress;
 use crate::mcp::registry::{OpSpec, ToolDef};
-use crate::odoo::config::{OdooEnvConfig, load_odoo_env};
+use crate::odoo::config::{OdooEnvConfig, OdooInstanceConfig, UserCredentials, load_odoo_env};
 use crate::odoo::types::OdooError;
 use crate::odoo::unified_client::OdooClient;
+use crate::odoo::version::{self, ServerVersion};
+
+/// Instances added, removed or changed by a reload of the configuration.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct InstanceChanges {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<String>,
+}
+
+impl InstanceChanges {
+    fn between(
+        old: &HashMap<String, OdooInstanceConfig>,
+        new: &HashMap<String, OdooInstanceConfig>,
+    ) -> Self {
+        let mut changes = Self::default();
+        for (name, cfg) in new {
+            match old.get(name) {
+                None => changes.added.push(name.clone()),
+                Some(previous) if previous != cfg => changes.changed.push(name.clone()),
+                Some(_) => {}
+            }
+        }
+        changes.removed = old
+            .keys()
+            .filter(|name| !new.contains_key(*name))
+            .cloned()
+            .collect();
+        changes.added.sort();
+        changes.removed.sort();
+        changes.changed.sort();
+        changes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Per-user clients by (instance, credentials), with their last use.
+type UserClients = HashMap<(String, UserCredentials), (OdooClient, Instant)>;
+
+/// Metadata caches of end users. Odoo filters `fields_get` by the caller's
+/// groups, so metadata is never shared between users.
+type UserCaches = HashMap<UserCredentials, MetadataCache>;
 
 /// Shared state: parsed env + instantiated clients per instance.
 /// Supports both Odoo 19+ (JSON-2 API) and Odoo < 19 (JSON-RPC).
 #[derive(Clone)]
 pub struct OdooClientPool {
-    env: Arc<OdooEnvConfig>,
+    /// Current instance configuration, replaced as a whole on reload.
+    env: Arc<std::sync::RwLock<Arc<OdooEnvConfig>>>,
     clients: Arc<Mutex<HashMap<String, OdooClient>>>,
+    /// Server versions detected when each client was created.
+    versions: Arc<std::sync::RwLock<HashMap<String, ServerVersion>>>,
+    changes: broadcast::Sender<InstanceChanges>,
+    /// Clients of end users passing their own credentials.
+    user_clients: Arc<Mutex<UserClients>>,
+    /// Idle time after which a user client is dropped.
+    user_idle: Duration,
+    /// Set on the views returned by [`with_credentials`](Self::with_credentials).
+    user: Option<Arc<UserCredentials>>,
+    user_caches: Arc<std::sync::Mutex<UserCaches>>,
+    /// Metadata as seen by this view's user (the service user by default).
     pub metadata_cache: MetadataCache,
 }
 
+/// Default idle time of per-user clients (`MCP_ODOO_USER_CLIENT_IDLE_SECS`).
+const DEFAULT_USER_CLIENT_IDLE_SECS: u64 = 900;
+
 impl OdooClientPool {
     pub fn from_env() -> anyhow::Result<Self> {
         let env = load_odoo_env()?;
         Ok(Self {
-            env: Arc::new(env),
+            env: Arc::new(std::sync::RwLock::new(Arc::new(env))),
             clients: Arc::new(Mutex::new(HashMap::new())),
+            versions: Arc::new(std::sync::RwLock::new(HashMap::new())),
+            changes: broadcast::channel(16).0,
+            user_clients: Arc::new(Mutex::new(HashMap::new())),
+            user_idle: Duration::from_secs(
+                std::env::var("MCP_ODOO_USER_CLIENT_IDLE_SECS")
+                    .ok()
+                    .and_then(|v| v.trim().parse().ok())
+                    .unwrap_or(DEFAULT_USER_CLIENT_IDLE_SECS),
+            ),
+            user: None,
+            user_caches: Arc::new(std::sync::Mutex::new(HashMap::new())),
             metadata_cache: MetadataCache::new(),
         })
     }
 
+    /// A view of the pool whose clients authenticate as `credentials` instead
+    /// of the configured service user. Clients are shared by all views with the
+    /// same credentials and dropped after `MCP_ODOO_USER_CLIENT_IDLE_SECS`,
+    /// as is the metadata cached for them.
+    pub fn with_credentials(&self, credentials: UserCredentials) -> Self {
+        let metadata_cache = self
+            .user_caches
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(credentials.clone())
+            .or_default()
+            .clone();
+        Self {
+            user: Some(Arc::new(credentials)),
+            metadata_cache,
+            ..self.clone()
+        }
+    }
+
+    fn env(&self) -> Arc<OdooEnvConfig> {
+        self.env.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
     pub async fn get(&self, instance: &str) -> anyhow::Result<OdooClient> {
+        if let Some(user) = &self.user {
+            return self.get_for_user(instance, user).await;
+        }
         {
             let guard = self.clients.lock().await;
             if let Some(c) = guard.get(instance) {
@@ -39,34 +147,340 @@ impl OdooClientPool {
             }
         }
 
-        let cfg = self.env.instances.get(instance).ok_or_else(|| {
-            let available = self
-                .env
-                .instances
-                .keys()
-                .cloned()
-                .collect::<Vec<_>>()
-                .join(", ");
-            anyhow::anyhow!("Unknown Odoo instance '{instance}'. Available: {available}")
-        })?;
+        let configured = self.instance_config(instance)?;
+        let cfg = self.detect_version(instance, &configured).await;
+        let client = OdooClient::new(&cfg)?.with_instance_name(instance);
+        // Do not cache a client built from a configuration replaced meanwhile.
+        if self.instance_config(instance).ok().as_ref() == Some(&configured) {
+            let mut guard = self.clients.lock().await;
+            guard.insert(instance.to_string(), client.clone());
+        }
+        Ok(client)
+    }
 
-        let client = OdooClient::new(cfg)?;
-        let mut guard = self.clients.lock().await;
-        guard.insert(instance.to_string(), client.clone());
+    async fn get_for_user(
+        &self,
+        instance: &str,
+        user: &UserCredentials,
+    ) -> anyhow::Result<OdooClient> {
+        let key = (instance.to_string(), user.clone());
+        {
+            let now = Instant::now();
+            let mut guard = self.user_clients.lock().await;
+            guard.retain(|_, (_, last_used)| now.duration_since(*last_used) < self.user_idle);
+            self.user_caches
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .retain(|cached, _| cached == user || guard.keys().any(|(_, c)| c == cached));
+            if let Some((client, last_used)) = guard.get_mut(&key) {
+                *last_used = now;
+                return Ok(client.clone());
+            }
+        }
+
+        let configured = self.instance_config(instance)?;
+        let cfg = self
+            .detect_version(instance, &user.apply(&configured))
+            .await;
+        let client = OdooClient::new(&cfg)?.with_instance_name(instance);
+        tracing::debug!(
+            instance,
+            user = user.label(),
+            "Created Odoo client for user"
+        );
+        if self.instance_config(instance).ok().as_ref() == Some(&configured) {
+            let mut guard = self.user_clients.lock().await;
+            guard.insert(key, (client.clone(), Instant::now()));
+        }
         Ok(client)
     }
 
+    /// Number of per-user clients currently kept, idle ones included.
+    pub async fn user_client_count(&self) -> usize {
+        self.user_clients.lock().await.len()
+    }
+
+    fn instance_config(&self, instance: &str) -> anyhow::Result<OdooInstanceConfig> {
+        let env = self.env();
+        env.instances.get(instance).cloned().ok_or_else(|| {
+            let mut available = env.instances.keys().cloned().collect::<Vec<_>>();
+            available.sort();
+            let available = available.join(", ");
+            anyhow::anyhow!("Unknown Odoo instance '{instance}'. Available: {available}")
+        })
+    }
+
+    /// Reload the instance configuration: clients of changed and removed
+    /// instances are dropped (with their detected version and cached metadata)
+    /// and rebuilt on next use; new instances become available. On error the
+    /// current configuration is kept.
+    pub async fn reload(&self) -> anyhow::Result<InstanceChanges> {
+        let new_env = load_odoo_env()?;
+        let changes = InstanceChanges::between(&self.env().instances, &new_env.instances);
+        *self.env.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(new_env);
+        if changes.is_empty() {
+            return Ok(changes);
+        }
+
+        let stale: Vec<&String> = changes.removed.iter().chain(&changes.changed).collect();
+        {
+            let mut clients = self.clients.lock().await;
+            for name in &stale {
+                clients.remove(*name);
+            }
+        }
+        self.user_clients
+            .lock()
+            .await
+            .retain(|(name, _), _| !stale.contains(&name));
+        {
+            let mut versions = self.versions.write().unwrap_or_else(|e| e.into_inner());
+            for name in &stale {
+                versions.remove(*name);
+            }
+        }
+        let user_caches: Vec<MetadataCache> = self
+            .user_caches
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .cloned()
+            .collect();
+        for cache in std::iter::once(&self.metadata_cache).chain(&user_caches) {
+            for name in &stale {
+                cache.clear_instance(name).await;
+            }
+        }
+
+        tracing::info!(
+            added = ?changes.added,
+            removed = ?changes.removed,
+            changed = ?changes.changed,
+            "Reloaded Odoo instances"
+        );
+        let _ = self.changes.send(changes.clone());
+        Ok(changes)
+    }
+
+    /// Changes applied by [`reload`](Self::reload).
+    pub fn subscribe_changes(&self) -> broadcast::Receiver<InstanceChanges> {
+        self.changes.subscribe()
+    }
+
+    /// Watch the instances file (`ODOO_INSTANCES_JSON`) and reload when it
+    /// changes. Returns false when instances do not come from a file.
+    pub fn start_watcher(&self) -> bool {
+        let Some(path) = std::env::var("ODOO_INSTANCES_JSON")
+            .ok()
+            .map(|p| std::path::PathBuf::from(p.trim()))
+            .filter(|p| !p.as_os_str().is_empty())
+        else {
+            return false;
+        };
+        let Some(file_name) = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .map(str::to_string)
+        else {
+            return false;
+        };
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+            _ => std::path::PathBuf::from("."),
+        };
+        let watcher = match ConfigWatcher::new(dir) {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                tracing::warn!(
+                    "Cannot watch {}: {e}; instance hot-reload disabled",
+                    path.display()
+                );
+                return false;
+            }
+        };
+
+        let mut events = watcher.subscribe();
+        let pool = self.clone();
+        tokio::spawn(async move {
+            let _watcher = watcher;
+            loop {
+                match events.recv().await {
+                    Ok(name) if name != file_name => continue,
+                    Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+                // Editors and the config UI write in several steps.
+                tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+                while events.try_recv().is_ok() {}
+                if let Err(e) = pool.reload().await {
+                    tracing::warn!("Instance reload failed; keeping last good configuration: {e}");
+                }
+            }
+        });
+        tracing::info!(path = %path.display(), "Watching instances file");
+        true
+    }
+
+    /// The client for `instance` if one was already created, without probing.
+    pub async fn cached(&self, instance: &str) -> Option<OdooClient> {
+        self.clients.lock().await.get(instance).cloned()
+    }
+
+    /// Probe the server version and point the config at the matching client:
+    /// JSON-2 for 19+, JSON-RPC below. Falls back to the configured settings when
+    /// the probe fails or the credentials do not fit the detected version.
+    async fn detect_version(&self, instance: &str, cfg: &OdooInstanceConfig) -> OdooInstanceConfig {
+        let mut cfg = cfg.clone();
+        if !version::detection_enabled() {
+            return cfg;
+        }
+        let detected = match version::detect(&cfg).await {
+            Ok(detected) => detected,
+            Err(e) => {
+                tracing::warn!(
+                    instance,
+                    "Odoo version detection failed, using configured settings: {e}"
+                );
+                return cfg;
+            }
+        };
+        tracing::info!(
+            instance,
+            version = %detected.server_version,
+            edition = ?detected.edition,
+            "Detected Odoo server version"
+        );
+
+        if let Some(configured) = version::configured_major(&cfg)
+            && configured != detected.major
+        {
+            tracing::warn!(
+                instance,
+                "Instance is configured as Odoo {configured} but the server reports {}; using the detected version",
+                detected.server_version
+            );
+        }
+
+        let has_password = cfg.username.as_ref().is_some_and(|u| !u.trim().is_empty())
+            && cfg.password.as_ref().is_some_and(|p| !p.trim().is_empty());
+        let has_db = cfg.db.as_ref().is_some_and(|d| !d.trim().is_empty());
+        let has_api_key = cfg.api_key.as_ref().is_some_and(|k| !k.trim().is_empty());
+        if (has_password && has_db) || (detected.major >= 19 && has_api_key) {
+            cfg.version = Some(detected.major.to_string());
+        } else {
+            tracing::warn!(
+                instance,
+                "Odoo {} needs db, username and password (JSON-RPC); keeping the configured client",
+                detected.server_version
+            );
+        }
+
+        self.versions
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(instance.to_string(), detected);
+        cfg
+    }
+
+    /// Server version detected for `instance`, once its client exists.
+    pub fn detected_version(&self, instance: &str) -> Option<ServerVersion> {
+        self.versions
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(instance)
+            .cloned()
+    }
+
+    /// Configured `version` string of an instance.
+    pub fn configured_version(&self, instance: &str) -> Option<String> {
+        self.env().instances.get(instance)?.version.clone()
+    }
+
     pub fn instance_names(&self) -> Vec<String> {
-        self.env.instances.keys().cloned().collect()
+        self.env().instances.keys().cloned().collect()
+    }
+
+    /// Major Odoo version of an instance: detected if known, else configured
+    /// (`"17.0"`, `"saas~17.2"` -> 17).
+    pub fn instance_version(&self, instance: &str) -> Option<u32> {
+        if let Some(detected) = self.detected_version(instance) {
+            return Some(detected.major);
+        }
+        let env = self.env();
+        let version = env.instances.get(instance)?.version.as_deref()?;
+        let version = version.trim().trim_start_matches("saas~");
+        let major: String = version.chars().take_while(|c| c.is_ascii_digit()).collect();
+        major.parse().ok()
     }
 }
 
+/// The instance a tool call targets, if its arguments name one.
+pub fn tool_instance(tool: &ToolDef, args: &Value) -> Option<String> {
+    opt_str(args, &tool.op, "instance").ok().flatten()
+}
+
+/// Read-type ops that follow the server-wide `defaultFormat`.
+const FORMATTABLE_OPS: &[&str] = &["search_read", "read", "read_group", "name_search"];
+
+/// Ops whose results are kept within the response size budget. Others (reports
+/// in particular) return data that is useless once cut.
+const BUDGETED_OPS: &[&str] = &[
+    "search_read",
+    "read",
+    "read_group",
+    "name_search",
+    "get_model_metadata",
+];
+
 pub async fn call_tool(
     pool: &OdooClientPool,
     tool: &ToolDef,
     args: Value,
+    default_format: OutputFormat,
+) -> Result<Value, OdooError> {
+    let instance = opt_str(&args, &tool.op, "instance").ok().flatten();
+    let model = opt_str(&args, &tool.op, "model").ok().flatten();
+    let output_format = match opt_str(&args, &tool.op, "format")? {
+        Some(f) => f.parse().map_err(OdooError::InvalidResponse)?,
+        None if FORMATTABLE_OPS.contains(&tool.op.op_type.as_str()) => default_format,
+        None => OutputFormat::Json,
+    };
+    let result = execute_op(pool, &tool.op, args).await?;
+    apply_budget(pool, tool, instance, model, result, output_format).await
+}
+
+/// Render `result` in `output_format`, kept within the tool's budget.
+async fn apply_budget(
+    pool: &OdooClientPool,
+    tool: &ToolDef,
+    instance: Option<String>,
+    model: Option<String>,
+    result: Value,
+    output_format: OutputFormat,
 ) -> Result<Value, OdooError> {
-    execute_op(pool, &tool.op, args).await
+    let limit = BUDGETED_OPS
+        .contains(&tool.op.op_type.as_str())
+        .then(|| ResponseBudget::effective_limit(tool.response_budget.as_ref()))
+        .flatten();
+    let Some(limit) = limit else {
+        return Ok(format::apply_format(result, output_format));
+    };
+    // Only consult metadata that is already cached; budgeting never adds a round trip.
+    let field_types = match (instance, model) {
+        (Some(instance), Some(model)) => {
+            pool.metadata_cache
+                .get(&instance, &model)
+                .await
+                .and_then(|m| {
+                    m.pointer("/model/fields")
+                        .and_then(|f| f.as_object())
+                        .cloned()
+                })
+        }
+        _ => None,
+    };
+    budget::apply_budget(result, limit, field_types.as_ref(), output_format)
+        .map_err(OdooError::InvalidResponse)
 }
 
 pub async fn execute_op(
@@ -97,6 +511,7 @@ pub async fn execute_op(
         "list_models" => op_list_models(pool, op, args).await,
         "check_access" => op_check_access(pool, op, args).await,
         "create_batch" => op_create_batch(pool, op, args).await,
+        "validate_domain" => op_validate_domain(pool, op, args).await,
         other => Err(OdooError::InvalidResponse(format!(
             "Unknown op.type: {other}"
         ))),
@@ -225,6 +640,68 @@ fn ok_text(payload: Value) -> Value {
     })
 }
 
+/// Resolves `fields_get` metadata through the pool's metadata cache.
+struct PoolFieldsSource<'a> {
+    pool: &'a OdooClientPool,
+    instance: &'a str,
+}
+
+#[async_trait]
+impl FieldsSource for PoolFieldsSource<'_> {
+    async fn fields(&self, model: &str) -> Option<Map<String, Value>> {
+        let metadata = load_model_metadata(self.pool, self.instance, model, None)
+            .await
+            .ok()?;
+        metadata.pointer("/model/fields")?.as_object().cloned()
+    }
+}
+
+fn domain_validation_enabled() -> bool {
+    std::env::var("ODOO_DOMAIN_VALIDATION")
+        .map(|v| {
+            !matches!(
+                v.trim().to_ascii_lowercase().as_str(),
+                "0" | "false" | "no" | "off"
+            )
+        })
+        .unwrap_or(true)
+}
+
+/// Accept a domain either as a JSON array or as a Python-style / infix string.
+fn normalize_domain(domain: Value) -> Result<Value, OdooError> {
+    match domain {
+        Value::String(s) => {
+            domain_parser::parse_domain(&s).map_err(|e| OdooError::InvalidResponse(e.render(&s)))
+        }
+        other => Ok(other),
+    }
+}
+
+/// Read the domain argument at `key` and lint it against `model` before it is sent.
+async fn opt_domain(
+    pool: &OdooClientPool,
+    args: &Value,
+    op: &OpSpec,
+    key: &str,
+    instance: &str,
+    model: &str,
+) -> Result<Option<Value>, OdooError> {
+    let Some(domain) = opt_value(args, op, key) else {
+        return Ok(None);
+    };
+    let domain = normalize_domain(domain)?;
+    if !domain_validation_enabled() || domain.as_array().is_some_and(|a| a.is_empty()) {
+        return Ok(Some(domain));
+    }
+
+    let source = PoolFieldsSource { pool, instance };
+    let issues = domain::validate_domain(&domain, model, &source).await;
+    if !issues.is_empty() {
+        return Err(OdooError::InvalidResponse(domain::format_issues(&issues)));
+    }
+    Ok(Some(domain))
+}
+
 async fn op_search(pool: &OdooClientPool, op: &OpSpec, args: Value) -> Result<Value, OdooError> {
     let instance = req_str(&args, op, "instance")?;
     let model = req_str(&args, op, "model")?;
@@ -233,7 +710,7 @@ async fn op_search(pool: &OdooClientPool, op: &OpSpec, args: Value) -> Result<Va
         .await
         .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
 
-    let domain = opt_value(&args, op, "domain");
+    let domain = opt_domain(pool, &args, op, "domain", &instance, &model).await?;
     let limit = opt_i64(&args, op, "limit")?;
     let offset = opt_i64(&args, op, "offset")?;
     let order = opt_str(&args, op, "order")?;
@@ -257,33 +734,157 @@ async fn op_search_read(
         .await
         .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
 
-    let domain = opt_value(&args, op, "domain");
-    let fields = opt_vec_string(&args, op, "fields")?;
+    let domain = opt_domain(pool, &args, op, "domain", &instance, &model).await?;
+    let (fields, omitted) = default_fields(
+        pool,
+        &instance,
+        &model,
+        opt_vec_string(&args, op, "fields")?,
+    )
+    .await;
     let limit = opt_i64(&args, op, "limit")?;
     let offset = opt_i64(&args, op, "offset")?;
     let order = opt_str(&args, op, "order")?;
     let context = opt_value(&args, op, "context");
 
-    let records = client
-        .search_read(&model, domain, fields, limit, offset, order, context)
-        .await?;
+    let records = if let Some(expand) = opt_value(&args, op, "expand") {
+        let plan = expand_plan(pool, &instance, &model, &expand, fields.as_deref()).await?;
+        if supports_web_read(pool, &instance, &client) {
+            let mut params = Map::new();
+            params.insert("domain".to_string(), domain.unwrap_or_else(|| json!([])));
+            params.insert("specification".to_string(), plan.specification());
+            if let Some(offset) = offset {
+                params.insert("offset".to_string(), json!(offset));
+            }
+            if let Some(limit) = limit {
+                params.insert("limit".to_string(), json!(limit));
+            }
+            if let Some(order) = order {
+                params.insert("order".to_string(), json!(order));
+            }
+            let result = client
+                .call_named(&model, "web_search_read", None, params, context)
+                .await?;
+            let mut records = result.get("records").cloned().unwrap_or_else(|| json!([]));
+            plan.normalize(&mut records);
+            records
+        } else {
+            let mut records = client
+                .search_read(
+                    &model,
+                    domain,
+                    plan.read_fields(),
+                    limit,
+                    offset,
+                    order,
+                    context.clone(),
+                )
+                .await?;
+            plan.expand_records(&client, &mut records, context).await?;
+            records
+        }
+    } else {
+        client
+            .search_read(&model, domain, fields, limit, offset, order, context)
+            .await?
+    };
     let count = records.as_array().map(|a| a.len()).unwrap_or(0);
-    Ok(ok_text(json!({ "records": records, "count": count })))
+    let mut payload = json!({ "records": records, "count": count });
+    if !omitted.is_empty() {
+        payload["omitted_binary_fields"] = json!(omitted);
+    }
+    Ok(ok_text(payload))
 }
 
 async fn op_read(pool: &OdooClientPool, op: &OpSpec, args: Value) -> Result<Value, OdooError> {
     let instance = req_str(&args, op, "instance")?;
     let model = req_str(&args, op, "model")?;
     let ids = req_vec_i64(&args, op, "ids")?;
-    let fields = opt_vec_string(&args, op, "fields")?;
+    let (fields, omitted) = default_fields(
+        pool,
+        &instance,
+        &model,
+        opt_vec_string(&args, op, "fields")?,
+    )
+    .await;
     let context = opt_value(&args, op, "context");
 
     let client = pool
         .get(&instance)
         .await
         .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
-    let records = client.read(&model, ids, fields, context).await?;
-    Ok(ok_text(json!({ "records": records })))
+
+    let records = if let Some(expand) = opt_value(&args, op, "expand") {
+        let plan = expand_plan(pool, &instance, &model, &expand, fields.as_deref()).await?;
+        if supports_web_read(pool, &instance, &client) {
+            let mut params = Map::new();
+            params.insert("specification".to_string(), plan.specification());
+            let mut records = client
+                .call_named(&model, "web_read", Some(ids), params, context)
+                .await?;
+            plan.normalize(&mut records);
+            records
+        } else {
+            let mut records = client
+                .read(&model, ids, plan.read_fields(), context.clone())
+                .await?;
+            plan.expand_records(&client, &mut records, context).await?;
+            records
+        }
+    } else {
+        client.read(&model, ids, fields, context).await?
+    };
+    let mut payload = json!({ "records": records });
+    if !omitted.is_empty() {
+        payload["omitted_binary_fields"] = json!(omitted);
+    }
+    Ok(ok_text(payload))
+}
+
+/// When no `fields` are given, read every field except binaries, which are
+/// only returned when named explicitly. Returns the field list to send and
+/// the binary fields left out.
+async fn default_fields(
+    pool: &OdooClientPool,
+    instance: &str,
+    model: &str,
+    fields: Option<Vec<String>>,
+) -> (Option<Vec<String>>, Vec<String>) {
+    if fields.is_some() {
+        return (fields, Vec::new());
+    }
+    let source = PoolFieldsSource { pool, instance };
+    let Some(meta) = source.fields(model).await else {
+        return (None, Vec::new());
+    };
+    let mut binary = budget::binary_fields(&meta);
+    if binary.is_empty() {
+        return (None, Vec::new());
+    }
+    binary.sort();
+    let mut names: Vec<String> = meta
+        .keys()
+        .filter(|k| !binary.contains(k))
+        .cloned()
+        .collect();
+    names.sort();
+    (Some(names), binary)
+}
+
+async fn expand_plan(
+    pool: &OdooClientPool,
+    instance: &str,
+    model: &str,
+    expand: &Value,
+    fields: Option<&[String]>,
+) -> Result<ExpandPlan, OdooError> {
+    let source = PoolFieldsSource { pool, instance };
+    ExpandPlan::build(expand, model, fields, &source, expand::max_depth_from_env()).await
+}
+
+/// `web_search_read` / `web_read` with a `specification` exist from Odoo 17.
+fn supports_web_read(pool: &OdooClientPool, instance: &str, client: &OdooClient) -> bool {
+    !client.is_legacy() || pool.instance_version(instance).is_some_and(|v| v >= 17)
 }
 
 async fn op_create(pool: &OdooClientPool, op: &OpSpec, args: Value) -> Result<Value, OdooError> {
@@ -291,13 +892,29 @@ async fn op_create(pool: &OdooClientPool, op: &OpSpec, args: Value) -> Result<Va
     let model = req_str(&args, op, "model")?;
     let values = req_value(&args, op, "values")?;
     let context = opt_value(&args, op, "context");
+    let idempotency_key = opt_str(&args, op, "idempotency_key")?;
+    let idempotency_field = opt_str(&args, op, "idempotency_field")?;
 
     let client = pool
         .get(&instance)
         .await
         .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
-    let id = client.create(&model, values, context).await?;
-    Ok(ok_text(json!({ "id": id, "success": true })))
+    let Some(key) = idempotency_key else {
+        let id = client.create(&model, values, context).await?;
+        return Ok(ok_text(json!({ "id": id, "success": true })));
+    };
+    let field = idempotency_field.ok_or_else(|| {
+        OdooError::InvalidResponse(
+            "'idempotency_key' requires 'idempotency_field' (a char field on the model that stores the key)"
+                .to_string(),
+        )
+    })?;
+    let (id, created) = client
+        .create_idempotent(&model, values, context, &field, &key)
+        .await?;
+    Ok(ok_text(
+        json!({ "id": id, "success": true, "created": created }),
+    ))
 }
 
 async fn op_write(pool: &OdooClientPool, op: &OpSpec, args: Value) -> Result<Value, OdooError> {
@@ -340,7 +957,7 @@ async fn op_search_count(
 ) -> Result<Value, OdooError> {
     let instance = req_str(&args, op, "instance")?;
     let model = req_str(&args, op, "model")?;
-    let domain = opt_value(&args, op, "domain");
+    let domain = opt_domain(pool, &args, op, "domain", &instance, &model).await?;
     let context = opt_value(&args, op, "context");
 
     let client = pool
@@ -464,6 +1081,17 @@ async fn op_get_model_metadata(
     let model = req_str(&args, op, "model")?;
     let context = opt_value(&args, op, "context");
 
+    let metadata = load_model_metadata(pool, &instance, &model, context).await?;
+    Ok(ok_text(metadata))
+}
+
+/// Fetch `fields_get` plus the model description, going through the metadata cache.
+async fn load_model_metadata(
+    pool: &OdooClientPool,
+    instance: &str,
+    model: &str,
+    context: Option<Value>,
+) -> Result<Value, OdooError> {
     // Get cache TTL from environment (default: 300 seconds, 0 disables cache)
     let cache_ttl_secs: u64 = std::env::var("ODOO_METADATA_CACHE_TTL_SECS")
         .unwrap_or_else(|_| "300".to_string())
@@ -472,16 +1100,16 @@ async fn op_get_model_metadata(
 
     // Check cache if TTL > 0
     if cache_ttl_secs > 0
-        && let Some(cached) = pool.metadata_cache.get(&instance, &model).await
+        && let Some(cached) = pool.metadata_cache.get(instance, model).await
     {
-        return Ok(ok_text(cached));
+        return Ok(cached);
     }
 
     let client = pool
-        .get(&instance)
+        .get(instance)
         .await
         .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
-    let fields = client.fields_get(&model, context.clone()).await?;
+    let fields = client.fields_get(model, context.clone()).await?;
 
     let domain = json!([["model", "=", model]]);
     let info = client
@@ -501,7 +1129,7 @@ async fn op_get_model_metadata(
         .and_then(|arr| arr.first())
         .and_then(|o| o.get("name"))
         .and_then(|v| v.as_str())
-        .unwrap_or(&model)
+        .unwrap_or(model)
         .to_string();
 
     let metadata = json!({
@@ -515,11 +1143,37 @@ async fn op_get_model_metadata(
     // Insert into cache if TTL > 0
     if cache_ttl_secs > 0 {
         pool.metadata_cache
-            .insert(&instance, &model, metadata.clone(), cache_ttl_secs)
+            .insert(instance, model, metadata.clone(), cache_ttl_secs)
             .await;
     }
 
-    Ok(ok_text(metadata))
+    Ok(metadata)
+}
+
+async fn op_validate_domain(
+    pool: &OdooClientPool,
+    op: &OpSpec,
+    args: Value,
+) -> Result<Value, OdooError> {
+    let instance = req_str(&args, op, "instance")?;
+    let model = req_str(&args, op, "model")?;
+    let domain = normalize_domain(req_value(&args, op, "domain")?)?;
+
+    // Load metadata up front so an unknown model or instance is reported
+    // instead of silently skipping the field checks.
+    load_model_metadata(pool, &instance, &model, None).await?;
+
+    let source = PoolFieldsSource {
+        pool,
+        instance: &instance,
+    };
+    let issues = domain::validate_domain(&domain, &model, &source).await;
+    Ok(ok_text(json!({
+        "valid": issues.is_empty(),
+        "model": model,
+        "domain": domain,
+        "issues": issues.iter().map(|i| i.to_json()).collect::<Vec<_>>()
+    })))
 }
 
 async fn op_database_cleanup(
@@ -583,7 +1237,7 @@ async fn op_read_group(
     let model = req_str(&args, op, "model")?;
     let fields = opt_vec_string(&args, op, "fields")?.unwrap_or_default();
     let groupby = opt_vec_string(&args, op, "groupby")?.unwrap_or_default();
-    let domain = opt_value(&args, op, "domain");
+    let domain = opt_domain(pool, &args, op, "domain", &instance, &model).await?;
     let offset = opt_i64(&args, op, "offset")?;
     let limit = opt_i64(&args, op, "limit")?;
     let orderby = opt_str(&args, op, "orderby")?;
@@ -610,7 +1264,7 @@ async fn op_name_search(
     let instance = req_str(&args, op, "instance")?;
     let model = req_str(&args, op, "model")?;
     let name = opt_str(&args, op, "name")?;
-    let domain = opt_value(&args, op, "args");
+    let domain = opt_domain(pool, &args, op, "args", &instance, &model).await?;
     let operator = opt_str(&args, op, "operator")?;
     let limit = opt_i64(&args, op, "limit")?;
     let context = opt_value(&args, op, "context");
@@ -698,8 +1352,9 @@ async fn op_list_models(
     args: Value,
 ) -> Result<Value, OdooError> {
     let instance = req_str(&args, op, "instance")?;
-    let domain =
-        opt_value(&args, op, "domain").unwrap_or_else(|| json!([["transient", "=", false]]));
+    let domain = opt_domain(pool, &args, op, "domain", &instance, "ir.model")
+        .await?
+        .unwrap_or_else(|| json!([["transient", "=", false]]));
     let limit = opt_i64(&args, op, "limit")?;
     let offset = opt_i64(&args, op, "offset")?;
     let context = opt_value(&args, op, "context");
@@ -740,46 +1395,11 @@ async fn op_check_access(
         .await
         .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
 
-    // Note: check_access() in Odoo 19+ is a private method and cannot be called remotely.
-    // We must use check_access_rights() and check_access_rule() for all Odoo versions,
-    // even though they are deprecated in Odoo 19+. They are still callable remotely.
-    let mut params = serde_json::Map::new();
-    params.insert("operation".to_string(), json!(operation));
-
-    let access_result = client
-        .call_named(
-            &model,
-            "check_access_rights",
-            None,
-            params.clone(),
-            context.clone(),
-        )
+    let mut result = client
+        .check_access(&model, &operation, ids, context)
         .await?;
-
-    // If IDs provided, also check record-level access rules
-    let record_result = if let Some(record_ids) = ids {
-        let ids_array: Vec<i64> = record_ids.clone();
-        client
-            .call_named(
-                &model,
-                "check_access_rule",
-                Some(ids_array),
-                params,
-                context,
-            )
-            .await
-            .ok()
-    } else {
-        None
-    };
-
-    let result = json!({
-        "has_access": true,
-        "model": model,
-        "operation": operation,
-        "model_level": access_result,
-        "record_level": record_result
-    });
+    result["model"] = json!(model);
+    result["operation"] = json!(operation);
 
     Ok(ok_text(result))
 }
@@ -811,12 +1431,14 @@ async fn op_create_batch(
         .await
         .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
 
+    let total = values_list.len() as u64;
     let mut created_ids = Vec::new();
     for values in values_list {
         let id = client
             .create(&model, values.clone(), context.clone())
             .await?;
         created_ids.push(id);
+        progress::progress(created_ids.len() as u64, Some(total), None);
     }
 
     Ok(ok_text(json!({
@@ -0,0 +1,155 @@
+use async_trait::async_trait;
+use futures::stream::SplitSink;
+use futures::{SinkExt, Stream, StreamExt};
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::sync::{Mutex, mpsc};
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_tungstenite::WebSocketStream;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+use mcp_rust_sdk::{
+    error::Error,
+    transport::{Message, Transport},
+};
+
+use super::batch::{Batches, Collected};
+use super::cursor_stdio::parse_jsonrpc_value;
+
+type Sink<S> = SplitSink<WebSocketStream<S>, WsMessage>;
+
+/// WebSocket transport for `--transport ws`.
+///
+/// Like the SDK's `WebSocketTransport`, it takes SDK tagged messages
+/// (`{ "type": "request", ... }`) and sends them back tagged. It also takes
+/// plain JSON-RPC objects and JSON-RPC batches; a batch is answered with one
+/// frame holding the array of its responses.
+///
+/// Frames are read by a task of their own, so the server can send (responses,
+/// notifications) while waiting for the next frame.
+pub struct WsTransport<S> {
+    sink: Arc<Mutex<Sink<S>>>,
+    /// Taken by the first `receive`, so no frame read before it is lost.
+    receiver: std::sync::Mutex<Option<mpsc::Receiver<Result<Message, Error>>>>,
+    batches: Arc<Batches>,
+}
+
+impl<S> WsTransport<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    pub fn from_stream(stream: WebSocketStream<S>) -> Self {
+        let (sink, mut frames) = stream.split();
+        let (sender, receiver) = mpsc::channel(100);
+        let transport = Self {
+            sink: Arc::new(Mutex::new(sink)),
+            receiver: std::sync::Mutex::new(Some(receiver)),
+            batches: Arc::new(Batches::default()),
+        };
+
+        let sink = transport.sink.clone();
+        let batches = transport.batches.clone();
+        tokio::spawn(async move {
+            while let Some(frame) = frames.next().await {
+                let text = match frame {
+                    Ok(WsMessage::Text(text)) => text,
+                    // Pings are answered by tungstenite itself.
+                    Ok(WsMessage::Ping(_) | WsMessage::Pong(_) | WsMessage::Frame(_)) => continue,
+                    Ok(WsMessage::Close(_)) => break,
+                    Ok(WsMessage::Binary(_)) => {
+                        let _ = sender
+                            .send(Err(Error::Transport(
+                                "Binary messages not supported".to_string(),
+                            )))
+                            .await;
+                        continue;
+                    }
+                    Err(err) => {
+                        let _ = sender.send(Err(Error::Transport(err.to_string()))).await;
+                        break;
+                    }
+                };
+
+                if let Ok(msg) = serde_json::from_str::<Message>(&text) {
+                    if sender.send(Ok(msg)).await.is_err() {
+                        break;
+                    }
+                    continue;
+                }
+                let msg = match serde_json::from_str::<serde_json::Value>(&text) {
+                    Ok(serde_json::Value::Array(items)) => {
+                        let (messages, reply) = batches.receive(items);
+                        if let Some(reply) = reply
+                            && let Err(err) = send_text(&sink, reply.to_string()).await
+                        {
+                            let _ = sender.send(Err(err)).await;
+                            break;
+                        }
+                        for msg in messages {
+                            if sender.send(Ok(msg)).await.is_err() {
+                                return;
+                            }
+                        }
+                        continue;
+                    }
+                    Ok(v) => parse_jsonrpc_value(v),
+                    Err(err) => Err(Error::Serialization(err.to_string())),
+                };
+                if sender.send(msg).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        transport
+    }
+}
+
+async fn send_text<S>(sink: &Mutex<Sink<S>>, text: String) -> Result<(), Error>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    sink.lock()
+        .await
+        .send(WsMessage::Text(text))
+        .await
+        .map_err(|e| Error::Transport(e.to_string()))
+}
+
+#[async_trait]
+impl<S> Transport for WsTransport<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    async fn send(&self, message: Message) -> Result<(), Error> {
+        let text = match message {
+            Message::Response(r) => match self.batches.collect(r) {
+                Collected::Single(r) => serde_json::to_string(&Message::Response(r))?,
+                Collected::Waiting => return Ok(()),
+                Collected::Complete(responses) => responses.to_string(),
+            },
+            message => serde_json::to_string(&message)?,
+        };
+        send_text(&self.sink, text).await
+    }
+
+    fn receive(&self) -> Pin<Box<dyn Stream<Item = Result<Message, Error>> + Send>> {
+        match self
+            .receiver
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()
+        {
+            Some(rx) => Box::pin(ReceiverStream::new(rx)),
+            None => Box::pin(futures::stream::empty()),
+        }
+    }
+
+    async fn close(&self) -> Result<(), Error> {
+        let mut sink = self.sink.lock().await;
+        sink.send(WsMessage::Close(None))
+            .await
+            .map_err(|e| Error::Transport(e.to_string()))
+    }
+}
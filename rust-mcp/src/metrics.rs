@@ -0,0 +1,353 @@
+//! Process-wide Prometheus metrics.
+//!
+//! Counters are recorded from the tool dispatcher, the Odoo clients, the metadata
+//! cache and the registry, and rendered in the Prometheus text exposition format by
+//! the HTTP transport's `/metrics` endpoint. Gauges that describe transport state
+//! (sessions, SSE subscribers) are sampled by the caller at scrape time.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+/// Upper bounds (seconds) of the tool call latency histogram buckets.
+const LATENCY_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0,
+];
+
+/// Outcome label of a tool call.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Success,
+    Error,
+}
+
+impl Outcome {
+    fn as_str(self) -> &'static str {
+        match self {
+            Outcome::Success => "success",
+            Outcome::Error => "error",
+        }
+    }
+}
+
+#[derive(Default)]
+struct Histogram {
+    buckets: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, secs: f64) {
+        if self.buckets.is_empty() {
+            self.buckets = vec![0; LATENCY_BUCKETS.len()];
+        }
+        for (bucket, bound) in self.buckets.iter_mut().zip(LATENCY_BUCKETS) {
+            if secs <= *bound {
+                *bucket += 1;
+            }
+        }
+        self.sum += secs;
+        self.count += 1;
+    }
+}
+
+/// (tool, instance, outcome)
+type ToolKey = (String, String, String);
+
+/// (instance, status)
+type RequestKey = (String, String);
+
+#[derive(Default)]
+pub struct Metrics {
+    tool_calls: Mutex<BTreeMap<ToolKey, Histogram>>,
+    odoo_requests: Mutex<BTreeMap<RequestKey, u64>>,
+    odoo_retries: Mutex<BTreeMap<String, u64>>,
+    cache_hits: AtomicU64,
+    cache_misses: AtomicU64,
+    reload_success: AtomicU64,
+    reload_failure: AtomicU64,
+}
+
+/// The process-wide metrics registry.
+pub fn global() -> &'static Metrics {
+    static METRICS: OnceLock<Metrics> = OnceLock::new();
+    METRICS.get_or_init(Metrics::default)
+}
+
+impl Metrics {
+    pub fn record_tool_call(
+        &self,
+        tool: &str,
+        instance: &str,
+        outcome: Outcome,
+        elapsed: Duration,
+    ) {
+        let key = (
+            tool.to_string(),
+            instance.to_string(),
+            outcome.as_str().to_string(),
+        );
+        let mut guard = self.tool_calls.lock().unwrap_or_else(|e| e.into_inner());
+        guard.entry(key).or_default().observe(elapsed.as_secs_f64());
+    }
+
+    /// Record one HTTP round trip to Odoo. `status` is the HTTP status code, or
+    /// `None` when the request failed before a response arrived.
+    pub fn record_odoo_request(&self, instance: &str, status: Option<u16>) {
+        let status = status.map_or_else(|| "error".to_string(), |s| s.to_string());
+        let mut guard = self.odoo_requests.lock().unwrap_or_else(|e| e.into_inner());
+        *guard.entry((instance.to_string(), status)).or_default() += 1;
+    }
+
+    pub fn record_odoo_retry(&self, instance: &str) {
+        let mut guard = self.odoo_retries.lock().unwrap_or_else(|e| e.into_inner());
+        *guard.entry(instance.to_string()).or_default() += 1;
+    }
+
+    pub fn record_cache_lookup(&self, hit: bool) {
+        let counter = if hit {
+            &self.cache_hits
+        } else {
+            &self.cache_misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_registry_reload(&self, ok: bool) {
+        let counter = if ok {
+            &self.reload_success
+        } else {
+            &self.reload_failure
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Render all metrics in the Prometheus text format. `gauges` are
+    /// `(name, help, value)` triples sampled by the caller.
+    pub fn render(&self, gauges: &[(&str, &str, u64)]) -> String {
+        let mut out = String::new();
+
+        {
+            let tool_calls = self.tool_calls.lock().unwrap_or_else(|e| e.into_inner());
+            header(
+                &mut out,
+                "odoo_mcp_tool_calls_total",
+                "counter",
+                "Tool calls by tool, instance and outcome.",
+            );
+            for ((tool, instance, outcome), h) in tool_calls.iter() {
+                let labels =
+                    labels(&[("tool", tool), ("instance", instance), ("outcome", outcome)]);
+                let _ = writeln!(out, "odoo_mcp_tool_calls_total{{{labels}}} {}", h.count);
+            }
+
+            header(
+                &mut out,
+                "odoo_mcp_tool_call_duration_seconds",
+                "histogram",
+                "Tool call latency in seconds.",
+            );
+            for ((tool, instance, outcome), h) in tool_calls.iter() {
+                let labels =
+                    labels(&[("tool", tool), ("instance", instance), ("outcome", outcome)]);
+                for (bound, n) in LATENCY_BUCKETS.iter().zip(&h.buckets) {
+                    let _ = writeln!(
+                        out,
+                        "odoo_mcp_tool_call_duration_seconds_bucket{{{labels},le=\"{bound}\"}} {n}"
+                    );
+                }
+                let _ = writeln!(
+                    out,
+                    "odoo_mcp_tool_call_duration_seconds_bucket{{{labels},le=\"+Inf\"}} {}",
+                    h.count
+                );
+                let _ = writeln!(
+                    out,
+                    "odoo_mcp_tool_call_duration_seconds_sum{{{labels}}} {}",
+                    h.sum
+                );
+                let _ = writeln!(
+                    out,
+                    "odoo_mcp_tool_call_duration_seconds_count{{{labels}}} {}",
+                    h.count
+                );
+            }
+        }
+
+        header(
+            &mut out,
+            "odoo_mcp_odoo_requests_total",
+            "counter",
+            "HTTP requests sent to Odoo by instance and response status (\"error\" when no response was received).",
+        );
+        for ((instance, status), n) in self
+            .odoo_requests
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+        {
+            let labels = labels(&[("instance", instance), ("status", status)]);
+            let _ = writeln!(out, "odoo_mcp_odoo_requests_total{{{labels}}} {n}");
+        }
+
+        header(
+            &mut out,
+            "odoo_mcp_odoo_retries_total",
+            "counter",
+            "Odoo requests re-sent after a retryable failure.",
+        );
+        for (instance, n) in self
+            .odoo_retries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+        {
+            let labels = labels(&[("instance", instance)]);
+            let _ = writeln!(out, "odoo_mcp_odoo_retries_total{{{labels}}} {n}");
+        }
+
+        header(
+            &mut out,
+            "odoo_mcp_metadata_cache_lookups_total",
+            "counter",
+            "Model metadata cache lookups by result.",
+        );
+        for (result, counter) in [("hit", &self.cache_hits), ("miss", &self.cache_misses)] {
+            let _ = writeln!(
+                out,
+                "odoo_mcp_metadata_cache_lookups_total{{result=\"{result}\"}} {}",
+                counter.load(Ordering::Relaxed)
+            );
+        }
+
+        header(
+            &mut out,
+            "odoo_mcp_registry_reloads_total",
+            "counter",
+            "Reloads of tools.json, prompts.json and server.json by result.",
+        );
+        for (result, counter) in [
+            ("success", &self.reload_success),
+            ("failure", &self.reload_failure),
+        ] {
+            let _ = writeln!(
+                out,
+                "odoo_mcp_registry_reloads_total{{result=\"{result}\"}} {}",
+                counter.load(Ordering::Relaxed)
+            );
+        }
+
+        for (name, help, value) in gauges {
+            header(&mut out, name, "gauge", help);
+            let _ = writeln!(out, "{name} {value}");
+        }
+
+        out
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+fn labels(pairs: &[(&str, &str)]) -> String {
+    pairs
+        .iter()
+        .map(|(k, v)| format!("{k}=\"{}\"", escape_label(v)))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tool_call_histogram() {
+        let m = Metrics::default();
+        m.record_tool_call(
+            "odoo_read",
+            "default",
+            Outcome::Success,
+            Duration::from_millis(20),
+        );
+        m.record_tool_call(
+            "odoo_read",
+            "default",
+            Outcome::Success,
+            Duration::from_secs(2),
+        );
+        m.record_tool_call("odoo_read", "default", Outcome::Error, Duration::ZERO);
+        let text = m.render(&[]);
+
+        let ok = "tool=\"odoo_read\",instance=\"default\",outcome=\"success\"";
+        assert!(text.contains(&format!("odoo_mcp_tool_calls_total{{{ok}}} 2")));
+        assert!(text.contains(&format!(
+            "odoo_mcp_tool_call_duration_seconds_bucket{{{ok},le=\"0.025\"}} 1"
+        )));
+        assert!(text.contains(&format!(
+            "odoo_mcp_tool_call_duration_seconds_bucket{{{ok},le=\"2.5\"}} 2"
+        )));
+        assert!(text.contains(&format!(
+            "odoo_mcp_tool_call_duration_seconds_bucket{{{ok},le=\"+Inf\"}} 2"
+        )));
+        assert!(text.contains(&format!(
+            "odoo_mcp_tool_call_duration_seconds_sum{{{ok}}} 2.02"
+        )));
+        assert!(text.contains(
+            "odoo_mcp_tool_calls_total{tool=\"odoo_read\",instance=\"default\",outcome=\"error\"} 1"
+        ));
+        assert!(text.contains("# TYPE odoo_mcp_tool_call_duration_seconds histogram"));
+    }
+
+    #[test]
+    fn test_odoo_request_and_retry_counters() {
+        let m = Metrics::default();
+        m.record_odoo_request("prod", Some(200));
+        m.record_odoo_request("prod", Some(503));
+        m.record_odoo_request("prod", Some(503));
+        m.record_odoo_request("prod", None);
+        m.record_odoo_retry("prod");
+        let text = m.render(&[]);
+
+        assert!(text.contains("odoo_mcp_odoo_requests_total{instance=\"prod\",status=\"200\"} 1"));
+        assert!(text.contains("odoo_mcp_odoo_requests_total{instance=\"prod\",status=\"503\"} 2"));
+        assert!(
+            text.contains("odoo_mcp_odoo_requests_total{instance=\"prod\",status=\"error\"} 1")
+        );
+        assert!(text.contains("odoo_mcp_odoo_retries_total{instance=\"prod\"} 1"));
+    }
+
+    #[test]
+    fn test_cache_reload_and_gauges() {
+        let m = Metrics::default();
+        m.record_cache_lookup(true);
+        m.record_cache_lookup(false);
+        m.record_cache_lookup(true);
+        m.record_registry_reload(true);
+        m.record_registry_reload(false);
+        let text = m.render(&[("odoo_mcp_http_sessions", "Active sessions.", 3)]);
+
+        assert!(text.contains("odoo_mcp_metadata_cache_lookups_total{result=\"hit\"} 2"));
+        assert!(text.contains("odoo_mcp_metadata_cache_lookups_total{result=\"miss\"} 1"));
+        assert!(text.contains("odoo_mcp_registry_reloads_total{result=\"success\"} 1"));
+        assert!(text.contains("odoo_mcp_registry_reloads_total{result=\"failure\"} 1"));
+        assert!(text.contains("# TYPE odoo_mcp_http_sessions gauge\nodoo_mcp_http_sessions 3\n"));
+    }
+
+    #[test]
+    fn test_label_escaping() {
+        assert_eq!(escape_label("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+    }
+}
@@ -0,0 +1,284 @@
+//! Per-instance circuit breaker.
+//!
+//! After `threshold` consecutive outages (transport errors or 5xx once retries are
+//! exhausted) the breaker opens and calls fail immediately instead of waiting
+//! through timeouts and backoff. After the cool-down one probe call is let
+//! through (half-open); its outcome closes or re-opens the breaker. Odoo business
+//! errors (access, validation, ...) prove the instance is up and count as success.
+
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use super::types::{OdooError, OdooResult};
+
+/// Consecutive failures that open the breaker.
+pub const DEFAULT_THRESHOLD: u32 = 5;
+
+/// How long the breaker stays open before a probe is allowed.
+pub const DEFAULT_COOLDOWN_SECS: u64 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakerState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+/// Point-in-time view of a breaker, as reported by `/health`.
+#[derive(Debug, Clone, Serialize)]
+pub struct BreakerSnapshot {
+    pub state: BreakerState,
+    pub consecutive_failures: u32,
+    pub last_error: Option<String>,
+    pub last_success: Option<DateTime<Utc>>,
+    pub last_failure: Option<DateTime<Utc>>,
+}
+
+struct Inner {
+    state: BreakerState,
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    probe_started: Option<Instant>,
+    last_error: Option<String>,
+    last_success: Option<DateTime<Utc>>,
+    last_failure: Option<DateTime<Utc>>,
+}
+
+pub struct CircuitBreaker {
+    threshold: u32,
+    cooldown: Duration,
+    inner: Mutex<Inner>,
+}
+
+impl CircuitBreaker {
+    /// `threshold` 0 disables the breaker.
+    pub fn new(threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            threshold,
+            cooldown,
+            inner: Mutex::new(Inner {
+                state: BreakerState::Closed,
+                consecutive_failures: 0,
+                opened_at: None,
+                probe_started: None,
+                last_error: None,
+                last_success: None,
+                last_failure: None,
+            }),
+        }
+    }
+
+    /// Read `ODOO_BREAKER_THRESHOLD` and `ODOO_BREAKER_COOLDOWN_SECS`.
+    pub fn from_env() -> Self {
+        let threshold = std::env::var("ODOO_BREAKER_THRESHOLD")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .unwrap_or(DEFAULT_THRESHOLD);
+        let cooldown = std::env::var("ODOO_BREAKER_COOLDOWN_SECS")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .unwrap_or(DEFAULT_COOLDOWN_SECS);
+        Self::new(threshold, Duration::from_secs(cooldown))
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Ask to send a request. `Err` carries the time left until the next probe.
+    pub fn acquire(&self) -> Result<(), Duration> {
+        if self.threshold == 0 {
+            return Ok(());
+        }
+        let mut inner = self.lock();
+        let now = Instant::now();
+        match inner.state {
+            BreakerState::Closed => Ok(()),
+            BreakerState::Open => {
+                let elapsed = inner.opened_at.map_or(self.cooldown, |t| now - t);
+                if elapsed >= self.cooldown {
+                    inner.state = BreakerState::HalfOpen;
+                    inner.probe_started = Some(now);
+                    Ok(())
+                } else {
+                    Err(self.cooldown - elapsed)
+                }
+            }
+            BreakerState::HalfOpen => {
+                // One probe at a time; a probe that never reported back (cancelled)
+                // is given up on after another cool-down.
+                match inner.probe_started {
+                    Some(t) if now - t < self.cooldown => Err(self.cooldown - (now - t)),
+                    _ => {
+                        inner.probe_started = Some(now);
+                        Ok(())
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn on_success(&self) {
+        let mut inner = self.lock();
+        inner.state = BreakerState::Closed;
+        inner.consecutive_failures = 0;
+        inner.opened_at = None;
+        inner.probe_started = None;
+        inner.last_success = Some(Utc::now());
+    }
+
+    pub fn on_failure(&self, error: &str) {
+        let mut inner = self.lock();
+        inner.consecutive_failures = inner.consecutive_failures.saturating_add(1);
+        inner.last_error = Some(error.to_string());
+        inner.last_failure = Some(Utc::now());
+        let trip = match inner.state {
+            BreakerState::HalfOpen => true,
+            BreakerState::Closed => {
+                self.threshold > 0 && inner.consecutive_failures >= self.threshold
+            }
+            BreakerState::Open => false,
+        };
+        if trip {
+            inner.state = BreakerState::Open;
+            inner.opened_at = Some(Instant::now());
+            inner.probe_started = None;
+        }
+    }
+
+    pub fn snapshot(&self) -> BreakerSnapshot {
+        let inner = self.lock();
+        BreakerSnapshot {
+            state: inner.state,
+            consecutive_failures: inner.consecutive_failures,
+            last_error: inner.last_error.clone(),
+            last_success: inner.last_success,
+            last_failure: inner.last_failure,
+        }
+    }
+
+    /// Run `call` through the breaker, failing fast while it is open.
+    pub async fn call<T>(
+        &self,
+        instance: &str,
+        call: impl Future<Output = OdooResult<T>>,
+    ) -> OdooResult<T> {
+        if let Err(wait) = self.acquire() {
+            let last_error = self.lock().last_error.clone().unwrap_or_default();
+            return Err(OdooError::Unavailable(format!(
+                "'{instance}' is failing ({last_error}); next attempt in {}s",
+                wait.as_secs().max(1)
+            )));
+        }
+        let result = call.await;
+        match &result {
+            Err(e) if is_outage(e) => self.on_failure(&e.to_string()),
+            _ => self.on_success(),
+        }
+        result
+    }
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self::new(
+            DEFAULT_THRESHOLD,
+            Duration::from_secs(DEFAULT_COOLDOWN_SECS),
+        )
+    }
+}
+
+/// Errors that say the instance itself is unhealthy rather than the request.
+fn is_outage(err: &OdooError) -> bool {
+    match err {
+        OdooError::Http(_) => true,
+        OdooError::Api { status, .. } | OdooError::Odoo { status, .. } => *status >= 500,
+        OdooError::InvalidResponse(_) | OdooError::Unavailable(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_opens_after_threshold_and_fails_fast() {
+        let b = CircuitBreaker::new(2, Duration::from_secs(60));
+        assert!(b.acquire().is_ok());
+        b.on_failure("boom");
+        assert_eq!(b.snapshot().state, BreakerState::Closed);
+        b.on_failure("boom again");
+
+        let snap = b.snapshot();
+        assert_eq!(snap.state, BreakerState::Open);
+        assert_eq!(snap.consecutive_failures, 2);
+        assert_eq!(snap.last_error.as_deref(), Some("boom again"));
+        assert!(b.acquire().is_err());
+    }
+
+    #[test]
+    fn test_success_resets_failures() {
+        let b = CircuitBreaker::new(2, Duration::from_secs(60));
+        b.on_failure("boom");
+        b.on_success();
+        b.on_failure("boom");
+        assert_eq!(b.snapshot().state, BreakerState::Closed);
+        assert!(b.snapshot().last_success.is_some());
+    }
+
+    #[test]
+    fn test_half_open_allows_one_probe() {
+        let b = CircuitBreaker::new(1, Duration::from_millis(20));
+        b.on_failure("down");
+        assert!(b.acquire().is_err());
+        std::thread::sleep(Duration::from_millis(30));
+
+        assert!(b.acquire().is_ok());
+        assert_eq!(b.snapshot().state, BreakerState::HalfOpen);
+        // Second caller waits for the probe.
+        assert!(b.acquire().is_err());
+
+        // Probe fails: open again.
+        b.on_failure("still down");
+        assert_eq!(b.snapshot().state, BreakerState::Open);
+        std::thread::sleep(Duration::from_millis(30));
+
+        // Probe succeeds: closed.
+        assert!(b.acquire().is_ok());
+        b.on_success();
+        assert_eq!(b.snapshot().state, BreakerState::Closed);
+        assert!(b.acquire().is_ok());
+    }
+
+    #[test]
+    fn test_zero_threshold_disables() {
+        let b = CircuitBreaker::new(0, Duration::from_secs(60));
+        for _ in 0..10 {
+            b.on_failure("boom");
+        }
+        assert!(b.acquire().is_ok());
+        assert_eq!(b.snapshot().state, BreakerState::Closed);
+    }
+
+    #[tokio::test]
+    async fn test_call_counts_only_outages() {
+        let b = CircuitBreaker::new(1, Duration::from_secs(60));
+        let business: OdooResult<()> = Err(OdooError::from_odoo(400, "denied".to_string(), None));
+        let _ = b.call("prod", async { business }).await;
+        assert_eq!(b.snapshot().state, BreakerState::Closed);
+
+        let outage: OdooResult<()> =
+            Err(OdooError::from_odoo(502, "Bad Gateway".to_string(), None));
+        let _ = b.call("prod", async { outage }).await;
+        assert_eq!(b.snapshot().state, BreakerState::Open);
+
+        let err = b.call("prod", async { Ok(()) }).await.unwrap_err();
+        assert!(matches!(err, OdooError::Unavailable(_)));
+        assert!(err.to_string().contains("'prod'"));
+    }
+}
@@ -1,11 +1,17 @@
+use std::sync::Arc;
 use std::time::Duration;
 
 use reqwest::header::{AUTHORIZATION, CONTENT_TYPE, HeaderMap, HeaderValue, USER_AGENT};
 use serde_json::{Value, json};
 use url::Url;
 
+use super::breaker::CircuitBreaker;
 use super::config::OdooInstanceConfig;
+use super::retry;
+use super::transport;
 use super::types::{OdooError, OdooErrorBody, OdooResult};
+use super::version;
+use crate::metrics;
 
 #[derive(Clone)]
 pub struct OdooHttpClient {
@@ -14,6 +20,12 @@ pub struct OdooHttpClient {
     api_key: String,
     http: reqwest::Client,
     max_retries: usize,
+    /// Instance name used to label metrics (defaults to the URL host).
+    instance: String,
+    /// Shared by clones, so one breaker guards each pooled instance.
+    breaker: Arc<CircuitBreaker>,
+    /// Major server version from the config (set from detection by the pool).
+    server_major: Option<u32>,
 }
 
 impl OdooHttpClient {
@@ -32,11 +44,9 @@ impl OdooHttpClient {
 
         let timeout = Duration::from_millis(cfg.timeout_ms.unwrap_or(30_000));
         let max_retries = cfg.max_retries.unwrap_or(3);
+        let instance = base_url.host_str().unwrap_or_default().to_string();
 
-        let http = reqwest::Client::builder()
-            .timeout(timeout)
-            .cookie_store(true)
-            .build()?;
+        let http = transport::build_http_client(cfg, timeout, true)?;
 
         Ok(Self {
             base_url,
@@ -44,9 +54,37 @@ impl OdooHttpClient {
             api_key,
             http,
             max_retries,
+            instance,
+            breaker: Arc::new(CircuitBreaker::from_env()),
+            server_major: version::configured_major(cfg),
         })
     }
 
+    /// Maximum number of re-sends after a retryable failure.
+    pub fn max_retries(&self) -> usize {
+        self.max_retries
+    }
+
+    pub fn server_major(&self) -> Option<u32> {
+        self.server_major
+    }
+
+    /// Label this client's metrics with the configured instance name.
+    pub fn with_instance_name(mut self, name: &str) -> Self {
+        self.instance = name.to_string();
+        self
+    }
+
+    /// Replace the circuit breaker configured from the environment.
+    pub fn with_circuit_breaker(mut self, breaker: CircuitBreaker) -> Self {
+        self.breaker = Arc::new(breaker);
+        self
+    }
+
+    pub fn breaker(&self) -> &CircuitBreaker {
+        &self.breaker
+    }
+
     fn headers(&self) -> anyhow::Result<HeaderMap> {
         let mut headers = HeaderMap::new();
         headers.insert(
@@ -78,16 +116,34 @@ impl OdooHttpClient {
     }
 
     async fn post_json2_raw(&self, model: &str, method: &str, body: Value) -> OdooResult<Value> {
+        self.breaker
+            .call(
+                &self.instance,
+                self.post_json2_attempts(model, method, body),
+            )
+            .await
+    }
+
+    async fn post_json2_attempts(
+        &self,
+        model: &str,
+        method: &str,
+        body: Value,
+    ) -> OdooResult<Value> {
         let url = self
             .endpoint(model, method)
             .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
         let headers = self
             .headers()
             .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+        let idempotent = retry::is_idempotent(method);
 
         let mut last_err: Option<OdooError> = None;
 
         for attempt in 0..=self.max_retries {
+            if attempt > 0 {
+                metrics::global().record_odoo_retry(&self.instance);
+            }
             let resp = self
                 .http
                 .post(url.clone())
@@ -96,9 +152,11 @@ impl OdooHttpClient {
                 .send()
                 .await;
 
-            match resp {
+            let wait = match resp {
                 Ok(r) => {
                     let status = r.status();
+                    metrics::global().record_odoo_request(&self.instance, Some(status.as_u16()));
+                    let retry_headers = r.headers().clone();
                     let text = r.text().await.unwrap_or_default();
                     if status.is_success() {
                         let v: Value = serde_json::from_str(&text).map_err(|e| {
@@ -109,34 +167,33 @@ impl OdooHttpClient {
                         return Ok(v);
                     }
 
-                    let parsed_err: Option<OdooErrorBody> = serde_json::from_str(&text).ok();
+                    let parsed_err = serde_json::from_str::<Value>(&text)
+                        .ok()
+                        .and_then(|v| OdooErrorBody::from_payload(&v));
                     let message = parsed_err
                         .as_ref()
                         .and_then(|b| b.message.clone())
                         .unwrap_or_else(|| text.clone());
-                    let err = OdooError::Api {
-                        status: status.as_u16(),
-                        message,
-                        body: parsed_err,
-                    };
-
-                    // Retry on 5xx and 429; do not retry auth/4xx.
-                    if status.is_server_error() || status.as_u16() == 429 {
-                        last_err = Some(err);
-                    } else {
+                    let err = OdooError::from_odoo(status.as_u16(), message, parsed_err);
+                    if !retry::retry_status(idempotent, status) {
                         return Err(err);
                     }
+                    last_err = Some(err);
+                    retry::delay(attempt, Some(status), Some(&retry_headers))
                 }
                 Err(e) => {
-                    // Retry on transport-level errors.
+                    metrics::global().record_odoo_request(&self.instance, None);
+                    if !retry::retry_transport_error(idempotent, &e) {
+                        return Err(OdooError::Http(e));
+                    }
                     last_err = Some(OdooError::Http(e));
+                    retry::backoff(attempt)
                 }
-            }
+            };
 
             if attempt < self.max_retries {
-                // Exponential backoff: 250ms, 500ms, 1s, 2s...
-                let backoff_ms = 250u64.saturating_mul(2u64.saturating_pow(attempt as u32));
-                tokio::time::sleep(Duration::from_millis(backoff_ms)).await;
+                retry::report(&self.instance, last_err.as_ref(), attempt + 1, wait);
+                tokio::time::sleep(wait).await;
             }
         }
 
@@ -146,6 +203,12 @@ impl OdooHttpClient {
     }
 
     pub async fn download_report_pdf(&self, report_name: &str, ids: &[i64]) -> OdooResult<Vec<u8>> {
+        self.breaker
+            .call(&self.instance, self.fetch_report_pdf(report_name, ids))
+            .await
+    }
+
+    async fn fetch_report_pdf(&self, report_name: &str, ids: &[i64]) -> OdooResult<Vec<u8>> {
         let mut url = self.base_url.clone();
         let ids_csv = ids
             .iter()
@@ -158,49 +221,61 @@ impl OdooHttpClient {
             .headers()
             .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
 
+        // Rendering a report does not change data.
+        let idempotent = true;
         let mut last_err: Option<OdooError> = None;
         for attempt in 0..=self.max_retries {
+            if attempt > 0 {
+                metrics::global().record_odoo_retry(&self.instance);
+            }
             let resp = self
                 .http
                 .get(url.clone())
                 .headers(headers.clone())
                 .send()
                 .await;
-            match resp {
+            let wait = match resp {
                 Ok(r) => {
                     let status = r.status();
+                    metrics::global().record_odoo_request(&self.instance, Some(status.as_u16()));
+                    let retry_headers = r.headers().clone();
                     if status.is_success() {
                         let bytes = r.bytes().await.map_err(OdooError::Http)?;
                         return Ok(bytes.to_vec());
                     }
 
                     let text = r.text().await.unwrap_or_default();
-                    let parsed_err: Option<OdooErrorBody> = serde_json::from_str(&text).ok();
+                    let parsed_err = serde_json::from_str::<Value>(&text)
+                        .ok()
+                        .and_then(|v| OdooErrorBody::from_payload(&v));
                     let message = parsed_err
                         .as_ref()
                         .and_then(|b| b.message.clone())
                         .unwrap_or_else(|| text.clone());
 
-                    let err = OdooError::Api {
-                        status: status.as_u16(),
-                        message,
-                        body: parsed_err,
-                    };
+                    let err = OdooError::from_odoo(status.as_u16(), message, parsed_err);
 
-                    if status.is_server_error() || status.as_u16() == 429 {
-                        last_err = Some(err);
-                    } else {
+                    if !retry::retry_status(idempotent, status) {
                         return Err(err);
                     }
+
+                    last_err = Some(err);
+
+                    retry::delay(attempt, Some(status), Some(&retry_headers))
                 }
                 Err(e) => {
+                    metrics::global().record_odoo_request(&self.instance, None);
+                    if !retry::retry_transport_error(idempotent, &e) {
+                        return Err(OdooError::Http(e));
+                    }
                     last_err = Some(OdooError::Http(e));
+                    retry::backoff(attempt)
                 }
-            }
+            };
 
             if attempt < self.max_retries {
-                let backoff_ms = 250u64.saturating_mul(2u64.saturating_pow(attempt as u32));
-                tokio::time::sleep(Duration::from_millis(backoff_ms)).await;
+                retry::report(&self.instance, last_err.as_ref(), attempt + 1, wait);
+                tokio::time::sleep(wait).await;
             }
         }
 
@@ -590,6 +665,8 @@ mod tests {
             version: Some("19".to_string()),
             timeout_ms: Some(5000),
             max_retries: Some(2),
+            protocol: None,
+            transport: Default::default(),
             extra: HashMap::new(),
         }
     }
@@ -643,6 +720,8 @@ mod tests {
             version: None,
             timeout_ms: None,
             max_retries: None,
+            protocol: None,
+            transport: Default::default(),
             extra: HashMap::new(),
         };
         let client = OdooHttpClient::new(&cfg).unwrap();
@@ -667,6 +746,8 @@ mod tests {
             version: None,
             timeout_ms: None,
             max_retries: None,
+            protocol: None,
+            transport: Default::default(),
             extra: HashMap::new(),
         };
         let client = OdooHttpClient::new(&cfg).unwrap();
@@ -0,0 +1,350 @@
+//! Shims for model methods that changed between Odoo 14 and 19.
+//!
+//! - `name_get` was removed in 17; `display_name` is read instead.
+//! - `read_group` is deprecated in 19 in favour of `formatted_read_group`, which
+//!   takes explicit `field:aggregator` specs and always groups by every key.
+//! - `check_access_rights`/`check_access_rule` gave way to `has_access` in 18.
+//!
+//! `OdooClient` picks the call from the server version; the helpers here
+//! translate arguments and results so tools keep seeing the older shapes.
+
+use serde_json::{Map, Value, json};
+
+/// First version without `name_get`.
+pub const DISPLAY_NAME_SINCE: u32 = 17;
+/// First version with a public `has_access`.
+pub const HAS_ACCESS_SINCE: u32 = 18;
+/// First version with a public `formatted_read_group`.
+pub const FORMATTED_READ_GROUP_SINCE: u32 = 19;
+
+/// `read(ids, ["display_name"])` -> `[[id, display_name], ...]`, as `name_get`
+/// returned it.
+pub fn display_names_to_name_get(records: &Value) -> Value {
+    let pairs = records
+        .as_array()
+        .map(|records| {
+            records
+                .iter()
+                .map(|r| {
+                    json!([
+                        r.get("id").cloned().unwrap_or(Value::Null),
+                        r.get("display_name").cloned().unwrap_or(Value::Bool(false))
+                    ])
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Value::Array(pairs)
+}
+
+/// One aggregated column: read_group returns it under `alias`,
+/// formatted_read_group under `spec` (`field:aggregator`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Aggregate {
+    alias: String,
+    field: String,
+    aggregator: Option<String>,
+}
+
+impl Aggregate {
+    /// `field`, `field:agg` or `alias:agg(field)`.
+    fn parse(spec: &str) -> Option<Self> {
+        let spec = spec.trim();
+        let (name, func) = match spec.split_once(':') {
+            Some((name, func)) => (name, Some(func)),
+            None => (spec, None),
+        };
+        if name.is_empty() {
+            return None;
+        }
+        let (aggregator, field) = match func {
+            Some(func) => match func.split_once('(') {
+                Some((agg, field)) => (Some(agg), field.trim_end_matches(')')),
+                None => (Some(func), name),
+            },
+            None => (None, name),
+        };
+        Some(Self {
+            alias: name.to_string(),
+            field: field.to_string(),
+            aggregator: aggregator.filter(|a| !a.is_empty()).map(str::to_string),
+        })
+    }
+
+    fn spec(&self) -> Option<String> {
+        Some(format!("{}:{}", self.field, self.aggregator.as_deref()?))
+    }
+}
+
+/// A `read_group` call expressed as `formatted_read_group`.
+#[derive(Debug, Clone)]
+pub struct ReadGroupShim {
+    domain: Value,
+    /// Keys grouped by in this call: the first one only when lazy.
+    groupby: Vec<String>,
+    /// Keys left for the next level of a lazy grouping.
+    remaining: Vec<String>,
+    aggregates: Vec<Aggregate>,
+    count_key: String,
+}
+
+impl ReadGroupShim {
+    pub fn new(domain: Option<Value>, fields: &[String], groupby: &[String], lazy: bool) -> Self {
+        let base = |key: &str| key.split(':').next().unwrap_or(key).to_string();
+        let grouped: Vec<String> = groupby.iter().map(|g| base(g)).collect();
+        let aggregates = fields
+            .iter()
+            .filter(|f| f.as_str() != "__count")
+            .filter_map(|f| Aggregate::parse(f))
+            .filter(|a| a.aggregator.is_some() || !grouped.contains(&a.field))
+            .collect();
+
+        let (groupby, remaining, count_key) = if lazy && !groupby.is_empty() {
+            (
+                groupby[..1].to_vec(),
+                groupby[1..].to_vec(),
+                format!("{}_count", grouped[0]),
+            )
+        } else {
+            (groupby.to_vec(), Vec::new(), "__count".to_string())
+        };
+
+        Self {
+            domain: domain.unwrap_or_else(|| json!([])),
+            groupby,
+            remaining,
+            aggregates,
+            count_key,
+        }
+    }
+
+    /// Fields requested without an aggregator, which read_group aggregated with
+    /// the field's default one.
+    pub fn bare_fields(&self) -> Vec<String> {
+        self.aggregates
+            .iter()
+            .filter(|a| a.aggregator.is_none())
+            .map(|a| a.field.clone())
+            .collect()
+    }
+
+    /// Fill in default aggregators from `fields_get(attributes=["aggregator"])`.
+    /// Fields without one are dropped, as read_group did.
+    pub fn resolve_aggregators(&mut self, fields_meta: &Value) {
+        for aggregate in &mut self.aggregates {
+            if aggregate.aggregator.is_none() {
+                aggregate.aggregator = fields_meta
+                    .get(&aggregate.field)
+                    .and_then(|f| f.get("aggregator"))
+                    .and_then(Value::as_str)
+                    .map(str::to_string);
+            }
+        }
+        self.aggregates.retain(|a| a.aggregator.is_some());
+    }
+
+    /// Keyword arguments of `formatted_read_group`.
+    pub fn params(
+        &self,
+        offset: Option<i64>,
+        limit: Option<i64>,
+        orderby: Option<String>,
+    ) -> Map<String, Value> {
+        let mut aggregates: Vec<String> =
+            self.aggregates.iter().filter_map(Aggregate::spec).collect();
+        aggregates.push("__count".to_string());
+
+        let mut params = Map::new();
+        params.insert("domain".to_string(), self.domain.clone());
+        params.insert("groupby".to_string(), json!(self.groupby));
+        params.insert("aggregates".to_string(), json!(aggregates));
+        if let Some(v) = offset {
+            params.insert("offset".to_string(), json!(v));
+        }
+        if let Some(v) = limit {
+            params.insert("limit".to_string(), json!(v));
+        }
+        if let Some(order) = orderby {
+            params.insert("order".to_string(), json!(self.order(&order)));
+        }
+        params
+    }
+
+    /// read_group orders by aggregate aliases; formatted_read_group by specs.
+    fn order(&self, orderby: &str) -> String {
+        orderby
+            .split(',')
+            .map(|term| {
+                let term = term.trim();
+                let (key, direction) = term.split_once(' ').unwrap_or((term, ""));
+                let key = self
+                    .aggregates
+                    .iter()
+                    .find(|a| a.alias == key)
+                    .and_then(Aggregate::spec)
+                    .unwrap_or_else(|| key.to_string());
+                format!("{key} {direction}").trim_end().to_string()
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Reshape `formatted_read_group` rows like read_group's: aggregates under
+    /// their alias, `<groupby>_count` when lazy, and `__domain`/`__context`.
+    pub fn to_read_group(&self, groups: Value) -> Value {
+        let Value::Array(groups) = groups else {
+            return groups;
+        };
+        let rows = groups
+            .into_iter()
+            .map(|group| {
+                let mut row = Map::new();
+                for key in &self.groupby {
+                    let value = group.get(key).cloned().unwrap_or(Value::Bool(false));
+                    row.insert(key.clone(), granularity_label(key, value));
+                }
+                for aggregate in &self.aggregates {
+                    let value = aggregate
+                        .spec()
+                        .and_then(|spec| group.get(&spec).cloned())
+                        .unwrap_or(Value::Null);
+                    row.insert(aggregate.alias.clone(), value);
+                }
+                row.insert(
+                    self.count_key.clone(),
+                    group.get("__count").cloned().unwrap_or(json!(0)),
+                );
+
+                let mut domain = self.domain.as_array().cloned().unwrap_or_default();
+                if let Some(extra) = group.get("__extra_domain").and_then(Value::as_array) {
+                    domain.extend(extra.iter().cloned());
+                }
+                row.insert("__domain".to_string(), Value::Array(domain));
+                if !self.remaining.is_empty() {
+                    row.insert("__context".to_string(), json!({"group_by": self.remaining}));
+                }
+                Value::Object(row)
+            })
+            .collect();
+        Value::Array(rows)
+    }
+}
+
+/// Dates grouped by `field:granularity` come back as `[value, label]`; read_group
+/// returned the label only.
+fn granularity_label(key: &str, value: Value) -> Value {
+    if !key.contains(':') {
+        return value;
+    }
+    match value.get(1) {
+        Some(Value::String(label)) => Value::String(label.clone()),
+        _ => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_names_to_name_get() {
+        let records = json!([
+            {"id": 1, "display_name": "ACME"},
+            {"id": 2, "display_name": "ACME, Bob"}
+        ]);
+        assert_eq!(
+            display_names_to_name_get(&records),
+            json!([[1, "ACME"], [2, "ACME, Bob"]])
+        );
+    }
+
+    #[test]
+    fn test_parse_aggregate_specs() {
+        let a = Aggregate::parse("amount_total").unwrap();
+        assert_eq!(
+            (a.alias.as_str(), a.field.as_str()),
+            ("amount_total", "amount_total")
+        );
+        assert_eq!(a.aggregator, None);
+
+        let a = Aggregate::parse("amount_total:sum").unwrap();
+        assert_eq!(a.spec().as_deref(), Some("amount_total:sum"));
+
+        let a = Aggregate::parse("biggest:max(amount_total)").unwrap();
+        assert_eq!(a.alias, "biggest");
+        assert_eq!(a.spec().as_deref(), Some("amount_total:max"));
+    }
+
+    #[test]
+    fn test_lazy_read_group_params_and_result() {
+        let fields = vec![
+            "partner_id".to_string(),
+            "amount_total".to_string(),
+            "biggest:max(amount_total)".to_string(),
+            "name".to_string(),
+        ];
+        let groupby = vec!["partner_id".to_string(), "date_order:month".to_string()];
+        let mut shim = ReadGroupShim::new(
+            Some(json!([["state", "=", "sale"]])),
+            &fields,
+            &groupby,
+            true,
+        );
+
+        assert_eq!(shim.bare_fields(), vec!["amount_total", "name"]);
+        shim.resolve_aggregators(&json!({
+            "amount_total": {"aggregator": "sum"},
+            "name": {}
+        }));
+
+        let params = shim.params(None, Some(10), Some("biggest desc, partner_id".to_string()));
+        assert_eq!(params["groupby"], json!(["partner_id"]));
+        assert_eq!(
+            params["aggregates"],
+            json!(["amount_total:sum", "amount_total:max", "__count"])
+        );
+        assert_eq!(params["order"], json!("amount_total:max desc, partner_id"));
+        assert_eq!(params["limit"], json!(10));
+
+        let rows = shim.to_read_group(json!([{
+            "partner_id": [3, "ACME"],
+            "amount_total:sum": 150.0,
+            "amount_total:max": 100.0,
+            "__count": 2,
+            "__extra_domain": [["partner_id", "=", 3]]
+        }]));
+        assert_eq!(
+            rows,
+            json!([{
+                "partner_id": [3, "ACME"],
+                "amount_total": 150.0,
+                "biggest": 100.0,
+                "partner_id_count": 2,
+                "__domain": [["state", "=", "sale"], ["partner_id", "=", 3]],
+                "__context": {"group_by": ["date_order:month"]}
+            }])
+        );
+    }
+
+    #[test]
+    fn test_eager_read_group_uses_all_keys() {
+        let groupby = vec!["date_order:month".to_string(), "user_id".to_string()];
+        let shim = ReadGroupShim::new(None, &["amount_total:sum".to_string()], &groupby, false);
+        assert!(shim.bare_fields().is_empty());
+        assert_eq!(
+            shim.params(None, None, None)["groupby"],
+            json!(["date_order:month", "user_id"])
+        );
+
+        let rows = shim.to_read_group(json!([{
+            "date_order:month": ["2024-01-01", "January 2024"],
+            "user_id": false,
+            "amount_total:sum": 10.0,
+            "__count": 1,
+            "__extra_domain": []
+        }]));
+        assert_eq!(rows[0]["date_order:month"], "January 2024");
+        assert_eq!(rows[0]["__count"], 1);
+        assert!(rows[0].get("__context").is_none());
+    }
+}
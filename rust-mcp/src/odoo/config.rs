@@ -3,6 +3,8 @@ use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use super::secrets;
+
 /// Authentication mode for Odoo instances.
 /// - `ApiKey`: Odoo 19+ JSON-2 API with bearer token
 /// - `Password`: Odoo < 19 JSON-RPC with username/password
@@ -13,7 +15,79 @@ pub enum OdooAuthMode {
     Password,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Wire protocol of the legacy (username/password) client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OdooProtocol {
+    /// `/jsonrpc` and `/web/session/authenticate`.
+    #[default]
+    JsonRpc,
+    /// `/xmlrpc/2/common` and `/xmlrpc/2/object`, for proxies that only expose those.
+    XmlRpc,
+}
+
+/// How to reach an instance behind an internal CA, an HTTP proxy, mTLS or a
+/// reverse proxy that wants its own headers. Applies to RPC calls, report
+/// downloads and the version probe.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct OdooTransportConfig {
+    /// PEM file of CA certificates to trust in addition to the built-in roots.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ca_bundle: Option<String>,
+    /// PEM client certificate for mTLS (`clientKey` is required with it).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_cert: Option<String>,
+    /// PEM private key matching `clientCert`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_key: Option<String>,
+    /// Accept any server certificate. For development only.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub insecure_skip_verify: bool,
+    /// Proxy URL for all requests, e.g. `http://proxy.internal:3128`. Without it
+    /// the `HTTP(S)_PROXY` environment variables apply.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    /// Comma-separated hosts, domains or CIDRs that bypass `proxy`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_proxy: Option<String>,
+    /// Static headers sent with every request (e.g. Cloudflare Access tokens or
+    /// basic auth for a fronting proxy). JSON-2 keeps its own `Authorization`.
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+}
+
+impl OdooTransportConfig {
+    /// Single-instance settings: `ODOO_CA_BUNDLE`, `ODOO_CLIENT_CERT`,
+    /// `ODOO_CLIENT_KEY`, `ODOO_INSECURE_SKIP_VERIFY`, `ODOO_PROXY`,
+    /// `ODOO_NO_PROXY` and `ODOO_EXTRA_HEADERS` (a JSON object).
+    pub fn from_env() -> anyhow::Result<Self> {
+        let var = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        let headers = match var("ODOO_EXTRA_HEADERS") {
+            Some(raw) => serde_json::from_str(&raw).map_err(|e| {
+                anyhow::anyhow!("ODOO_EXTRA_HEADERS must be a JSON object of strings: {e}")
+            })?,
+            None => HashMap::new(),
+        };
+        Ok(Self {
+            ca_bundle: var("ODOO_CA_BUNDLE"),
+            client_cert: var("ODOO_CLIENT_CERT"),
+            client_key: var("ODOO_CLIENT_KEY"),
+            insecure_skip_verify: var("ODOO_INSECURE_SKIP_VERIFY")
+                .is_some_and(|v| matches!(v.to_lowercase().as_str(), "1" | "true" | "yes" | "on")),
+            proxy: var("ODOO_PROXY"),
+            no_proxy: var("ODOO_NO_PROXY"),
+            headers,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OdooInstanceConfig {
     pub url: String,
     #[serde(default)]
@@ -33,6 +107,12 @@ pub struct OdooInstanceConfig {
     pub timeout_ms: Option<u64>,
     #[serde(default)]
     pub max_retries: Option<usize>,
+    /// `"jsonrpc"` (default) or `"xmlrpc"`. XML-RPC always uses username/password.
+    #[serde(default)]
+    pub protocol: Option<OdooProtocol>,
+    /// TLS, proxy and extra headers used to reach the instance.
+    #[serde(flatten)]
+    pub transport: OdooTransportConfig,
 
     // Allow extra fields in ODOO_INSTANCES JSON.
     #[serde(flatten, default)]
@@ -42,6 +122,9 @@ pub struct OdooInstanceConfig {
 impl OdooInstanceConfig {
     /// Determine authentication mode based on version or available credentials.
     pub fn auth_mode(&self) -> OdooAuthMode {
+        if self.protocol == Some(OdooProtocol::XmlRpc) {
+            return OdooAuthMode::Password;
+        }
         // If version is explicitly set and < 19, use password mode
         if let Some(v) = &self.version
             && let Ok(major) = v.split('.').next().unwrap_or(v).parse::<u32>()
@@ -64,6 +147,64 @@ impl OdooInstanceConfig {
     }
 }
 
+/// Odoo credentials of the end user behind an MCP session, used instead of the
+/// instance's service credentials so Odoo applies that user's access rules and
+/// records them as the author of changes.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct UserCredentials {
+    api_key: Option<String>,
+    login: Option<String>,
+    password: Option<String>,
+}
+
+impl std::fmt::Debug for UserCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UserCredentials")
+            .field("login", &self.login)
+            .finish_non_exhaustive()
+    }
+}
+
+impl UserCredentials {
+    /// An API key (with the login for Odoo < 19), or a login and password.
+    /// Returns `Ok(None)` when nothing is given.
+    pub fn from_parts(
+        api_key: Option<String>,
+        login: Option<String>,
+        password: Option<String>,
+    ) -> anyhow::Result<Option<Self>> {
+        let clean = |v: Option<String>| v.map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+        let (api_key, login, password) = (clean(api_key), clean(login), clean(password));
+        if api_key.is_none() && login.is_none() && password.is_none() {
+            return Ok(None);
+        }
+        if api_key.is_none() && (login.is_none() || password.is_none()) {
+            anyhow::bail!("Odoo user credentials need an API key, or a login and a password");
+        }
+        Ok(Some(Self {
+            api_key,
+            login,
+            password,
+        }))
+    }
+
+    /// Name for logs: the login, or a marker for API-key-only credentials.
+    pub fn label(&self) -> &str {
+        self.login.as_deref().unwrap_or("<api key>")
+    }
+
+    /// `cfg` with its credentials replaced by these. An API key doubles as the
+    /// password of JSON-RPC and XML-RPC, which Odoo accepts since 14.0.
+    pub fn apply(&self, cfg: &OdooInstanceConfig) -> OdooInstanceConfig {
+        OdooInstanceConfig {
+            api_key: self.api_key.clone(),
+            username: self.login.clone(),
+            password: self.password.clone().or_else(|| self.api_key.clone()),
+            ..cfg.clone()
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct OdooEnvConfig {
     pub instances: HashMap<String, OdooInstanceConfig>,
@@ -170,6 +311,10 @@ pub fn load_odoo_env() -> anyhow::Result<OdooEnvConfig> {
                     max_retries: std::env::var("ODOO_MAX_RETRIES")
                         .ok()
                         .and_then(|v| v.parse().ok()),
+                    protocol: std::env::var("ODOO_PROTOCOL").ok().and_then(|v| {
+                        serde_json::from_value(Value::String(v.trim().to_lowercase())).ok()
+                    }),
+                    transport: OdooTransportConfig::from_env()?,
                     extra: HashMap::new(),
                 },
             );
@@ -264,6 +409,14 @@ pub fn load_odoo_env() -> anyhow::Result<OdooEnvConfig> {
                 }
             }
         }
+
+        secrets::resolve_field(&mut cfg.api_key, &format!("apiKey of instance '{name}'"))?;
+        secrets::resolve_field(&mut cfg.password, &format!("password of instance '{name}'"))?;
+        for (header, value) in cfg.transport.headers.iter_mut() {
+            *value = secrets::resolve(value).map_err(|e| {
+                anyhow::anyhow!("Cannot resolve header '{header}' of instance '{name}': {e}")
+            })?;
+        }
     }
 
     Ok(OdooEnvConfig { instances })
@@ -282,6 +435,38 @@ fn normalize_url(raw: &str) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_user_credentials_replace_service_user() {
+        let service = OdooInstanceConfig {
+            url: "http://localhost".to_string(),
+            db: Some("prod".to_string()),
+            api_key: None,
+            username: Some("service".to_string()),
+            password: Some("secret".to_string()),
+            version: Some("17".to_string()),
+            timeout_ms: None,
+            max_retries: None,
+            protocol: None,
+            transport: Default::default(),
+            extra: HashMap::new(),
+        };
+        let user = UserCredentials::from_parts(Some(" key ".into()), Some("alice".into()), None)
+            .unwrap()
+            .unwrap();
+        let cfg = user.apply(&service);
+        assert_eq!(cfg.username.as_deref(), Some("alice"));
+        assert_eq!(cfg.password.as_deref(), Some("key"));
+        assert_eq!(cfg.db.as_deref(), Some("prod"));
+        assert!(!format!("{user:?}").contains("key"));
+
+        assert!(
+            UserCredentials::from_parts(None, None, Some(String::new()))
+                .unwrap()
+                .is_none()
+        );
+        assert!(UserCredentials::from_parts(None, Some("alice".into()), None).is_err());
+    }
+
     #[test]
     fn test_normalize_url_with_scheme() {
         assert_eq!(normalize_url("https://example.com"), "https://example.com");
@@ -313,6 +498,8 @@ mod tests {
             version: None,
             timeout_ms: None,
             max_retries: None,
+            protocol: None,
+            transport: Default::default(),
             extra: HashMap::new(),
         };
         assert_eq!(config.auth_mode(), OdooAuthMode::ApiKey);
@@ -329,6 +516,8 @@ mod tests {
             version: Some("18".to_string()),
             timeout_ms: None,
             max_retries: None,
+            protocol: None,
+            transport: Default::default(),
             extra: HashMap::new(),
         };
         assert_eq!(config.auth_mode(), OdooAuthMode::Password);
@@ -345,6 +534,8 @@ mod tests {
             version: None,
             timeout_ms: None,
             max_retries: None,
+            protocol: None,
+            transport: Default::default(),
             extra: HashMap::new(),
         };
         assert_eq!(config.auth_mode(), OdooAuthMode::Password);
@@ -361,6 +552,8 @@ mod tests {
             version: Some("19".to_string()),
             timeout_ms: None,
             max_retries: None,
+            protocol: None,
+            transport: Default::default(),
             extra: HashMap::new(),
         };
         assert_eq!(config.auth_mode(), OdooAuthMode::ApiKey);
@@ -399,4 +592,24 @@ mod tests {
         assert_eq!(config.password, Some("admin123".to_string()));
         assert_eq!(config.auth_mode(), OdooAuthMode::Password);
     }
+
+    #[test]
+    fn test_instance_config_deserialize_xmlrpc() {
+        let json = r#"{
+            "url": "http://localhost:8069",
+            "db": "mydb",
+            "username": "admin",
+            "password": "admin123",
+            "apiKey": "unused",
+            "protocol": "xmlrpc"
+        }"#;
+        let config: OdooInstanceConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.protocol, Some(OdooProtocol::XmlRpc));
+        assert!(!config.extra.contains_key("protocol"));
+        // XML-RPC always authenticates with username/password.
+        assert_eq!(config.auth_mode(), OdooAuthMode::Password);
+
+        let bad = r#"{"url": "http://localhost", "protocol": "soap"}"#;
+        assert!(serde_json::from_str::<OdooInstanceConfig>(bad).is_err());
+    }
 }
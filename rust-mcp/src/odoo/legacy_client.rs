@@ -3,11 +3,24 @@ use std::time::Duration;
 
 use reqwest::header::{CONTENT_TYPE, HeaderMap, HeaderValue, USER_AGENT};
 use serde_json::{Value, json};
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
 use url::Url;
 
-use super::config::OdooInstanceConfig;
-use super::types::{OdooError, OdooErrorBody, OdooResult};
+use super::breaker::CircuitBreaker;
+use super::config::{OdooInstanceConfig, OdooProtocol};
+use super::retry;
+use super::session::{SessionInfo, needs_relogin};
+use super::transport;
+use super::types::{OdooError, OdooErrorBody, OdooErrorKind, OdooResult};
+use super::version;
+use super::xmlrpc;
+use crate::metrics;
+
+/// Request body for [`OdooLegacyClient::rpc_attempts`].
+enum RpcBody {
+    Json(Value),
+    Xml(String),
+}
 
 /// Odoo Legacy JSON-RPC client for Odoo < 19.
 /// Uses /jsonrpc endpoint with username/password authentication.
@@ -19,8 +32,17 @@ pub struct OdooLegacyClient {
     password: String,
     http: reqwest::Client,
     max_retries: usize,
-    /// Cached user ID after authentication
-    uid: Arc<RwLock<Option<i64>>>,
+    protocol: OdooProtocol,
+    /// Instance name used to label metrics (defaults to the URL host).
+    instance: String,
+    /// Shared by clones, so one breaker guards each pooled instance.
+    breaker: Arc<CircuitBreaker>,
+    /// Login state, shared by clones and cleared when Odoo rejects it.
+    session: Arc<RwLock<Option<SessionInfo>>>,
+    /// Serializes logins so concurrent calls do not each authenticate.
+    login_lock: Arc<Mutex<()>>,
+    /// Major server version from the config (set from detection by the pool).
+    server_major: Option<u32>,
 }
 
 impl OdooLegacyClient {
@@ -43,11 +65,9 @@ impl OdooLegacyClient {
 
         let timeout = Duration::from_millis(cfg.timeout_ms.unwrap_or(30_000));
         let max_retries = cfg.max_retries.unwrap_or(3);
+        let instance = base_url.host_str().unwrap_or_default().to_string();
 
-        let http = reqwest::Client::builder()
-            .timeout(timeout)
-            .cookie_store(true)
-            .build()?;
+        let http = transport::build_http_client(cfg, timeout, true)?;
 
         Ok(Self {
             base_url,
@@ -56,10 +76,44 @@ impl OdooLegacyClient {
             password,
             http,
             max_retries,
-            uid: Arc::new(RwLock::new(None)),
+            protocol: cfg.protocol.unwrap_or_default(),
+            instance,
+            breaker: Arc::new(CircuitBreaker::from_env()),
+            session: Arc::new(RwLock::new(None)),
+            login_lock: Arc::new(Mutex::new(())),
+            server_major: version::configured_major(cfg),
         })
     }
 
+    /// Maximum number of re-sends after a retryable failure.
+    pub fn max_retries(&self) -> usize {
+        self.max_retries
+    }
+
+    pub fn server_major(&self) -> Option<u32> {
+        self.server_major
+    }
+
+    pub fn protocol(&self) -> OdooProtocol {
+        self.protocol
+    }
+
+    /// Label this client's metrics with the configured instance name.
+    pub fn with_instance_name(mut self, name: &str) -> Self {
+        self.instance = name.to_string();
+        self
+    }
+
+    /// Replace the circuit breaker configured from the environment.
+    pub fn with_circuit_breaker(mut self, breaker: CircuitBreaker) -> Self {
+        self.breaker = Arc::new(breaker);
+        self
+    }
+
+    pub fn breaker(&self) -> &CircuitBreaker {
+        &self.breaker
+    }
+
     fn headers(&self) -> HeaderMap {
         let mut headers = HeaderMap::new();
         headers.insert(
@@ -71,8 +125,12 @@ impl OdooLegacyClient {
     }
 
     fn jsonrpc_endpoint(&self) -> Url {
+        self.endpoint("/jsonrpc")
+    }
+
+    fn endpoint(&self, path: &str) -> Url {
         let mut url = self.base_url.clone();
-        url.set_path("/jsonrpc");
+        url.set_path(path);
         url
     }
 
@@ -90,87 +148,103 @@ impl OdooLegacyClient {
         })
     }
 
-    /// Send a JSON-RPC request and extract the result
-    async fn jsonrpc_call(&self, service: &str, method: &str, args: Value) -> OdooResult<Value> {
-        let url = self.jsonrpc_endpoint();
-        let headers = self.headers();
-        let body = self.build_jsonrpc_request(service, method, args);
+    /// Call `service.method(*args)` over the configured protocol and extract the result
+    async fn rpc_call(&self, service: &str, method: &str, args: Value) -> OdooResult<Value> {
+        // execute_kw carries the model method as its fifth argument.
+        let idempotent = match service {
+            "object" => args
+                .get(4)
+                .and_then(|m| m.as_str())
+                .is_some_and(retry::is_idempotent),
+            _ => retry::is_idempotent(method),
+        };
+        let (url, body) = match self.protocol {
+            OdooProtocol::JsonRpc => (
+                self.jsonrpc_endpoint(),
+                RpcBody::Json(self.build_jsonrpc_request(service, method, args)),
+            ),
+            OdooProtocol::XmlRpc => {
+                let params = match args {
+                    Value::Array(params) => params,
+                    other => vec![other],
+                };
+                (
+                    self.endpoint(&format!("/xmlrpc/2/{service}")),
+                    RpcBody::Xml(xmlrpc::encode_call(method, &params)),
+                )
+            }
+        };
+        self.breaker
+            .call(&self.instance, self.rpc_attempts(url, body, idempotent))
+            .await
+    }
 
+    /// POST an RPC payload to `url` with retries and extract the result.
+    async fn rpc_attempts(&self, url: Url, body: RpcBody, idempotent: bool) -> OdooResult<Value> {
+        let mut headers = self.headers();
+        if let RpcBody::Xml(_) = body {
+            headers.insert(
+                CONTENT_TYPE,
+                HeaderValue::from_static("text/xml; charset=utf-8"),
+            );
+        }
         let mut last_err: Option<OdooError> = None;
 
         for attempt in 0..=self.max_retries {
-            let resp = self
-                .http
-                .post(url.clone())
-                .headers(headers.clone())
-                .json(&body)
-                .send()
-                .await;
-
-            match resp {
+            if attempt > 0 {
+                metrics::global().record_odoo_retry(&self.instance);
+            }
+            let request = self.http.post(url.clone()).headers(headers.clone());
+            let request = match &body {
+                RpcBody::Json(payload) => request.json(payload),
+                RpcBody::Xml(payload) => request.body(payload.clone()),
+            };
+            let resp = request.send().await;
+
+            let wait = match resp {
                 Ok(r) => {
                     let status = r.status();
+                    metrics::global().record_odoo_request(&self.instance, Some(status.as_u16()));
+                    let retry_headers = r.headers().clone();
                     let text = r.text().await.unwrap_or_default();
 
                     if status.is_success() {
-                        let v: Value = serde_json::from_str(&text).map_err(|e| {
-                            OdooError::InvalidResponse(format!(
-                                "Failed to parse JSON-RPC response: {e}. Body: {text}"
-                            ))
-                        })?;
-
-                        // Check for JSON-RPC error
-                        if let Some(error) = v.get("error") {
-                            let message = error
-                                .get("data")
-                                .and_then(|d| d.get("message"))
-                                .and_then(|m| m.as_str())
-                                .or_else(|| error.get("message").and_then(|m| m.as_str()))
-                                .unwrap_or("Unknown JSON-RPC error")
-                                .to_string();
-
-                            return Err(OdooError::Api {
-                                status: 400,
-                                message,
-                                body: None,
-                            });
-                        }
-
-                        // Extract result
-                        if let Some(result) = v.get("result") {
-                            return Ok(result.clone());
-                        }
-
-                        return Err(OdooError::InvalidResponse(
-                            "JSON-RPC response missing 'result' field".to_string(),
-                        ));
+                        return match &body {
+                            RpcBody::Json(_) => parse_jsonrpc_response(&text),
+                            RpcBody::Xml(_) => xmlrpc::decode_response(&text),
+                        };
                     }
 
-                    let parsed_err: Option<OdooErrorBody> = serde_json::from_str(&text).ok();
+                    let parsed_err = serde_json::from_str::<Value>(&text)
+                        .ok()
+                        .and_then(|v| OdooErrorBody::from_payload(&v));
                     let message = parsed_err
                         .as_ref()
                         .and_then(|b| b.message.clone())
                         .unwrap_or_else(|| text.clone());
-                    let err = OdooError::Api {
-                        status: status.as_u16(),
-                        message,
-                        body: parsed_err,
-                    };
-
-                    if status.is_server_error() || status.as_u16() == 429 {
-                        last_err = Some(err);
-                    } else {
+                    let err = OdooError::from_odoo(status.as_u16(), message, parsed_err);
+
+                    if !retry::retry_status(idempotent, status) {
                         return Err(err);
                     }
+
+                    last_err = Some(err);
+
+                    retry::delay(attempt, Some(status), Some(&retry_headers))
                 }
                 Err(e) => {
+                    metrics::global().record_odoo_request(&self.instance, None);
+                    if !retry::retry_transport_error(idempotent, &e) {
+                        return Err(OdooError::Http(e));
+                    }
                     last_err = Some(OdooError::Http(e));
+                    retry::backoff(attempt)
                 }
-            }
+            };
 
             if attempt < self.max_retries {
-                let backoff_ms = 250u64.saturating_mul(2u64.saturating_pow(attempt as u32));
-                tokio::time::sleep(Duration::from_millis(backoff_ms)).await;
+                retry::report(&self.instance, last_err.as_ref(), attempt + 1, wait);
+                tokio::time::sleep(wait).await;
             }
         }
 
@@ -181,48 +255,160 @@ impl OdooLegacyClient {
 
     /// Authenticate and get user ID
     pub async fn authenticate(&self) -> OdooResult<i64> {
-        // Check cache first
+        Ok(self.session().await?.uid)
+    }
+
+    /// The current login, authenticating first if there is none.
+    pub async fn session(&self) -> OdooResult<SessionInfo> {
+        if let Some(session) = self.session.read().await.clone() {
+            return Ok(session);
+        }
+
+        let _login = self.login_lock.lock().await;
+        // Another caller may have logged in while we waited.
+        if let Some(session) = self.session.read().await.clone() {
+            return Ok(session);
+        }
+        let session = self.login().await?;
+        *self.session.write().await = Some(session.clone());
+        Ok(session)
+    }
+
+    /// Drop `stale` and log in again, unless another caller already did.
+    async fn refresh_session(&self, stale: &SessionInfo) -> OdooResult<SessionInfo> {
+        self.forget_session(stale).await;
+        self.session().await
+    }
+
+    /// Drop `stale`, unless another caller already replaced it.
+    async fn forget_session(&self, stale: &SessionInfo) {
         {
-            let cached = self.uid.read().await;
-            if let Some(uid) = *cached {
-                return Ok(uid);
+            let mut cached = self.session.write().await;
+            if cached.as_ref() == Some(stale) {
+                *cached = None;
             }
         }
+        tracing::info!(
+            instance = %self.instance,
+            user = %self.username,
+            "Odoo session rejected, logging in again"
+        );
+    }
 
-        // Authenticate via common service
-        let args = json!([self.db, self.username, self.password, {}]);
-        let result = self.jsonrpc_call("common", "authenticate", args).await?;
+    /// The current login if it carries a web session cookie, which report
+    /// downloads need; otherwise open one through `/web/session/authenticate`.
+    /// XML-RPC logins, and `common.authenticate` fallbacks, have none.
+    async fn web_session(&self) -> OdooResult<SessionInfo> {
+        let cached = || async { self.session.read().await.clone().filter(|s| s.web_session) };
+        if let Some(session) = cached().await {
+            return Ok(session);
+        }
 
-        let uid = result.as_i64().ok_or_else(|| OdooError::Api {
-            status: 401,
+        let _login = self.login_lock.lock().await;
+        if let Some(session) = cached().await {
+            return Ok(session);
+        }
+        let session = self.web_login().await?.ok_or_else(|| OdooError::Api {
+            status: 404,
             message: format!(
-                "Authentication failed for user '{}'. Check username/password.",
-                self.username
+                "Reports need an Odoo web session, but {} refused /web/session/authenticate \
+                 (the route is not exposed, or the password is an API key, which Odoo only \
+                 accepts for RPC). Expose /web/session/authenticate and /report/ to this \
+                 server and log in with the user's password to download reports.",
+                self.base_url
             ),
             body: None,
         })?;
+        *self.session.write().await = Some(session.clone());
+        Ok(session)
+    }
 
-        if uid == 0 {
-            return Err(OdooError::Api {
-                status: 401,
-                message: format!(
-                    "Authentication failed for user '{}'. Invalid credentials.",
-                    self.username
-                ),
-                body: None,
-            });
+    /// Log in through `/web/session/authenticate`, which also sets the session
+    /// cookie. Falls back to `common.authenticate` when the web login is refused:
+    /// a proxy that only forwards `/jsonrpc`, or an API key, which Odoo 14+
+    /// accepts for RPC but not for web logins. With XML-RPC, only
+    /// `common.authenticate` is used; reports open a web session on demand.
+    async fn login(&self) -> OdooResult<SessionInfo> {
+        if self.protocol == OdooProtocol::XmlRpc {
+            let uid = self.common_authenticate().await?;
+            return Ok(SessionInfo::from_uid(uid));
         }
+        match self.web_login().await? {
+            Some(session) => Ok(session),
+            None => {
+                let uid = self.common_authenticate().await?;
+                Ok(SessionInfo::from_uid(uid))
+            }
+        }
+    }
 
-        // Cache the uid
-        {
-            let mut cached = self.uid.write().await;
-            *cached = Some(uid);
+    /// Log in through `/web/session/authenticate`; `None` when the route is not
+    /// exposed or blocked (4xx), or rejects the credentials.
+    async fn web_login(&self) -> OdooResult<Option<SessionInfo>> {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "method": "call",
+            "params": {
+                "db": self.db,
+                "login": self.username,
+                "password": self.password
+            },
+            "id": 1
+        });
+        let url = self.endpoint("/web/session/authenticate");
+        let result = self
+            .breaker
+            .call(
+                &self.instance,
+                self.rpc_attempts(url, RpcBody::Json(body), true),
+            )
+            .await;
+
+        match result {
+            Ok(result) => Ok(SessionInfo::from_web_session(&result)),
+            Err(OdooError::Api { status, .. } | OdooError::Odoo { status, .. })
+                if (400..500).contains(&status) =>
+            {
+                Ok(None)
+            }
+            Err(e) if e.kind() == Some(OdooErrorKind::AccessDenied) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn common_authenticate(&self) -> OdooResult<i64> {
+        let args = json!([self.db, self.username, self.password, {}]);
+        let result = self.rpc_call("common", "authenticate", args).await?;
+
+        let uid = result.as_i64().ok_or_else(|| {
+            self.auth_failed(format!(
+                "Authentication failed for user '{}'. Check username/password.",
+                self.username
+            ))
+        })?;
+
+        if uid == 0 {
+            return Err(self.auth_failed(format!(
+                "Authentication failed for user '{}'. Invalid credentials.",
+                self.username
+            )));
         }
 
         Ok(uid)
     }
 
+    fn auth_failed(&self, message: String) -> OdooError {
+        OdooError::Odoo {
+            kind: OdooErrorKind::AccessDenied,
+            status: 401,
+            message,
+            body: None,
+        }
+    }
+
     /// Call execute_kw on the object service
+    ///
+    /// A session or credential rejection triggers one fresh login and re-send.
     async fn execute_kw(
         &self,
         model: &str,
@@ -230,8 +416,29 @@ impl OdooLegacyClient {
         args: Value,
         kwargs: Option<Value>,
     ) -> OdooResult<Value> {
-        let uid = self.authenticate().await?;
+        let kwargs = kwargs.unwrap_or_else(|| json!({}));
+        let session = self.session().await?;
+        match self
+            .execute_kw_as(session.uid, model, method, args.clone(), kwargs.clone())
+            .await
+        {
+            Err(e) if needs_relogin(&e) => {
+                let session = self.refresh_session(&session).await?;
+                self.execute_kw_as(session.uid, model, method, args, kwargs)
+                    .await
+            }
+            result => result,
+        }
+    }
 
+    async fn execute_kw_as(
+        &self,
+        uid: i64,
+        model: &str,
+        method: &str,
+        args: Value,
+        kwargs: Value,
+    ) -> OdooResult<Value> {
         // execute_kw always expects 7 arguments: [db, uid, password, model, method, args, kwargs]
         // kwargs must be an object (even if empty) for proper Odoo execution
         let call_args = vec![
@@ -241,10 +448,10 @@ impl OdooLegacyClient {
             json!(model),
             json!(method),
             args,
-            kwargs.unwrap_or_else(|| json!({})),
+            kwargs,
         ];
 
-        self.jsonrpc_call("object", "execute_kw", json!(call_args))
+        self.rpc_call("object", "execute_kw", json!(call_args))
             .await
     }
 
@@ -255,10 +462,14 @@ impl OdooLegacyClient {
         limit: Option<i64>,
         offset: Option<i64>,
         order: Option<String>,
-        _context: Option<Value>,
+        context: Option<Value>,
     ) -> OdooResult<Vec<i64>> {
         let domain = domain.unwrap_or(json!([]));
         let mut kwargs = serde_json::Map::new();
+        // The context decides which records match (`active_test`, company, ...).
+        if let Some(v) = context {
+            kwargs.insert("context".to_string(), v);
+        }
         if let Some(v) = limit {
             kwargs.insert("limit".to_string(), json!(v));
         }
@@ -286,10 +497,13 @@ impl OdooLegacyClient {
         limit: Option<i64>,
         offset: Option<i64>,
         order: Option<String>,
-        _context: Option<Value>,
+        context: Option<Value>,
     ) -> OdooResult<Value> {
         let domain = domain.unwrap_or(json!([]));
         let mut kwargs = serde_json::Map::new();
+        if let Some(v) = context {
+            kwargs.insert("context".to_string(), v);
+        }
         if let Some(v) = fields {
             kwargs.insert("fields".to_string(), json!(v));
         }
@@ -405,9 +619,27 @@ impl OdooLegacyClient {
         self.execute_kw(model, method, args, kwargs).await
     }
 
+    /// Render a report through the web controller, authenticated by the session
+    /// cookie. Logs in again once if the session has expired.
     pub async fn download_report_pdf(&self, report_name: &str, ids: &[i64]) -> OdooResult<Vec<u8>> {
-        let _uid = self.authenticate().await?;
+        let session = self.web_session().await?;
+        match self
+            .breaker
+            .call(&self.instance, self.fetch_report_pdf(report_name, ids))
+            .await
+        {
+            Err(e) if needs_relogin(&e) => {
+                self.forget_session(&session).await;
+                self.web_session().await?;
+                self.breaker
+                    .call(&self.instance, self.fetch_report_pdf(report_name, ids))
+                    .await
+            }
+            result => result,
+        }
+    }
 
+    async fn fetch_report_pdf(&self, report_name: &str, ids: &[i64]) -> OdooResult<Vec<u8>> {
         // For legacy Odoo, we use the web controller for reports
         let mut url = self.base_url.clone();
         let ids_csv = ids
@@ -417,76 +649,67 @@ impl OdooLegacyClient {
             .join(",");
         url.set_path(&format!("/report/pdf/{report_name}/{ids_csv}"));
 
-        // We need to authenticate via session first
-        // This is a simplified approach - in production you might need session cookies
+        // Rendering a report does not change data.
+        let idempotent = true;
         let mut last_err: Option<OdooError> = None;
         for attempt in 0..=self.max_retries {
-            // First, establish session via web/session/authenticate
-            let session_url = {
-                let mut u = self.base_url.clone();
-                u.set_path("/web/session/authenticate");
-                u
-            };
-
-            let session_body = json!({
-                "jsonrpc": "2.0",
-                "method": "call",
-                "params": {
-                    "db": self.db,
-                    "login": self.username,
-                    "password": self.password
-                },
-                "id": 1
-            });
-
-            let session_resp = self
-                .http
-                .post(session_url)
-                .headers(self.headers())
-                .json(&session_body)
-                .send()
-                .await;
-
-            if let Err(e) = session_resp {
-                last_err = Some(OdooError::Http(e));
-                if attempt < self.max_retries {
-                    let backoff_ms = 250u64.saturating_mul(2u64.saturating_pow(attempt as u32));
-                    tokio::time::sleep(Duration::from_millis(backoff_ms)).await;
-                }
-                continue;
+            if attempt > 0 {
+                metrics::global().record_odoo_retry(&self.instance);
             }
 
-            // Now fetch the report
             let resp = self.http.get(url.clone()).send().await;
-            match resp {
+            let wait = match resp {
                 Ok(r) => {
                     let status = r.status();
+                    metrics::global().record_odoo_request(&self.instance, Some(status.as_u16()));
+                    // Without a valid session Odoo redirects to the login page.
+                    if r.url().path().starts_with("/web/login") {
+                        return Err(OdooError::Odoo {
+                            kind: OdooErrorKind::SessionExpired,
+                            status: 401,
+                            message:
+                                "Session expired: report request was redirected to the login page"
+                                    .to_string(),
+                            body: None,
+                        });
+                    }
+                    let retry_headers = r.headers().clone();
                     if status.is_success() {
                         let bytes = r.bytes().await.map_err(OdooError::Http)?;
                         return Ok(bytes.to_vec());
                     }
 
                     let text = r.text().await.unwrap_or_default();
-                    let err = OdooError::Api {
-                        status: status.as_u16(),
-                        message: text,
-                        body: None,
-                    };
-
-                    if status.is_server_error() || status.as_u16() == 429 {
-                        last_err = Some(err);
-                    } else {
+                    let body = serde_json::from_str::<Value>(&text)
+                        .ok()
+                        .and_then(|v| OdooErrorBody::from_payload(&v));
+                    let message = body
+                        .as_ref()
+                        .and_then(|b| b.message.clone())
+                        .unwrap_or(text);
+                    let err = OdooError::from_odoo(status.as_u16(), message, body);
+
+                    if !retry::retry_status(idempotent, status) {
                         return Err(err);
                     }
+
+                    last_err = Some(err);
+
+                    retry::delay(attempt, Some(status), Some(&retry_headers))
                 }
                 Err(e) => {
+                    metrics::global().record_odoo_request(&self.instance, None);
+                    if !retry::retry_transport_error(idempotent, &e) {
+                        return Err(OdooError::Http(e));
+                    }
                     last_err = Some(OdooError::Http(e));
+                    retry::backoff(attempt)
                 }
-            }
+            };
 
             if attempt < self.max_retries {
-                let backoff_ms = 250u64.saturating_mul(2u64.saturating_pow(attempt as u32));
-                tokio::time::sleep(Duration::from_millis(backoff_ms)).await;
+                retry::report(&self.instance, last_err.as_ref(), attempt + 1, wait);
+                tokio::time::sleep(wait).await;
             }
         }
 
@@ -617,6 +840,41 @@ impl OdooLegacyClient {
     }
 }
 
+/// Extract the result of a JSON-RPC response body.
+fn parse_jsonrpc_response(text: &str) -> OdooResult<Value> {
+    let v: Value = serde_json::from_str(text).map_err(|e| {
+        OdooError::InvalidResponse(format!(
+            "Failed to parse JSON-RPC response: {e}. Body: {text}"
+        ))
+    })?;
+
+    // Check for JSON-RPC error
+    if let Some(error) = v.get("error") {
+        let message = error
+            .get("data")
+            .and_then(|d| d.get("message"))
+            .and_then(|m| m.as_str())
+            .or_else(|| error.get("message").and_then(|m| m.as_str()))
+            .unwrap_or("Unknown JSON-RPC error")
+            .to_string();
+
+        return Err(OdooError::from_odoo(
+            400,
+            message,
+            OdooErrorBody::from_payload(error),
+        ));
+    }
+
+    // Extract result
+    if let Some(result) = v.get("result") {
+        return Ok(result.clone());
+    }
+
+    Err(OdooError::InvalidResponse(
+        "JSON-RPC response missing 'result' field".to_string(),
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -637,6 +895,8 @@ mod tests {
             version: Some("18".to_string()),
             timeout_ms: Some(5000),
             max_retries: Some(2),
+            protocol: None,
+            transport: Default::default(),
             extra: HashMap::new(),
         }
     }
@@ -720,6 +980,8 @@ mod tests {
             version: None,
             timeout_ms: None,
             max_retries: None,
+            protocol: None,
+            transport: Default::default(),
             extra: HashMap::new(),
         };
         let client = OdooLegacyClient::new(&cfg).unwrap();
@@ -1,5 +1,13 @@
+pub mod breaker;
 pub mod client;
+pub mod compat;
 pub mod config;
 pub mod legacy_client;
+pub mod retry;
+pub mod secrets;
+pub mod session;
+pub mod transport;
 pub mod types;
 pub mod unified_client;
+pub mod version;
+pub mod xmlrpc;
@@ -0,0 +1,238 @@
+//! Retry policy shared by the Odoo clients.
+//!
+//! Read-only methods are retried on transport errors, 5xx and 429. Anything that
+//! may change data is retried only when the connection could not be established,
+//! because a timeout or a 5xx after the request reached Odoo says nothing about
+//! whether the transaction committed.
+
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use reqwest::StatusCode;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+
+use super::types::OdooError;
+
+/// Base delay of the exponential backoff (250ms, 500ms, 1s, 2s...).
+const BACKOFF_BASE_MS: u64 = 250;
+
+/// Upper bound for a single backoff or `Retry-After` wait.
+const MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// Methods that never change data and can be re-sent safely.
+const IDEMPOTENT_METHODS: &[&str] = &[
+    "search",
+    "search_read",
+    "search_count",
+    "search_fetch",
+    "read",
+    "read_group",
+    "formatted_read_group",
+    "web_read",
+    "web_search_read",
+    "web_read_group",
+    "fields_get",
+    "name_search",
+    "name_get",
+    "default_get",
+    "onchange",
+    "exists",
+    "check_access",
+    "check_access_rights",
+    "check_access_rule",
+    "has_access",
+    "get_views",
+    "get_view",
+    "fields_view_get",
+    // common service
+    "version",
+    "authenticate",
+    "login",
+];
+
+/// Whether `method` can be re-sent after an ambiguous failure.
+pub fn is_idempotent(method: &str) -> bool {
+    IDEMPOTENT_METHODS.contains(&method)
+}
+
+/// Whether a transport-level error may be retried. Non-idempotent calls are only
+/// retried when the request provably never left (connection refused, DNS, TLS).
+pub fn retry_transport_error(idempotent: bool, err: &reqwest::Error) -> bool {
+    idempotent || err.is_connect()
+}
+
+/// Whether an HTTP error status may be retried.
+pub fn retry_status(idempotent: bool, status: StatusCode) -> bool {
+    idempotent && (status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS)
+}
+
+/// Whether a failed call may have reached Odoo (so a write could have committed).
+pub fn is_ambiguous(err: &OdooError) -> bool {
+    match err {
+        OdooError::Http(e) => !e.is_connect(),
+        OdooError::Api { status, .. } | OdooError::Odoo { status, .. } => {
+            *status >= 500 || *status == 429
+        }
+        OdooError::InvalidResponse(_) | OdooError::Unavailable(_) => false,
+    }
+}
+
+/// Exponential backoff with "equal jitter": half the delay is fixed, the other
+/// half random, so concurrent clients do not retry in lockstep.
+pub fn backoff(attempt: usize) -> Duration {
+    let exp = BACKOFF_BASE_MS.saturating_mul(2u64.saturating_pow(attempt as u32));
+    let ms = exp.min(MAX_DELAY.as_millis() as u64);
+    let half = ms / 2;
+    Duration::from_millis(half + rand::rng().random_range(0..=half))
+}
+
+/// Delay before the next attempt: `Retry-After` on 429 when present, otherwise
+/// the jittered backoff.
+pub fn delay(attempt: usize, status: Option<StatusCode>, headers: Option<&HeaderMap>) -> Duration {
+    if status == Some(StatusCode::TOO_MANY_REQUESTS)
+        && let Some(wait) = headers.and_then(retry_after)
+    {
+        return wait.min(MAX_DELAY);
+    }
+    backoff(attempt)
+}
+
+/// Parse a `Retry-After` header (delay in seconds or an HTTP date).
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = DateTime::parse_from_rfc2822(value)
+        .ok()?
+        .with_timezone(&Utc);
+    Some((at - Utc::now()).to_std().unwrap_or(Duration::ZERO))
+}
+
+/// A failed attempt that is about to be retried.
+pub struct RetryEvent<'a> {
+    pub instance: &'a str,
+    pub error: Option<&'a OdooError>,
+    /// Number of the attempt that comes next (the first retry is 1).
+    pub attempt: usize,
+    pub wait: Duration,
+}
+
+/// Told about every retry of the calls made within [`observe`].
+pub type RetryObserver = Arc<dyn Fn(&RetryEvent) + Send + Sync>;
+
+tokio::task_local! {
+    static OBSERVER: RetryObserver;
+}
+
+/// Run `fut` with `observer` told about the retries of its Odoo calls.
+pub async fn observe<F: Future>(observer: RetryObserver, fut: F) -> F::Output {
+    OBSERVER.scope(observer, fut).await
+}
+
+/// Tell the observer of the current call, if any, that a failed attempt is
+/// retried after `wait`.
+pub fn report(instance: &str, error: Option<&OdooError>, attempt: usize, wait: Duration) {
+    let _ = OBSERVER.try_with(|observer| {
+        observer(&RetryEvent {
+            instance,
+            error,
+            attempt,
+            wait,
+        })
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_is_idempotent() {
+        assert!(is_idempotent("search_read"));
+        assert!(is_idempotent("read"));
+        assert!(!is_idempotent("create"));
+        assert!(!is_idempotent("copy"));
+        assert!(!is_idempotent("message_post"));
+        assert!(!is_idempotent("action_confirm"));
+    }
+
+    #[test]
+    fn test_retry_status() {
+        assert!(retry_status(true, StatusCode::BAD_GATEWAY));
+        assert!(retry_status(true, StatusCode::TOO_MANY_REQUESTS));
+        assert!(!retry_status(true, StatusCode::FORBIDDEN));
+        assert!(!retry_status(false, StatusCode::BAD_GATEWAY));
+        assert!(!retry_status(false, StatusCode::TOO_MANY_REQUESTS));
+    }
+
+    #[test]
+    fn test_backoff_has_jitter_within_bounds() {
+        for attempt in 0..4 {
+            let full = 250u64 << attempt;
+            let d = backoff(attempt).as_millis() as u64;
+            assert!(d >= full / 2 && d <= full, "attempt {attempt}: {d}");
+        }
+        assert!(backoff(20) <= MAX_DELAY);
+    }
+
+    #[test]
+    fn test_retry_after_seconds_and_date() {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("7"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(7)));
+        assert_eq!(
+            delay(0, Some(StatusCode::TOO_MANY_REQUESTS), Some(&headers)),
+            Duration::from_secs(7)
+        );
+
+        headers.insert(
+            RETRY_AFTER,
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+        assert_eq!(retry_after(&headers), Some(Duration::ZERO));
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("3600"));
+        assert_eq!(
+            delay(0, Some(StatusCode::TOO_MANY_REQUESTS), Some(&headers)),
+            MAX_DELAY
+        );
+    }
+
+    #[test]
+    fn test_is_ambiguous() {
+        let err = OdooError::from_odoo(502, "Bad Gateway".to_string(), None);
+        assert!(is_ambiguous(&err));
+        let err = OdooError::from_odoo(403, "Forbidden".to_string(), None);
+        assert!(!is_ambiguous(&err));
+        assert!(!is_ambiguous(&OdooError::InvalidResponse("x".to_string())));
+    }
+
+    #[tokio::test]
+    async fn test_report_reaches_observer_only() {
+        // No observer: nothing happens.
+        report("prod", None, 1, Duration::from_millis(250));
+
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        let observer: RetryObserver = Arc::new(move |event: &RetryEvent| {
+            sink.lock().unwrap_or_else(|e| e.into_inner()).push((
+                event.instance.to_string(),
+                event.attempt,
+                event.wait,
+            ));
+        });
+        observe(observer, async {
+            report("prod", None, 2, Duration::from_millis(500));
+        })
+        .await;
+        assert_eq!(
+            *seen.lock().unwrap_or_else(|e| e.into_inner()),
+            vec![("prod".to_string(), 2, Duration::from_millis(500))]
+        );
+    }
+}
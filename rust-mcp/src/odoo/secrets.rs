@@ -0,0 +1,246 @@
+//! Secret references for credentials.
+//!
+//! `apiKey`, `password` and `headers` values may name where the secret lives
+//! instead of holding it:
+//!
+//! - `env:VAR` reads an environment variable;
+//! - `file:/run/secrets/odoo` reads a file (Docker/Kubernetes secrets), without
+//!   its trailing newline;
+//! - `cmd:pass show odoo/prod` runs a credential helper through the shell and
+//!   uses its standard output.
+//!
+//! References are resolved once, when the instances are loaded. Resolved
+//! values never appear in errors or in the config manager API.
+
+use std::process::Command;
+
+use serde_json::Value;
+
+/// Instance fields that hold credentials.
+pub const SECRET_FIELDS: &[&str] = &["apiKey", "password"];
+
+/// Placeholder shown instead of a plaintext secret.
+pub const MASK: &str = "********";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecretRef<'a> {
+    Env(&'a str),
+    File(&'a str),
+    Cmd(&'a str),
+}
+
+impl<'a> SecretRef<'a> {
+    pub fn parse(raw: &'a str) -> Option<Self> {
+        let raw = raw.trim();
+        if let Some(var) = raw.strip_prefix("env:") {
+            Some(Self::Env(var.trim()))
+        } else if let Some(path) = raw.strip_prefix("file:") {
+            Some(Self::File(path.trim()))
+        } else {
+            raw.strip_prefix("cmd:").map(|cmd| Self::Cmd(cmd.trim()))
+        }
+    }
+
+    fn resolve(self) -> anyhow::Result<String> {
+        match self {
+            Self::Env(var) => std::env::var(var)
+                .map_err(|_| anyhow::anyhow!("environment variable '{var}' is not set")),
+            Self::File(path) => std::fs::read_to_string(path)
+                .map(|s| s.trim_end_matches(['\r', '\n']).to_string())
+                .map_err(|e| anyhow::anyhow!("cannot read '{path}': {e}")),
+            Self::Cmd(cmd) => run(cmd),
+        }
+    }
+}
+
+/// Whether `raw` is a reference rather than a plaintext secret.
+pub fn is_reference(raw: &str) -> bool {
+    SecretRef::parse(raw).is_some()
+}
+
+/// The secret `raw` refers to, or `raw` itself when it is not a reference.
+pub fn resolve(raw: &str) -> anyhow::Result<String> {
+    match SecretRef::parse(raw) {
+        Some(reference) => reference.resolve(),
+        None => Ok(raw.to_string()),
+    }
+}
+
+/// Resolve an optional field in place, naming it (not its value) on failure.
+pub fn resolve_field(value: &mut Option<String>, what: &str) -> anyhow::Result<()> {
+    if let Some(raw) = value.as_deref()
+        && is_reference(raw)
+    {
+        let resolved = resolve(raw).map_err(|e| anyhow::anyhow!("Cannot resolve {what}: {e}"))?;
+        *value = Some(resolved);
+    }
+    Ok(())
+}
+
+fn run(cmd: &str) -> anyhow::Result<String> {
+    #[cfg(windows)]
+    let output = Command::new("cmd").args(["/C", cmd]).output();
+    #[cfg(not(windows))]
+    let output = Command::new("sh").args(["-c", cmd]).output();
+
+    let output = output.map_err(|e| anyhow::anyhow!("cannot run credential helper: {e}"))?;
+    if !output.status.success() {
+        anyhow::bail!("credential helper exited with {}", output.status);
+    }
+    let stdout = String::from_utf8(output.stdout)
+        .map_err(|_| anyhow::anyhow!("credential helper printed invalid UTF-8"))?;
+    let secret = stdout.trim_end_matches(['\r', '\n']).to_string();
+    if secret.is_empty() {
+        anyhow::bail!("credential helper printed nothing");
+    }
+    Ok(secret)
+}
+
+/// Copy of an instances config for display: plaintext credentials and header
+/// values become [`MASK`], references are shown as written.
+pub fn mask_instances(config: &Value) -> Value {
+    let mut masked = config.clone();
+    for_each_secret(&mut masked, |value| {
+        if let Value::String(s) = value
+            && !s.is_empty()
+            && !is_reference(s)
+        {
+            *s = MASK.to_string();
+        }
+    });
+    masked
+}
+
+/// Put back the stored secrets for values a client sent back as [`MASK`], so a
+/// masked config can be edited and saved without losing credentials.
+pub fn unmask_instances(config: &mut Value, stored: &Value) {
+    let Some(instances) = config.as_object_mut() else {
+        return;
+    };
+    for (name, instance) in instances.iter_mut() {
+        let stored = stored.get(name);
+        for field in SECRET_FIELDS {
+            restore(instance.get_mut(*field), stored.and_then(|s| s.get(*field)));
+        }
+        if let Some(Value::Object(headers)) = instance.get_mut("headers") {
+            for (header, value) in headers.iter_mut() {
+                let stored = stored
+                    .and_then(|s| s.get("headers"))
+                    .and_then(|h| h.get(header));
+                restore(Some(value), stored);
+            }
+        }
+    }
+}
+
+fn restore(value: Option<&mut Value>, stored: Option<&Value>) {
+    if let Some(value) = value
+        && value.as_str() == Some(MASK)
+        && let Some(stored) = stored
+    {
+        *value = stored.clone();
+    }
+}
+
+fn for_each_secret(config: &mut Value, mut f: impl FnMut(&mut Value)) {
+    let Some(instances) = config.as_object_mut() else {
+        return;
+    };
+    for instance in instances.values_mut() {
+        for field in SECRET_FIELDS {
+            if let Some(value) = instance.get_mut(*field) {
+                f(value);
+            }
+        }
+        if let Some(Value::Object(headers)) = instance.get_mut("headers") {
+            headers.values_mut().for_each(&mut f);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_references() {
+        assert_eq!(
+            SecretRef::parse("env:ODOO_KEY"),
+            Some(SecretRef::Env("ODOO_KEY"))
+        );
+        assert_eq!(
+            SecretRef::parse("file:/run/secrets/odoo"),
+            Some(SecretRef::File("/run/secrets/odoo"))
+        );
+        assert_eq!(
+            SecretRef::parse("cmd:pass show odoo"),
+            Some(SecretRef::Cmd("pass show odoo"))
+        );
+        assert_eq!(SecretRef::parse("plain-secret"), None);
+        assert_eq!(resolve("plain-secret").unwrap(), "plain-secret");
+    }
+
+    #[test]
+    fn test_resolve_env_and_file() {
+        unsafe { std::env::set_var("SECRETS_TEST_KEY", "from-env") };
+        assert_eq!(resolve("env:SECRETS_TEST_KEY").unwrap(), "from-env");
+        let err = resolve("env:SECRETS_TEST_MISSING").unwrap_err();
+        assert!(err.to_string().contains("SECRETS_TEST_MISSING"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("secret");
+        std::fs::write(&path, "from-file\n").unwrap();
+        assert_eq!(
+            resolve(&format!("file:{}", path.display())).unwrap(),
+            "from-file"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_cmd() {
+        assert_eq!(resolve("cmd:echo from-helper").unwrap(), "from-helper");
+        let err = resolve("cmd:echo leaked; exit 3").unwrap_err();
+        assert!(!err.to_string().contains("leaked"));
+    }
+
+    #[test]
+    fn test_resolve_field_error_names_field_only() {
+        let mut value = Some("env:SECRETS_TEST_UNSET".to_string());
+        let err = resolve_field(&mut value, "apiKey of 'prod'").unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("Cannot resolve apiKey of 'prod'")
+        );
+    }
+
+    #[test]
+    fn test_mask_and_unmask_round_trip() {
+        let stored = json!({
+            "prod": {
+                "url": "https://odoo.example.com",
+                "apiKey": "plaintext-key",
+                "headers": {"CF-Access-Client-Secret": "cf-secret", "X-Ref": "env:CF_ID"}
+            },
+            "legacy": {"url": "http://legacy", "password": "file:/run/secrets/legacy"}
+        });
+        let masked = mask_instances(&stored);
+        assert_eq!(masked["prod"]["apiKey"], MASK);
+        assert_eq!(masked["prod"]["headers"]["CF-Access-Client-Secret"], MASK);
+        assert_eq!(masked["prod"]["headers"]["X-Ref"], "env:CF_ID");
+        assert_eq!(masked["legacy"]["password"], "file:/run/secrets/legacy");
+        assert_eq!(masked["prod"]["url"], "https://odoo.example.com");
+
+        let mut edited = masked.clone();
+        edited["prod"]["url"] = json!("https://new.example.com");
+        edited["legacy"]["password"] = json!("env:LEGACY_PASSWORD");
+        unmask_instances(&mut edited, &stored);
+        assert_eq!(edited["prod"]["apiKey"], "plaintext-key");
+        assert_eq!(
+            edited["prod"]["headers"]["CF-Access-Client-Secret"],
+            "cf-secret"
+        );
+        assert_eq!(edited["legacy"]["password"], "env:LEGACY_PASSWORD");
+    }
+}
@@ -0,0 +1,139 @@
+//! Login state of the legacy JSON-RPC client.
+//!
+//! The client logs in once through `/web/session/authenticate`, which sets the
+//! session cookie used by web controllers (reports) and returns the user's uid,
+//! company and context. The result is cached until Odoo reports the session as
+//! expired or the credentials as rejected; the client then logs in again once.
+
+use serde::Serialize;
+use serde_json::Value;
+
+use super::types::{OdooError, OdooErrorKind};
+
+/// What Odoo told us about the logged-in user.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SessionInfo {
+    pub uid: i64,
+    /// Current company, when the server reports it.
+    pub company_id: Option<i64>,
+    /// User language from the session context (`en_US`, `fr_FR`, ...).
+    pub lang: Option<String>,
+    /// User timezone from the session context.
+    pub tz: Option<String>,
+    /// Whether a web session cookie was established. `false` when the server
+    /// only allowed the stateless `common.authenticate` login.
+    pub web_session: bool,
+}
+
+impl SessionInfo {
+    /// Parse the `result` of `/web/session/authenticate`. Returns `None` when the
+    /// login was rejected (`uid` false or missing).
+    pub fn from_web_session(result: &Value) -> Option<Self> {
+        let uid = result
+            .get("uid")
+            .and_then(Value::as_i64)
+            .filter(|&uid| uid > 0)?;
+        let context = result.get("user_context");
+        let context_str = |key: &str| {
+            context
+                .and_then(|c| c.get(key))
+                .and_then(Value::as_str)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+        };
+        Some(Self {
+            uid,
+            company_id: company_id(result),
+            lang: context_str("lang"),
+            tz: context_str("tz"),
+            web_session: true,
+        })
+    }
+
+    /// Session from a `common.authenticate` login, which only yields the uid.
+    pub fn from_uid(uid: i64) -> Self {
+        Self {
+            uid,
+            company_id: None,
+            lang: None,
+            tz: None,
+            web_session: false,
+        }
+    }
+}
+
+/// `company_id` (Odoo <= 12) or `user_companies.current_company`, which is an id
+/// on 15+ and an `[id, name]` pair on 13/14.
+fn company_id(result: &Value) -> Option<i64> {
+    if let Some(id) = result.get("company_id").and_then(Value::as_i64) {
+        return Some(id);
+    }
+    let current = result.get("user_companies")?.get("current_company")?;
+    current
+        .as_i64()
+        .or_else(|| current.get(0).and_then(Value::as_i64))
+}
+
+/// Errors after which logging in again may help: the session expired, or the
+/// cached uid/password was rejected.
+pub fn needs_relogin(err: &OdooError) -> bool {
+    matches!(
+        err.kind(),
+        Some(OdooErrorKind::SessionExpired | OdooErrorKind::AccessDenied)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::odoo::types::OdooErrorBody;
+    use serde_json::json;
+
+    #[test]
+    fn test_from_web_session_modern() {
+        let result = json!({
+            "uid": 2,
+            "user_context": {"lang": "fr_FR", "tz": "Europe/Paris", "uid": 2},
+            "user_companies": {
+                "current_company": 3,
+                "allowed_companies": {"3": {"id": 3, "name": "ACME"}}
+            }
+        });
+        let info = SessionInfo::from_web_session(&result).unwrap();
+        assert_eq!(info.uid, 2);
+        assert_eq!(info.company_id, Some(3));
+        assert_eq!(info.lang.as_deref(), Some("fr_FR"));
+        assert_eq!(info.tz.as_deref(), Some("Europe/Paris"));
+        assert!(info.web_session);
+    }
+
+    #[test]
+    fn test_from_web_session_older_company_shapes() {
+        let v14 = json!({"uid": 2, "user_companies": {"current_company": [4, "ACME"]}});
+        assert_eq!(
+            SessionInfo::from_web_session(&v14).unwrap().company_id,
+            Some(4)
+        );
+        let v12 = json!({"uid": 2, "company_id": 1, "user_context": {"tz": ""}});
+        let info = SessionInfo::from_web_session(&v12).unwrap();
+        assert_eq!(info.company_id, Some(1));
+        assert_eq!(info.tz, None);
+    }
+
+    #[test]
+    fn test_from_web_session_rejected() {
+        assert!(SessionInfo::from_web_session(&json!({"uid": false})).is_none());
+        assert!(SessionInfo::from_web_session(&json!({})).is_none());
+    }
+
+    #[test]
+    fn test_needs_relogin() {
+        let error = |name: &str| {
+            let body = OdooErrorBody::from_payload(&json!({"name": name, "message": "x"}));
+            OdooError::from_odoo(400, "x".to_string(), body)
+        };
+        assert!(needs_relogin(&error("odoo.http.SessionExpiredException")));
+        assert!(needs_relogin(&error("odoo.exceptions.AccessDenied")));
+        assert!(!needs_relogin(&error("odoo.exceptions.AccessError")));
+    }
+}
@@ -0,0 +1,156 @@
+//! HTTP client construction from an instance's TLS, proxy and header settings.
+
+use std::time::Duration;
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Certificate, Identity, NoProxy, Proxy};
+
+use super::config::{OdooInstanceConfig, OdooTransportConfig};
+
+/// Build the `reqwest::Client` used for an instance. `cookies` keeps a cookie
+/// store (session-based clients); the version probe does without.
+pub fn build_http_client(
+    cfg: &OdooInstanceConfig,
+    timeout: Duration,
+    cookies: bool,
+) -> anyhow::Result<reqwest::Client> {
+    let builder = reqwest::Client::builder()
+        .timeout(timeout)
+        .cookie_store(cookies);
+    Ok(apply(&cfg.transport, builder)?.build()?)
+}
+
+fn apply(
+    transport: &OdooTransportConfig,
+    mut builder: reqwest::ClientBuilder,
+) -> anyhow::Result<reqwest::ClientBuilder> {
+    if let Some(path) = &transport.ca_bundle {
+        let pem = read(path, "caBundle")?;
+        let certs = Certificate::from_pem_bundle(&pem)
+            .map_err(|e| anyhow::anyhow!("Invalid caBundle '{path}': {e}"))?;
+        if certs.is_empty() {
+            anyhow::bail!("caBundle '{path}' contains no certificates");
+        }
+        for cert in certs {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+
+    match (&transport.client_cert, &transport.client_key) {
+        (Some(cert), Some(key)) => {
+            let mut pem = read(cert, "clientCert")?;
+            pem.push(b'\n');
+            pem.extend(read(key, "clientKey")?);
+            let identity = Identity::from_pem(&pem)
+                .map_err(|e| anyhow::anyhow!("Invalid clientCert/clientKey '{cert}': {e}"))?;
+            builder = builder.identity(identity);
+        }
+        (None, None) => {}
+        _ => anyhow::bail!("clientCert and clientKey must be set together"),
+    }
+
+    if transport.insecure_skip_verify {
+        tracing::warn!("TLS certificate verification is disabled (insecureSkipVerify)");
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+
+    if let Some(url) = &transport.proxy {
+        let proxy = Proxy::all(url.as_str())
+            .map_err(|e| anyhow::anyhow!("Invalid proxy '{url}': {e}"))?
+            .no_proxy(transport.no_proxy.as_deref().and_then(NoProxy::from_string));
+        builder = builder.proxy(proxy);
+    }
+
+    if !transport.headers.is_empty() {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &transport.headers {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|e| anyhow::anyhow!("Invalid header name '{name}': {e}"))?;
+            let mut value = HeaderValue::from_str(value)
+                .map_err(|e| anyhow::anyhow!("Invalid value for header '{name}': {e}"))?;
+            value.set_sensitive(true);
+            headers.insert(name, value);
+        }
+        builder = builder.default_headers(headers);
+    }
+
+    Ok(builder)
+}
+
+fn read(path: &str, field: &str) -> anyhow::Result<Vec<u8>> {
+    std::fs::read(path).map_err(|e| anyhow::anyhow!("Cannot read {field} '{path}': {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn config(transport: serde_json::Value) -> OdooInstanceConfig {
+        let mut value = serde_json::json!({"url": "https://odoo.internal"});
+        value
+            .as_object_mut()
+            .unwrap()
+            .extend(transport.as_object().unwrap().clone());
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn build(transport: serde_json::Value) -> anyhow::Result<reqwest::Client> {
+        build_http_client(&config(transport), Duration::from_secs(1), true)
+    }
+
+    #[test]
+    fn test_default_transport_builds() {
+        assert!(build(serde_json::json!({})).is_ok());
+    }
+
+    #[test]
+    fn test_proxy_and_headers() {
+        let client = build(serde_json::json!({
+            "proxy": "http://proxy.internal:3128",
+            "noProxy": "localhost,.corp",
+            "headers": {"CF-Access-Client-Id": "id", "CF-Access-Client-Secret": "secret"},
+            "insecureSkipVerify": true
+        }));
+        assert!(client.is_ok());
+
+        let err = build(serde_json::json!({"headers": {"Bad Header": "x"}})).unwrap_err();
+        assert!(err.to_string().contains("Invalid header name"));
+    }
+
+    #[test]
+    fn test_missing_files_and_half_identity() {
+        let err = build(serde_json::json!({"caBundle": "/nonexistent/ca.pem"})).unwrap_err();
+        assert!(err.to_string().contains("Cannot read caBundle"));
+
+        let err = build(serde_json::json!({"clientCert": "/tmp/cert.pem"})).unwrap_err();
+        assert!(err.to_string().contains("must be set together"));
+    }
+
+    #[test]
+    fn test_empty_ca_bundle_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ca.pem");
+        std::fs::write(&path, "not a certificate").unwrap();
+        let err = build(serde_json::json!({"caBundle": path})).unwrap_err();
+        assert!(err.to_string().contains("no certificates"));
+    }
+
+    #[test]
+    fn test_transport_fields_are_not_extra() {
+        let cfg = config(serde_json::json!({
+            "caBundle": "/etc/ssl/internal.pem",
+            "headers": {"X-Env": "staging"},
+            "note": "kept"
+        }));
+        assert_eq!(
+            cfg.transport.ca_bundle.as_deref(),
+            Some("/etc/ssl/internal.pem")
+        );
+        assert_eq!(
+            cfg.transport.headers,
+            HashMap::from([("X-Env".to_string(), "staging".to_string())])
+        );
+        assert_eq!(cfg.extra.keys().collect::<Vec<_>>(), vec!["note"]);
+    }
+}
@@ -12,11 +12,166 @@ pub struct OdooErrorBody {
     pub debug: Option<String>,
 }
 
+impl OdooErrorBody {
+    /// Parse an error payload in either shape Odoo uses: the JSON-2 body
+    /// (`{"name", "message", "debug", ...}`) or a JSON-RPC response whose
+    /// `error.data` carries the exception.
+    pub fn from_payload(payload: &Value) -> Option<Self> {
+        let error = payload.get("error").unwrap_or(payload);
+        let data = error.get("data").filter(|d| d.is_object()).unwrap_or(error);
+        let mut body: Self = serde_json::from_value(data.clone()).ok()?;
+        if body.message.is_none() {
+            body.message = error
+                .get("message")
+                .and_then(|m| m.as_str())
+                .map(str::to_string);
+        }
+        (body.name.is_some() || body.message.is_some()).then_some(body)
+    }
+}
+
+/// Odoo exception categories recognised from an error payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OdooErrorKind {
+    /// Authentication failed (bad credentials, API key or database).
+    AccessDenied,
+    /// Access rights or record rules forbid the operation.
+    AccessError,
+    /// A model constraint rejected the values.
+    ValidationError,
+    /// A business rule rejected the operation.
+    UserError,
+    /// The records do not exist (any more).
+    MissingError,
+    /// The model is not installed on the instance.
+    MissingModel,
+    /// The field does not exist on the model.
+    MissingField,
+    /// The web session is no longer valid.
+    SessionExpired,
+}
+
+impl OdooErrorKind {
+    /// Exception name as Odoo spells it.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::AccessDenied => "AccessDenied",
+            Self::AccessError => "AccessError",
+            Self::ValidationError => "ValidationError",
+            Self::UserError => "UserError",
+            Self::MissingError => "MissingError",
+            Self::MissingModel => "MissingModel",
+            Self::MissingField => "MissingField",
+            Self::SessionExpired => "SessionExpired",
+        }
+    }
+
+    /// Machine-readable category used in tool error payloads.
+    pub fn category(self) -> &'static str {
+        match self {
+            Self::AccessDenied => "access_denied",
+            Self::AccessError => "access_error",
+            Self::ValidationError => "validation_error",
+            Self::UserError => "user_error",
+            Self::MissingError => "missing_error",
+            Self::MissingModel => "missing_model",
+            Self::MissingField => "missing_field",
+            Self::SessionExpired => "session_expired",
+        }
+    }
+
+    /// What the caller can do about it.
+    pub fn hint(self) -> &'static str {
+        match self {
+            Self::AccessDenied => {
+                "Authentication failed. Check the instance's database name and credentials (API key, or username and password)."
+            }
+            Self::AccessError => {
+                "The Odoo user lacks access rights or record rules for this operation. Check with odoo_check_access, or ask an administrator to grant the required group."
+            }
+            Self::ValidationError => {
+                "The values violate a model constraint. Correct the values named in the message and retry."
+            }
+            Self::UserError => {
+                "Odoo refused the operation for a business reason. Read the message, adjust the data or record state, then retry."
+            }
+            Self::MissingError => {
+                "The records do not exist or were deleted. Search again to get current ids."
+            }
+            Self::MissingModel => {
+                "The model is not installed on this instance. Use odoo_list_models to find its technical name."
+            }
+            Self::MissingField => {
+                "The field does not exist on this model. Use odoo_get_model_metadata to list valid fields."
+            }
+            Self::SessionExpired => "The Odoo session expired. Retry the call.",
+        }
+    }
+
+    /// Classify an error from the exception name (`odoo.exceptions.AccessError`)
+    /// and, for generic exceptions, the message.
+    pub fn classify(name: Option<&str>, message: &str) -> Option<Self> {
+        let short = name.map(|n| n.rsplit('.').next().unwrap_or(n));
+        let by_name = match short {
+            Some("AccessDenied") => Some(Self::AccessDenied),
+            Some("AccessError") => Some(Self::AccessError),
+            Some("ValidationError") => Some(Self::ValidationError),
+            Some("UserError" | "RedirectWarning" | "Warning" | "except_orm") => {
+                Some(Self::UserError)
+            }
+            Some("MissingError") => Some(Self::MissingError),
+            Some("SessionExpiredException") => Some(Self::SessionExpired),
+            Some("KeyError") if looks_like_model_name(message) => Some(Self::MissingModel),
+            _ => None,
+        };
+        if by_name.is_some() {
+            return by_name;
+        }
+
+        let lower = message.to_lowercase();
+        if lower.contains("invalid field") {
+            Some(Self::MissingField)
+        } else if (lower.contains("model") || lower.contains("object"))
+            && (lower.contains("does not exist") || lower.contains("doesn't exist"))
+        {
+            Some(Self::MissingModel)
+        } else if lower.contains("session expired") {
+            Some(Self::SessionExpired)
+        } else {
+            None
+        }
+    }
+}
+
+impl std::fmt::Display for OdooErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// `KeyError` messages for unknown models are the quoted model name: `'res.partnr'`.
+fn looks_like_model_name(message: &str) -> bool {
+    let name = message.trim().trim_matches(|c| c == '\'' || c == '"');
+    name.contains('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '_')
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum OdooError {
     #[error("HTTP error: {0}")]
     Http(#[from] reqwest::Error),
 
+    /// An Odoo exception recognised as one of [`OdooErrorKind`].
+    #[error("Odoo {kind} (status {status}): {message}")]
+    Odoo {
+        kind: OdooErrorKind,
+        status: u16,
+        message: String,
+        body: Option<OdooErrorBody>,
+    },
+
     #[error("Odoo API error (status {status}): {message}")]
     Api {
         status: u16,
@@ -26,6 +181,81 @@ pub enum OdooError {
 
     #[error("Invalid response: {0}")]
     InvalidResponse(String),
+
+    /// The instance's circuit breaker is open; the request was not sent.
+    #[error("Odoo instance unavailable: {0}")]
+    Unavailable(String),
+}
+
+impl OdooError {
+    /// Build an error from an Odoo error response, promoting recognised
+    /// exceptions to [`OdooError::Odoo`].
+    pub fn from_odoo(status: u16, message: String, body: Option<OdooErrorBody>) -> Self {
+        let name = body.as_ref().and_then(|b| b.name.as_deref());
+        match OdooErrorKind::classify(name, &message) {
+            Some(kind) => OdooError::Odoo {
+                kind,
+                status,
+                message,
+                body,
+            },
+            None => OdooError::Api {
+                status,
+                message,
+                body,
+            },
+        }
+    }
+
+    pub fn kind(&self) -> Option<OdooErrorKind> {
+        match self {
+            OdooError::Odoo { kind, .. } => Some(*kind),
+            _ => None,
+        }
+    }
+
+    /// Machine-readable category for tool error payloads.
+    pub fn category(&self) -> &'static str {
+        match self {
+            OdooError::Http(_) => "connection",
+            OdooError::Odoo { kind, .. } => kind.category(),
+            OdooError::Api { .. } => "api_error",
+            OdooError::InvalidResponse(_) => "invalid_response",
+            OdooError::Unavailable(_) => "instance_unavailable",
+        }
+    }
+
+    /// The message as Odoo reported it (or the full error text otherwise).
+    pub fn message(&self) -> String {
+        match self {
+            OdooError::Odoo { message, .. } | OdooError::Api { message, .. } => message.clone(),
+            other => other.to_string(),
+        }
+    }
+
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            OdooError::Http(_) => {
+                Some("Could not reach the Odoo instance. Check its URL and network connectivity.")
+            }
+            OdooError::Odoo { kind, .. } => Some(kind.hint()),
+            OdooError::Unavailable(_) => Some(
+                "The instance failed repeatedly and requests are paused. Retry after the cool-down or check /health.",
+            ),
+            _ => None,
+        }
+    }
+
+    /// Server-side Python traceback, when Odoo sent one.
+    pub fn traceback(&self) -> Option<&str> {
+        match self {
+            OdooError::Odoo { body, .. } | OdooError::Api { body, .. } => body
+                .as_ref()
+                .and_then(|b| b.debug.as_deref())
+                .filter(|d| !d.is_empty()),
+            _ => None,
+        }
+    }
 }
 
 pub type OdooResult<T> = Result<T, OdooError>;
@@ -84,6 +314,114 @@ mod tests {
         assert_eq!(body.debug, Some("traceback here".to_string()));
     }
 
+    #[test]
+    fn test_error_kind_classify_by_name() {
+        let cases = [
+            ("odoo.exceptions.AccessDenied", OdooErrorKind::AccessDenied),
+            ("odoo.exceptions.AccessError", OdooErrorKind::AccessError),
+            (
+                "odoo.exceptions.ValidationError",
+                OdooErrorKind::ValidationError,
+            ),
+            ("odoo.exceptions.UserError", OdooErrorKind::UserError),
+            ("odoo.exceptions.RedirectWarning", OdooErrorKind::UserError),
+            ("odoo.exceptions.MissingError", OdooErrorKind::MissingError),
+            (
+                "odoo.http.SessionExpiredException",
+                OdooErrorKind::SessionExpired,
+            ),
+        ];
+        for (name, kind) in cases {
+            assert_eq!(
+                OdooErrorKind::classify(Some(name), "msg"),
+                Some(kind),
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_error_kind_classify_by_message() {
+        assert_eq!(
+            OdooErrorKind::classify(Some("builtins.KeyError"), "'res.partnr'"),
+            Some(OdooErrorKind::MissingModel)
+        );
+        assert_eq!(
+            OdooErrorKind::classify(
+                Some("builtins.ValueError"),
+                "Invalid field 'emial' on model 'res.partner'"
+            ),
+            Some(OdooErrorKind::MissingField)
+        );
+        assert_eq!(
+            OdooErrorKind::classify(None, "Object res.foo doesn't exist"),
+            Some(OdooErrorKind::MissingModel)
+        );
+        assert_eq!(
+            OdooErrorKind::classify(Some("builtins.KeyError"), "'name'"),
+            None
+        );
+        assert_eq!(
+            OdooErrorKind::classify(Some("builtins.TypeError"), "boom"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_error_body_from_jsonrpc_payload() {
+        let payload = serde_json::json!({
+            "jsonrpc": "2.0",
+            "error": {
+                "code": 200,
+                "message": "Odoo Server Error",
+                "data": {
+                    "name": "odoo.exceptions.UserError",
+                    "message": "Cannot delete a posted entry.",
+                    "debug": "Traceback ..."
+                }
+            }
+        });
+        let body = OdooErrorBody::from_payload(&payload).unwrap();
+        assert_eq!(body.name.as_deref(), Some("odoo.exceptions.UserError"));
+        assert_eq!(
+            body.message.as_deref(),
+            Some("Cannot delete a posted entry.")
+        );
+        assert_eq!(body.debug.as_deref(), Some("Traceback ..."));
+
+        // JSON-RPC error without a data message falls back to error.message.
+        let payload = serde_json::json!({"error": {"message": "Unauthorized", "data": {}}});
+        let body = OdooErrorBody::from_payload(&payload).unwrap();
+        assert_eq!(body.message.as_deref(), Some("Unauthorized"));
+
+        assert!(OdooErrorBody::from_payload(&serde_json::json!({"foo": 1})).is_none());
+    }
+
+    #[test]
+    fn test_from_odoo_classifies_or_falls_back() {
+        let body = OdooErrorBody {
+            name: Some("odoo.exceptions.MissingError".to_string()),
+            message: Some("Record does not exist or has been deleted.".to_string()),
+            arguments: vec![],
+            context: Value::Null,
+            debug: Some("Traceback ...".to_string()),
+        };
+        let err = OdooError::from_odoo(
+            400,
+            "Record does not exist or has been deleted.".to_string(),
+            Some(body),
+        );
+        assert_eq!(err.kind(), Some(OdooErrorKind::MissingError));
+        assert_eq!(err.category(), "missing_error");
+        assert_eq!(err.traceback(), Some("Traceback ..."));
+        assert!(err.to_string().contains("MissingError"));
+
+        let err = OdooError::from_odoo(500, "Internal Server Error".to_string(), None);
+        assert!(matches!(err, OdooError::Api { status: 500, .. }));
+        assert_eq!(err.category(), "api_error");
+        assert!(err.hint().is_none());
+    }
+
     #[test]
     fn test_odoo_error_body_deserialize_minimal() {
         let json = r#"{}"#;
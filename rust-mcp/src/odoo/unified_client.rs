@@ -1,10 +1,14 @@
 use async_trait::async_trait;
-use serde_json::Value;
+use serde_json::{Value, json};
 
+use super::breaker::CircuitBreaker;
 use super::client::OdooHttpClient;
-use super::config::{OdooAuthMode, OdooInstanceConfig};
+use super::compat::{self, ReadGroupShim};
+use super::config::{OdooAuthMode, OdooInstanceConfig, OdooProtocol};
 use super::legacy_client::OdooLegacyClient;
-use super::types::OdooResult;
+use super::retry;
+use super::session::SessionInfo;
+use super::types::{OdooError, OdooResult};
 
 /// Trait for Odoo client operations, enabling mockability for testing.
 #[async_trait]
@@ -158,11 +162,74 @@ impl OdooClient {
         }
     }
 
+    /// Label this client's request metrics with the configured instance name.
+    pub fn with_instance_name(self, name: &str) -> Self {
+        match self {
+            OdooClient::Modern(c) => OdooClient::Modern(c.with_instance_name(name)),
+            OdooClient::Legacy(c) => OdooClient::Legacy(c.with_instance_name(name)),
+        }
+    }
+
+    /// Replace the circuit breaker configured from the environment.
+    pub fn with_circuit_breaker(self, breaker: CircuitBreaker) -> Self {
+        match self {
+            OdooClient::Modern(c) => OdooClient::Modern(c.with_circuit_breaker(breaker)),
+            OdooClient::Legacy(c) => OdooClient::Legacy(c.with_circuit_breaker(breaker)),
+        }
+    }
+
+    pub fn breaker(&self) -> &CircuitBreaker {
+        match self {
+            OdooClient::Modern(c) => c.breaker(),
+            OdooClient::Legacy(c) => c.breaker(),
+        }
+    }
+
+    /// Logged-in user of a legacy (password) client: uid, company and lang.
+    /// API-key clients have no session and return `None`.
+    pub async fn session_info(&self) -> OdooResult<Option<SessionInfo>> {
+        match self {
+            OdooClient::Modern(_) => Ok(None),
+            OdooClient::Legacy(c) => c.session().await.map(Some),
+        }
+    }
+
+    pub fn max_retries(&self) -> usize {
+        match self {
+            OdooClient::Modern(c) => c.max_retries(),
+            OdooClient::Legacy(c) => c.max_retries(),
+        }
+    }
+
+    /// Major server version the shims route on. JSON-2 only exists on 19+, so
+    /// API-key clients without a configured version count as 19.
+    pub fn server_major(&self) -> Option<u32> {
+        match self {
+            OdooClient::Modern(c) => Some(c.server_major().unwrap_or(19)),
+            OdooClient::Legacy(c) => c.server_major(),
+        }
+    }
+
+    fn at_least(&self, major: u32) -> bool {
+        self.server_major().is_some_and(|v| v >= major)
+    }
+
     /// Returns true if using legacy (Odoo < 19) mode
     pub fn is_legacy(&self) -> bool {
         matches!(self, OdooClient::Legacy(_))
     }
 
+    /// Wire API in use: `"json2"`, `"jsonrpc"` or `"xmlrpc"`.
+    pub fn api(&self) -> &'static str {
+        match self {
+            OdooClient::Modern(_) => "json2",
+            OdooClient::Legacy(c) => match c.protocol() {
+                OdooProtocol::JsonRpc => "jsonrpc",
+                OdooProtocol::XmlRpc => "xmlrpc",
+            },
+        }
+    }
+
     pub async fn search(
         &self,
         model: &str,
@@ -225,6 +292,61 @@ impl OdooClient {
         }
     }
 
+    /// Create one record guarded by an idempotency key stored in `key_field`.
+    ///
+    /// The key is looked up before the first attempt and again before every
+    /// re-send, so a create that timed out after Odoo committed it is returned
+    /// instead of duplicated. Returns the id and whether this call created it.
+    pub async fn create_idempotent(
+        &self,
+        model: &str,
+        values: Value,
+        context: Option<Value>,
+        key_field: &str,
+        key: &str,
+    ) -> OdooResult<(i64, bool)> {
+        let Value::Object(mut values) = values else {
+            return Err(OdooError::InvalidResponse(
+                "An idempotency key requires 'values' to be a single record object".to_string(),
+            ));
+        };
+        values.insert(key_field.to_string(), json!(key));
+        let values = Value::Object(values);
+
+        // Archived records still hold the key.
+        let mut lookup_ctx = context.clone().unwrap_or_else(|| json!({}));
+        if let Some(ctx) = lookup_ctx.as_object_mut() {
+            ctx.insert("active_test".to_string(), json!(false));
+        }
+        let domain = json!([[key_field, "=", key]]);
+
+        let mut attempt = 0;
+        loop {
+            let existing = self
+                .search(
+                    model,
+                    Some(domain.clone()),
+                    Some(1),
+                    None,
+                    None,
+                    Some(lookup_ctx.clone()),
+                )
+                .await?;
+            if let Some(id) = existing.first() {
+                return Ok((*id, false));
+            }
+
+            match self.create(model, values.clone(), context.clone()).await {
+                Ok(id) => return Ok((id, true)),
+                Err(e) if attempt < self.max_retries() && retry::is_ambiguous(&e) => {
+                    tokio::time::sleep(retry::backoff(attempt)).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
     pub async fn write(
         &self,
         model: &str,
@@ -290,6 +412,8 @@ impl OdooClient {
         }
     }
 
+    /// read_group on any version: `formatted_read_group` on 19+, with the
+    /// arguments and groups translated so the result keeps read_group's shape.
     pub async fn read_group(
         &self,
         model: &str,
@@ -302,6 +426,29 @@ impl OdooClient {
         lazy: Option<bool>,
         context: Option<Value>,
     ) -> OdooResult<Value> {
+        if self.at_least(compat::FORMATTED_READ_GROUP_SINCE) {
+            let mut shim = ReadGroupShim::new(domain, &fields, &groupby, lazy.unwrap_or(true));
+            if !shim.bare_fields().is_empty() {
+                let mut params = serde_json::Map::new();
+                params.insert("allfields".to_string(), json!(shim.bare_fields()));
+                params.insert("attributes".to_string(), json!(["aggregator"]));
+                let meta = self
+                    .call_named(model, "fields_get", None, params, context.clone())
+                    .await?;
+                shim.resolve_aggregators(&meta);
+            }
+            let groups = self
+                .call_named(
+                    model,
+                    "formatted_read_group",
+                    None,
+                    shim.params(offset, limit, orderby),
+                    context,
+                )
+                .await?;
+            return Ok(shim.to_read_group(groups));
+        }
+
         match self {
             OdooClient::Modern(c) => {
                 c.read_group(
@@ -339,18 +486,86 @@ impl OdooClient {
         }
     }
 
+    /// `[[id, name], ...]` on any version: reads `display_name` on 17+, where
+    /// `name_get` no longer exists.
     pub async fn name_get(
         &self,
         model: &str,
         ids: Vec<i64>,
         context: Option<Value>,
     ) -> OdooResult<Value> {
+        if self.at_least(compat::DISPLAY_NAME_SINCE) {
+            let records = self
+                .read(model, ids, Some(vec!["display_name".to_string()]), context)
+                .await?;
+            return Ok(compat::display_names_to_name_get(&records));
+        }
         match self {
             OdooClient::Modern(c) => c.name_get(model, ids, context).await,
             OdooClient::Legacy(c) => c.name_get(model, ids, context).await,
         }
     }
 
+    /// Check whether the user may perform `operation` on the model and, with
+    /// `ids`, on those records. Uses `has_access` on 18+ and
+    /// `check_access_rights`/`check_access_rule` before, which raise when
+    /// access is denied at model level.
+    pub async fn check_access(
+        &self,
+        model: &str,
+        operation: &str,
+        ids: Option<Vec<i64>>,
+        context: Option<Value>,
+    ) -> OdooResult<Value> {
+        let mut params = serde_json::Map::new();
+        params.insert("operation".to_string(), json!(operation));
+
+        if self.at_least(compat::HAS_ACCESS_SINCE) {
+            let model_level = self
+                .call_named(model, "has_access", None, params.clone(), context.clone())
+                .await?;
+            let record_level = match ids {
+                Some(ids) => Some(
+                    self.call_named(model, "has_access", Some(ids), params, context)
+                        .await?,
+                ),
+                None => None,
+            };
+            let allowed = model_level.as_bool().unwrap_or(false)
+                && record_level
+                    .as_ref()
+                    .is_none_or(|r| r.as_bool().unwrap_or(false));
+            return Ok(json!({
+                "has_access": allowed,
+                "model_level": model_level,
+                "record_level": record_level
+            }));
+        }
+
+        let model_level = self
+            .call_named(
+                model,
+                "check_access_rights",
+                None,
+                params.clone(),
+                context.clone(),
+            )
+            .await?;
+        // check_access_rule raises on denial; report that as no record-level result.
+        let record_level = match ids {
+            Some(ids) => self
+                .call_named(model, "check_access_rule", Some(ids), params, context)
+                .await
+                .ok(),
+            None => None,
+        };
+        Ok(json!({
+            "has_access": true,
+            "model_level": model_level,
+            "record_level": record_level
+        }))
+    }
+
     pub async fn default_get(
         &self,
         model: &str,
@@ -599,6 +814,8 @@ mod tests {
             version: Some("19".to_string()),
             timeout_ms: None,
             max_retries: None,
+            protocol: None,
+            transport: Default::default(),
             extra: HashMap::new(),
         };
         assert_eq!(modern_cfg.auth_mode(), OdooAuthMode::ApiKey);
@@ -613,6 +830,8 @@ mod tests {
             version: Some("18".to_string()),
             timeout_ms: None,
             max_retries: None,
+            protocol: None,
+            transport: Default::default(),
             extra: HashMap::new(),
         };
         assert_eq!(legacy_cfg.auth_mode(), OdooAuthMode::Password);
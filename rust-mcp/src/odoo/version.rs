@@ -0,0 +1,236 @@
+//! Server version detection.
+//!
+//! Probes `/web/webclient/version_info` and falls back to `common.version`
+//! (over `/jsonrpc` or `/xmlrpc/2/common`), so the pool can choose between the
+//! JSON-2 and JSON-RPC clients without relying on a hand-written `version`.
+
+use std::time::Duration;
+
+use reqwest::header::{CONTENT_TYPE, HeaderValue, USER_AGENT};
+use serde::Serialize;
+use serde_json::{Value, json};
+use url::Url;
+
+use super::config::{OdooInstanceConfig, OdooProtocol};
+use super::transport;
+use super::types::{OdooError, OdooResult};
+use super::xmlrpc;
+
+/// Upper bound for a probe, whatever the instance timeout is.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Edition {
+    Community,
+    Enterprise,
+}
+
+/// Version reported by the server.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ServerVersion {
+    /// As reported, e.g. `"17.0+e"` or `"saas~17.2+e"`.
+    pub server_version: String,
+    pub major: u32,
+    pub minor: u32,
+    pub edition: Edition,
+}
+
+impl ServerVersion {
+    /// Parse the result of `version_info` / `common.version`, whose
+    /// `server_version_info` looks like `[17, 0, 0, "final", 0, "e"]` (or
+    /// `["saas~17", 2, ...]` on SaaS builds).
+    pub fn from_version_info(info: &Value) -> Option<Self> {
+        let server_version = info
+            .get("server_version")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        let parts = info.get("server_version_info").and_then(Value::as_array);
+
+        let (major, minor) = match parts {
+            Some(parts) => {
+                let major = match parts.first()? {
+                    Value::Number(n) => n.as_u64()? as u32,
+                    Value::String(s) => s.trim_start_matches("saas~").parse().ok()?,
+                    _ => return None,
+                };
+                let minor = parts.get(1).and_then(Value::as_u64).unwrap_or(0) as u32;
+                (major, minor)
+            }
+            None => parse_version_string(&server_version)?,
+        };
+
+        let enterprise = parts
+            .and_then(|p| p.get(5))
+            .and_then(Value::as_str)
+            .is_some_and(|e| e == "e")
+            || server_version.ends_with("+e");
+
+        Some(Self {
+            server_version,
+            major,
+            minor,
+            edition: if enterprise {
+                Edition::Enterprise
+            } else {
+                Edition::Community
+            },
+        })
+    }
+}
+
+/// `"17.0+e"`, `"saas~17.2"` -> (17, 0), (17, 2).
+fn parse_version_string(raw: &str) -> Option<(u32, u32)> {
+    let raw = raw.trim().trim_start_matches("saas~");
+    let raw = raw.split('+').next().unwrap_or(raw);
+    let mut parts = raw.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts
+        .next()
+        .and_then(|m| {
+            m.chars()
+                .take_while(|c| c.is_ascii_digit())
+                .collect::<String>()
+                .parse()
+                .ok()
+        })
+        .unwrap_or(0);
+    Some((major, minor))
+}
+
+/// Major version from a configured `version` string.
+pub fn configured_major(cfg: &OdooInstanceConfig) -> Option<u32> {
+    parse_version_string(cfg.version.as_deref()?).map(|(major, _)| major)
+}
+
+/// Whether `ODOO_VERSION_DETECTION` allows probing (default on).
+pub fn detection_enabled() -> bool {
+    std::env::var("ODOO_VERSION_DETECTION")
+        .map(|v| {
+            !matches!(
+                v.trim().to_lowercase().as_str(),
+                "0" | "false" | "no" | "off"
+            )
+        })
+        .unwrap_or(true)
+}
+
+/// Ask the server for its version.
+pub async fn detect(cfg: &OdooInstanceConfig) -> OdooResult<ServerVersion> {
+    let mut base = Url::parse(&cfg.url)
+        .map_err(|e| OdooError::InvalidResponse(format!("Invalid Odoo url '{}': {e}", cfg.url)))?;
+    base.set_query(None);
+    base.set_fragment(None);
+    let timeout = Duration::from_millis(cfg.timeout_ms.unwrap_or(30_000)).min(PROBE_TIMEOUT);
+    let http = transport::build_http_client(cfg, timeout, false)
+        .map_err(|e| OdooError::InvalidResponse(e.to_string()))?;
+    let endpoint = |path: &str| {
+        let mut url = base.clone();
+        url.set_path(path);
+        url
+    };
+
+    let result = if cfg.protocol == Some(OdooProtocol::XmlRpc) {
+        post_xmlrpc(&http, endpoint("/xmlrpc/2/common")).await
+    } else {
+        match post_jsonrpc(&http, endpoint("/web/webclient/version_info"), json!({})).await {
+            Ok(result) => Ok(result),
+            Err(OdooError::Http(e)) => Err(OdooError::Http(e)),
+            Err(_) => {
+                let params = json!({"service": "common", "method": "version", "args": []});
+                post_jsonrpc(&http, endpoint("/jsonrpc"), params).await
+            }
+        }
+    }?;
+
+    ServerVersion::from_version_info(&result)
+        .ok_or_else(|| OdooError::InvalidResponse(format!("Unrecognized version info: {result}")))
+}
+
+async fn post_jsonrpc(http: &reqwest::Client, url: Url, params: Value) -> OdooResult<Value> {
+    let body = json!({"jsonrpc": "2.0", "method": "call", "params": params, "id": 1});
+    let resp = http
+        .post(url)
+        .header(USER_AGENT, HeaderValue::from_static("odoo-mcp-rust/0.1"))
+        .json(&body)
+        .send()
+        .await?;
+    let status = resp.status();
+    let text = resp.text().await.unwrap_or_default();
+    if !status.is_success() {
+        return Err(OdooError::from_odoo(status.as_u16(), text, None));
+    }
+    let v: Value = serde_json::from_str(&text)
+        .map_err(|e| OdooError::InvalidResponse(format!("Failed to parse version info: {e}")))?;
+    v.get("result")
+        .cloned()
+        .ok_or_else(|| OdooError::InvalidResponse("version info missing 'result'".to_string()))
+}
+
+async fn post_xmlrpc(http: &reqwest::Client, url: Url) -> OdooResult<Value> {
+    let resp = http
+        .post(url)
+        .header(USER_AGENT, HeaderValue::from_static("odoo-mcp-rust/0.1"))
+        .header(
+            CONTENT_TYPE,
+            HeaderValue::from_static("text/xml; charset=utf-8"),
+        )
+        .body(xmlrpc::encode_call("version", &[]))
+        .send()
+        .await?;
+    let status = resp.status();
+    let text = resp.text().await.unwrap_or_default();
+    if !status.is_success() {
+        return Err(OdooError::from_odoo(status.as_u16(), text, None));
+    }
+    xmlrpc::decode_response(&text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_version_info_enterprise() {
+        let info = json!({
+            "server_version": "17.0+e",
+            "server_version_info": [17, 0, 0, "final", 0, "e"],
+            "server_serie": "17.0",
+            "protocol_version": 1
+        });
+        let v = ServerVersion::from_version_info(&info).unwrap();
+        assert_eq!((v.major, v.minor), (17, 0));
+        assert_eq!(v.edition, Edition::Enterprise);
+        assert_eq!(v.server_version, "17.0+e");
+    }
+
+    #[test]
+    fn test_from_version_info_saas_community() {
+        let info = json!({
+            "server_version": "saas~18.2",
+            "server_version_info": ["saas~18", 2, 0, "final", 0, ""]
+        });
+        let v = ServerVersion::from_version_info(&info).unwrap();
+        assert_eq!((v.major, v.minor), (18, 2));
+        assert_eq!(v.edition, Edition::Community);
+    }
+
+    #[test]
+    fn test_from_version_info_string_only() {
+        let v = ServerVersion::from_version_info(&json!({"server_version": "16.0"})).unwrap();
+        assert_eq!(v.major, 16);
+        assert!(ServerVersion::from_version_info(&json!({})).is_none());
+    }
+
+    #[test]
+    fn test_configured_major() {
+        let mut cfg: OdooInstanceConfig =
+            serde_json::from_value(json!({"url": "http://localhost"})).unwrap();
+        assert_eq!(configured_major(&cfg), None);
+        cfg.version = Some("saas~17.2".to_string());
+        assert_eq!(configured_major(&cfg), Some(17));
+        cfg.version = Some("18".to_string());
+        assert_eq!(configured_major(&cfg), Some(18));
+    }
+}
@@ -0,0 +1,389 @@
+//! Minimal XML-RPC codec for Odoo's `/xmlrpc/2/<service>` endpoints.
+//!
+//! Values are mapped to and from `serde_json::Value` so the legacy client can use
+//! the same `execute_kw` surface for both protocols. `dateTime.iso8601` values are
+//! decoded to Odoo's `YYYY-MM-DD HH:MM:SS` strings and `base64` values to their
+//! base64 text, matching what JSON-RPC returns for datetime and binary fields.
+
+use serde_json::{Map, Number, Value};
+
+use super::types::{OdooError, OdooErrorBody, OdooResult};
+
+/// Integers outside this range are sent as `<i8>`, which Python's XML-RPC
+/// unmarshaller accepts.
+const I4_RANGE: std::ops::RangeInclusive<i64> = i32::MIN as i64..=i32::MAX as i64;
+
+/// Encode a `<methodCall>` document.
+pub fn encode_call(method: &str, params: &[Value]) -> String {
+    let mut out = String::from("<?xml version=\"1.0\"?>\n<methodCall><methodName>");
+    escape_into(&mut out, method);
+    out.push_str("</methodName><params>");
+    for param in params {
+        out.push_str("<param>");
+        encode_value(&mut out, param);
+        out.push_str("</param>");
+    }
+    out.push_str("</params></methodCall>");
+    out
+}
+
+fn encode_value(out: &mut String, value: &Value) {
+    out.push_str("<value>");
+    match value {
+        Value::Null => out.push_str("<nil/>"),
+        Value::Bool(b) => {
+            out.push_str(if *b {
+                "<boolean>1</boolean>"
+            } else {
+                "<boolean>0</boolean>"
+            });
+        }
+        Value::Number(n) => match n.as_i64() {
+            Some(i) if I4_RANGE.contains(&i) => out.push_str(&format!("<int>{i}</int>")),
+            Some(i) => out.push_str(&format!("<i8>{i}</i8>")),
+            None => out.push_str(&format!("<double>{}</double>", n.as_f64().unwrap_or(0.0))),
+        },
+        Value::String(s) => {
+            out.push_str("<string>");
+            escape_into(out, s);
+            out.push_str("</string>");
+        }
+        Value::Array(items) => {
+            out.push_str("<array><data>");
+            for item in items {
+                encode_value(out, item);
+            }
+            out.push_str("</data></array>");
+        }
+        Value::Object(members) => {
+            out.push_str("<struct>");
+            for (name, member) in members {
+                out.push_str("<member><name>");
+                escape_into(out, name);
+                out.push_str("</name>");
+                encode_value(out, member);
+                out.push_str("</member>");
+            }
+            out.push_str("</struct>");
+        }
+    }
+    out.push_str("</value>");
+}
+
+fn escape_into(out: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            c => out.push(c),
+        }
+    }
+}
+
+/// Decode a `<methodResponse>`, turning a `<fault>` into an [`OdooError`].
+pub fn decode_response(xml: &str) -> OdooResult<Value> {
+    let doc = roxmltree::Document::parse(xml)
+        .map_err(|e| OdooError::InvalidResponse(format!("Invalid XML-RPC response: {e}")))?;
+    let root = doc.root_element();
+    if root.tag_name().name() != "methodResponse" {
+        return Err(invalid(format!(
+            "expected <methodResponse>, got <{}>",
+            root.tag_name().name()
+        )));
+    }
+
+    if let Some(fault) = child(root, "fault") {
+        let value = child(fault, "value")
+            .map(decode_value)
+            .transpose()?
+            .unwrap_or(Value::Null);
+        return Err(fault_error(&value));
+    }
+
+    let value = child(root, "params")
+        .and_then(|p| child(p, "param"))
+        .and_then(|p| child(p, "value"))
+        .ok_or_else(|| invalid("missing <params><param><value>".to_string()))?;
+    decode_value(value)
+}
+
+fn decode_value(node: roxmltree::Node) -> OdooResult<Value> {
+    // A <value> without a type element is a string.
+    let Some(typed) = node.children().find(|n| n.is_element()) else {
+        return Ok(Value::String(text(node)));
+    };
+    let raw = text(typed);
+    let value = match typed.tag_name().name() {
+        "int" | "i4" | "i8" => Value::from(
+            raw.trim()
+                .parse::<i64>()
+                .map_err(|e| invalid(format!("bad integer '{raw}': {e}")))?,
+        ),
+        "boolean" => Value::Bool(raw.trim() == "1"),
+        "double" => {
+            let f = raw
+                .trim()
+                .parse::<f64>()
+                .map_err(|e| invalid(format!("bad double '{raw}': {e}")))?;
+            Number::from_f64(f).map_or(Value::Null, Value::Number)
+        }
+        "string" => Value::String(raw),
+        "nil" => Value::Null,
+        "dateTime.iso8601" => Value::String(odoo_datetime(raw.trim())),
+        "base64" => Value::String(raw.split_whitespace().collect()),
+        "array" => Value::Array(
+            child(typed, "data")
+                .into_iter()
+                .flat_map(|data| data.children().filter(|n| n.has_tag_name("value")))
+                .map(decode_value)
+                .collect::<OdooResult<_>>()?,
+        ),
+        "struct" => {
+            let mut members = Map::new();
+            for member in typed.children().filter(|n| n.has_tag_name("member")) {
+                let name = child(member, "name").map(text).unwrap_or_default();
+                let value = child(member, "value")
+                    .map(decode_value)
+                    .transpose()?
+                    .unwrap_or(Value::Null);
+                members.insert(name, value);
+            }
+            Value::Object(members)
+        }
+        other => return Err(invalid(format!("unsupported XML-RPC type <{other}>"))),
+    };
+    Ok(value)
+}
+
+/// `20240115T10:30:00` (or `2024-01-15T10:30:00`) -> `2024-01-15 10:30:00`.
+fn odoo_datetime(raw: &str) -> String {
+    let (date, time) = raw.split_once('T').unwrap_or((raw, ""));
+    let date = if date.len() == 8 && date.chars().all(|c| c.is_ascii_digit()) {
+        format!("{}-{}-{}", &date[..4], &date[4..6], &date[6..])
+    } else {
+        date.to_string()
+    };
+    if time.is_empty() {
+        date
+    } else {
+        format!("{date} {time}")
+    }
+}
+
+/// Map an Odoo fault to an error. `/xmlrpc/2` uses numeric codes: 3 is
+/// AccessDenied, 4 AccessError, 2 a user-facing warning; anything else carries
+/// the server traceback, whose last line names the exception.
+fn fault_error(fault: &Value) -> OdooError {
+    let code = fault.get("faultCode").cloned().unwrap_or(Value::Null);
+    let text = fault
+        .get("faultString")
+        .and_then(Value::as_str)
+        .unwrap_or("XML-RPC fault")
+        .to_string();
+
+    let (name, message) = match code.as_i64() {
+        Some(3) => (
+            Some("odoo.exceptions.AccessDenied".to_string()),
+            text.clone(),
+        ),
+        Some(4) => (
+            Some("odoo.exceptions.AccessError".to_string()),
+            text.clone(),
+        ),
+        Some(2) => (Some("odoo.exceptions.UserError".to_string()), text.clone()),
+        _ => {
+            let last = text
+                .lines()
+                .rev()
+                .find(|l| !l.trim().is_empty())
+                .unwrap_or("");
+            match last.split_once(": ") {
+                Some((name, message))
+                    if !name.is_empty()
+                        && name
+                            .chars()
+                            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '_') =>
+                {
+                    (Some(name.to_string()), message.to_string())
+                }
+                _ => (None, last.to_string()),
+            }
+        }
+    };
+
+    let body = OdooErrorBody {
+        name,
+        message: Some(message.clone()),
+        arguments: Vec::new(),
+        context: Value::Null,
+        debug: Some(text),
+    };
+    OdooError::from_odoo(400, message, Some(body))
+}
+
+fn child<'a, 'input>(
+    node: roxmltree::Node<'a, 'input>,
+    name: &str,
+) -> Option<roxmltree::Node<'a, 'input>> {
+    node.children().find(|n| n.has_tag_name(name))
+}
+
+fn text(node: roxmltree::Node) -> String {
+    node.descendants()
+        .filter(|n| n.is_text())
+        .filter_map(|n| n.text())
+        .collect()
+}
+
+fn invalid(message: String) -> OdooError {
+    OdooError::InvalidResponse(format!("Invalid XML-RPC response: {message}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::odoo::types::OdooErrorKind;
+    use serde_json::json;
+
+    fn response(value: &str) -> String {
+        format!(
+            "<?xml version='1.0'?>\n<methodResponse><params><param><value>{value}</value></param></params></methodResponse>"
+        )
+    }
+
+    #[test]
+    fn test_encode_call() {
+        let xml = encode_call(
+            "execute_kw",
+            &[
+                json!("db"),
+                json!(2),
+                json!(null),
+                json!([["name", "ilike", "a&b<c>"]]),
+                json!({"limit": 5, "active": true, "ratio": 0.5}),
+                json!(5_000_000_000i64),
+            ],
+        );
+        assert!(xml.starts_with(
+            "<?xml version=\"1.0\"?>\n<methodCall><methodName>execute_kw</methodName>"
+        ));
+        assert!(xml.contains("<param><value><string>db</string></value></param>"));
+        assert!(xml.contains("<param><value><int>2</int></value></param>"));
+        assert!(xml.contains("<param><value><nil/></value></param>"));
+        assert!(xml.contains("<value><string>a&amp;b&lt;c&gt;</string></value>"));
+        assert!(
+            xml.contains("<member><name>active</name><value><boolean>1</boolean></value></member>")
+        );
+        assert!(xml.contains("<value><double>0.5</double></value>"));
+        assert!(xml.contains("<value><i8>5000000000</i8></value>"));
+    }
+
+    #[test]
+    fn test_round_trip_through_decoder() {
+        let value = json!({"ids": [1, 2], "name": "x", "none": null, "ok": false});
+        let mut xml = String::new();
+        encode_value(&mut xml, &value);
+        let xml = format!("<methodResponse><params><param>{xml}</param></params></methodResponse>");
+        assert_eq!(decode_response(&xml).unwrap(), value);
+    }
+
+    #[test]
+    fn test_decode_scalars() {
+        assert_eq!(
+            decode_response(&response("<int>42</int>")).unwrap(),
+            json!(42)
+        );
+        assert_eq!(
+            decode_response(&response("<i4>-1</i4>")).unwrap(),
+            json!(-1)
+        );
+        assert_eq!(
+            decode_response(&response("<boolean>0</boolean>")).unwrap(),
+            json!(false)
+        );
+        assert_eq!(
+            decode_response(&response("<double>1.5</double>")).unwrap(),
+            json!(1.5)
+        );
+        assert_eq!(decode_response(&response("<nil/>")).unwrap(), json!(null));
+        assert_eq!(
+            decode_response(&response("plain &amp; untyped")).unwrap(),
+            json!("plain & untyped")
+        );
+        assert_eq!(
+            decode_response(&response("<string></string>")).unwrap(),
+            json!("")
+        );
+    }
+
+    #[test]
+    fn test_decode_dates_and_binary() {
+        assert_eq!(
+            decode_response(&response(
+                "<dateTime.iso8601>20240115T10:30:00</dateTime.iso8601>"
+            ))
+            .unwrap(),
+            json!("2024-01-15 10:30:00")
+        );
+        assert_eq!(
+            decode_response(&response("<base64>\nSGVs\nbG8=\n</base64>")).unwrap(),
+            json!("SGVsbG8=")
+        );
+    }
+
+    #[test]
+    fn test_decode_struct_and_array() {
+        let xml = response(
+            "<array><data>\
+               <value><struct>\
+                 <member><name>id</name><value><int>7</int></value></member>\
+                 <member><name>partner_id</name><value><array><data>\
+                   <value><int>3</int></value><value><string>ACME</string></value>\
+                 </data></array></value></member>\
+                 <member><name>email</name><value><boolean>0</boolean></value></member>\
+               </struct></value>\
+             </data></array>",
+        );
+        assert_eq!(
+            decode_response(&xml).unwrap(),
+            json!([{"id": 7, "partner_id": [3, "ACME"], "email": false}])
+        );
+    }
+
+    fn fault(code: &str, text: &str) -> String {
+        format!(
+            "<methodResponse><fault><value><struct>\
+               <member><name>faultCode</name><value>{code}</value></member>\
+               <member><name>faultString</name><value><string>{text}</string></value></member>\
+             </struct></value></fault></methodResponse>"
+        )
+    }
+
+    #[test]
+    fn test_decode_faults() {
+        let err = decode_response(&fault("<int>3</int>", "Access Denied")).unwrap_err();
+        assert_eq!(err.kind(), Some(OdooErrorKind::AccessDenied));
+
+        let err = decode_response(&fault(
+            "<int>1</int>",
+            "Traceback (most recent call last):\n  File ...\nodoo.exceptions.ValidationError: Name is required\n",
+        ))
+        .unwrap_err();
+        assert_eq!(err.kind(), Some(OdooErrorKind::ValidationError));
+        assert_eq!(err.message(), "Name is required");
+        assert!(err.traceback().unwrap().starts_with("Traceback"));
+    }
+
+    #[test]
+    fn test_decode_rejects_garbage() {
+        assert!(matches!(
+            decode_response("not xml"),
+            Err(OdooError::InvalidResponse(_))
+        ));
+        assert!(matches!(
+            decode_response("<html/>"),
+            Err(OdooError::InvalidResponse(_))
+        ));
+    }
+}
@@ -301,7 +301,8 @@ pub fn minimal_tools_json() -> &'static str {
                     "required": ["instance", "model"]
                 },
                 "op": {
-                    "type": "search"
+                    "type": "search",
+                    "map": {"instance": "/instance", "model": "/model", "domain": "/domain"}
                 }
             }
         ]
@@ -71,7 +71,7 @@ async fn setup_test_server() -> (TestServer, TempDir) {
     }
 
     async fn get_instances(State(state): State<AppState>) -> impl IntoResponse {
-        match state.config_manager.load_instances().await {
+        match state.config_manager.load_instances_masked().await {
             Ok(config) => (StatusCode::OK, Json(config)).into_response(),
             Err(e) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -279,6 +279,61 @@ async fn test_update_instances() {
     assert_eq!(data["production"]["url"], "http://prod.example.com:8069");
 }
 
+#[tokio::test]
+async fn test_instances_secrets_masked_and_preserved() {
+    let (server, temp_dir) = setup_test_server().await;
+
+    let config = json!({
+        "default": {
+            "url": "http://localhost:8069",
+            "db": "test_db",
+            "apiKey": "test_key",
+            "headers": {"CF-Access-Client-Secret": "cf-secret"}
+        },
+        "legacy": {
+            "url": "http://legacy:8069",
+            "db": "legacy",
+            "username": "admin",
+            "password": "file:/run/secrets/legacy"
+        }
+    });
+    server
+        .post("/api/config/instances")
+        .json(&config)
+        .await
+        .assert_status_ok();
+
+    let data: Value = server.get("/api/config/instances").await.json();
+    assert_eq!(data["default"]["apiKey"], "********");
+    assert_eq!(
+        data["default"]["headers"]["CF-Access-Client-Secret"],
+        "********"
+    );
+    assert_eq!(data["legacy"]["password"], "file:/run/secrets/legacy");
+    assert!(!data.to_string().contains("test_key"));
+
+    // Saving the masked config back keeps the stored credentials.
+    let mut edited = data.clone();
+    edited["default"]["url"] = json!("http://odoo:8069");
+    server
+        .post("/api/config/instances")
+        .json(&edited)
+        .await
+        .assert_status_ok();
+
+    let stored: Value = serde_json::from_str(
+        &std::fs::read_to_string(temp_dir.path().join("instances.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(stored["default"]["url"], "http://odoo:8069");
+    assert_eq!(stored["default"]["apiKey"], "test_key");
+    assert_eq!(
+        stored["default"]["headers"]["CF-Access-Client-Secret"],
+        "cf-secret"
+    );
+    assert_eq!(stored["legacy"]["password"], "file:/run/secrets/legacy");
+}
+
 #[tokio::test]
 async fn test_update_instances_invalid_json() {
     let (server, _temp_dir) = setup_test_server().await;
@@ -452,7 +507,7 @@ async fn test_get_nonexistent_config() {
     };
 
     async fn get_instances(State(state): State<AppState>) -> impl IntoResponse {
-        match state.config_manager.load_instances().await {
+        match state.config_manager.load_instances_masked().await {
             Ok(config) => (StatusCode::OK, Json(config)).into_response(),
             Err(e) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -0,0 +1,250 @@
+//! Integration tests for the OAuth resource server of the HTTP transport, with
+//! locally generated Ed25519 keys and a JWKS served over HTTP.
+
+mod common;
+
+use axum::http::{HeaderName, HeaderValue};
+use axum_test::TestServer;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use common::{minimal_prompts_json, minimal_server_json, minimal_tools_json};
+use jsonwebtoken::{Algorithm, EncodingKey, Header, encode};
+use ring::rand::SystemRandom;
+use ring::signature::{Ed25519KeyPair, KeyPair};
+use rust_mcp::mcp::McpOdooHandler;
+use rust_mcp::mcp::http::{AuthConfig, create_app};
+use rust_mcp::mcp::oauth::OAuthConfig;
+use rust_mcp::mcp::registry::Registry;
+use rust_mcp::mcp::tools::OdooClientPool;
+use serde_json::{Value, json};
+use std::sync::Arc;
+use tempfile::TempDir;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const ISSUER: &str = "https://auth.example.com";
+const RESOURCE: &str = "https://mcp.example.com/mcp";
+
+struct OAuthTestServer {
+    server: TestServer,
+    signing_key: EncodingKey,
+    _jwks: MockServer,
+    _temp: TempDir,
+}
+
+async fn setup() -> OAuthTestServer {
+    let temp = TempDir::new().unwrap();
+    std::fs::write(temp.path().join("tools.json"), minimal_tools_json()).unwrap();
+    std::fs::write(temp.path().join("prompts.json"), minimal_prompts_json()).unwrap();
+    std::fs::write(temp.path().join("server.json"), minimal_server_json()).unwrap();
+    unsafe {
+        std::env::set_var("ODOO_URL", "http://localhost:8069");
+        std::env::set_var("ODOO_DB", "test_db");
+        std::env::set_var("ODOO_API_KEY", "test_key");
+        std::env::set_var("MCP_TOOLS_JSON", temp.path().join("tools.json"));
+        std::env::set_var("MCP_PROMPTS_JSON", temp.path().join("prompts.json"));
+        std::env::set_var("MCP_SERVER_JSON", temp.path().join("server.json"));
+    }
+
+    let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+    let pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+    let jwks = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/jwks.json"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "keys": [{
+                "kty": "OKP",
+                "crv": "Ed25519",
+                "alg": "EdDSA",
+                "kid": "test-key",
+                "x": URL_SAFE_NO_PAD.encode(pair.public_key().as_ref()),
+            }]
+        })))
+        // Fetched on the first token, then cached.
+        .expect(0..=1)
+        .mount(&jwks)
+        .await;
+
+    let oauth = OAuthConfig::new(
+        ISSUER.to_string(),
+        RESOURCE.to_string(),
+        RESOURCE.to_string(),
+        &format!("{}/jwks.json", jwks.uri()),
+        vec!["odoo:read".to_string()],
+        "odoo_instances".to_string(),
+    )
+    .unwrap();
+
+    let pool = OdooClientPool::from_env().unwrap();
+    let registry = Arc::new(Registry::from_env());
+    registry.initial_load().await.unwrap();
+    let handler = Arc::new(McpOdooHandler::new(pool, registry));
+    let app = create_app(handler, AuthConfig::with_oauth(oauth));
+
+    OAuthTestServer {
+        server: TestServer::new(app.into_make_service()).unwrap(),
+        signing_key: EncodingKey::from_ed_der(pkcs8.as_ref()),
+        _jwks: jwks,
+        _temp: temp,
+    }
+}
+
+impl OAuthTestServer {
+    fn token(&self, claims: Value) -> String {
+        let mut full = json!({
+            "iss": ISSUER,
+            "aud": RESOURCE,
+            "sub": "alice",
+            "exp": chrono::Utc::now().timestamp() + 300,
+        });
+        full.as_object_mut()
+            .unwrap()
+            .extend(claims.as_object().unwrap().clone());
+        let mut header = Header::new(Algorithm::EdDSA);
+        header.kid = Some("test-key".to_string());
+        encode(&header, &full, &self.signing_key).unwrap()
+    }
+
+    async fn post(
+        &self,
+        token: &str,
+        session: Option<&str>,
+        body: Value,
+    ) -> axum_test::TestResponse {
+        let mut request = self.server.post("/mcp").add_header(
+            HeaderName::from_static("authorization"),
+            HeaderValue::from_str(&format!("Bearer {token}")).unwrap(),
+        );
+        if let Some(session) = session {
+            request = request.add_header(
+                HeaderName::from_static("mcp-session-id"),
+                HeaderValue::from_str(session).unwrap(),
+            );
+        }
+        request.json(&body).await
+    }
+}
+
+fn challenge(response: &axum_test::TestResponse) -> String {
+    response.headers()["www-authenticate"]
+        .to_str()
+        .unwrap()
+        .to_string()
+}
+
+#[tokio::test]
+async fn test_protected_resource_metadata() {
+    let t = setup().await;
+    let response = t
+        .server
+        .get("/.well-known/oauth-protected-resource/mcp")
+        .await;
+    response.assert_status_ok();
+    let body: Value = response.json();
+    assert_eq!(body["resource"], RESOURCE);
+    assert_eq!(body["authorization_servers"], json!([ISSUER]));
+    assert!(
+        body["scopes_supported"]
+            .as_array()
+            .unwrap()
+            .contains(&json!("odoo:write"))
+    );
+}
+
+#[tokio::test]
+async fn test_missing_token_gets_challenge() {
+    let t = setup().await;
+    let response = t
+        .server
+        .post("/mcp")
+        .json(&json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}}))
+        .await;
+    response.assert_status_unauthorized();
+    assert_eq!(
+        challenge(&response),
+        "Bearer resource_metadata=\"https://mcp.example.com/.well-known/oauth-protected-resource/mcp\""
+    );
+}
+
+#[tokio::test]
+async fn test_jwt_claims_scope_tools_and_instances() {
+    let t = setup().await;
+    let token = t.token(json!({"scope": "odoo:read", "odoo_instances": ["prod-*"]}));
+
+    let init = t
+        .post(
+            &token,
+            None,
+            json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}}),
+        )
+        .await;
+    init.assert_status_ok();
+    let body: Value = init.json();
+    assert_eq!(
+        body["result"]["capabilities"]["experimental"]["odooInstances"]["available"],
+        json!([])
+    );
+    let session = init.headers()["mcp-session-id"]
+        .to_str()
+        .unwrap()
+        .to_string();
+    t.post(
+        &token,
+        Some(&session),
+        json!({"jsonrpc": "2.0", "method": "initialized"}),
+    )
+    .await;
+
+    let body: Value = t
+        .post(
+            &token,
+            Some(&session),
+            json!({"jsonrpc": "2.0", "id": 2, "method": "tools/list", "params": {}}),
+        )
+        .await
+        .json();
+    assert_eq!(body["result"]["tools"][0]["name"], "odoo_search");
+
+    let body: Value = t
+        .post(
+            &token,
+            Some(&session),
+            json!({"jsonrpc": "2.0", "id": 3, "method": "tools/call", "params": {
+                "name": "odoo_search",
+                "arguments": {"instance": "default", "model": "res.partner"}
+            }}),
+        )
+        .await
+        .json();
+    assert_eq!(body["result"]["isError"], true);
+    assert!(
+        body["result"]["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("Instance not permitted")
+    );
+}
+
+#[tokio::test]
+async fn test_invalid_and_insufficient_tokens() {
+    let t = setup().await;
+    let init = json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}});
+
+    let expired = t.token(json!({"scope": "odoo:read", "exp": 1_000_000}));
+    let response = t.post(&expired, None, init.clone()).await;
+    response.assert_status_unauthorized();
+    assert!(challenge(&response).contains("error=\"invalid_token\""));
+    assert!(challenge(&response).contains("expired"));
+
+    let wrong_audience = t.token(json!({"scope": "odoo:read", "aud": "https://other.example.com"}));
+    t.post(&wrong_audience, None, init.clone())
+        .await
+        .assert_status_unauthorized();
+
+    let write_only = t.token(json!({"scope": "odoo:write"}));
+    let response = t.post(&write_only, None, init).await;
+    response.assert_status_forbidden();
+    let header = challenge(&response);
+    assert!(header.contains("error=\"insufficient_scope\""), "{header}");
+    assert!(header.contains("scope=\"odoo:read\""), "{header}");
+}
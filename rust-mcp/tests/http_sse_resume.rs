@@ -0,0 +1,99 @@
+//! Integration test for SSE resumability: events sent while a client was away
+//! are replayed after `Last-Event-ID`, and lost ones are signalled.
+
+mod common;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use common::{minimal_prompts_json, minimal_server_json, minimal_tools_json};
+use rust_mcp::mcp::McpOdooHandler;
+use rust_mcp::mcp::http::{AuthConfig, SecurityConfig, SessionLimits, create_app_with_security};
+use rust_mcp::mcp::registry::Registry;
+use rust_mcp::mcp::tools::OdooClientPool;
+use serde_json::json;
+
+/// Read an SSE stream until `until` shows up (or a timeout).
+async fn read_stream(base: &str, session: &str, last_event_id: &str, until: &str) -> String {
+    let mut response = reqwest::Client::new()
+        .get(format!("{base}/mcp"))
+        .header("accept", "text/event-stream")
+        .header("mcp-session-id", session)
+        .header("last-event-id", last_event_id)
+        .send()
+        .await
+        .unwrap();
+    let mut body = String::new();
+    let _ = tokio::time::timeout(Duration::from_secs(5), async {
+        while let Some(chunk) = response.chunk().await.unwrap() {
+            body.push_str(&String::from_utf8_lossy(&chunk));
+            if body.contains(until) {
+                break;
+            }
+        }
+    })
+    .await;
+    body
+}
+
+#[tokio::test]
+async fn test_sse_replays_missed_events_and_signals_gaps() {
+    let temp = tempfile::TempDir::new().unwrap();
+    std::fs::write(temp.path().join("tools.json"), minimal_tools_json()).unwrap();
+    std::fs::write(temp.path().join("prompts.json"), minimal_prompts_json()).unwrap();
+    std::fs::write(temp.path().join("server.json"), minimal_server_json()).unwrap();
+    unsafe {
+        std::env::set_var("ODOO_URL", "http://localhost:8069");
+        std::env::set_var("ODOO_API_KEY", "test_key");
+        std::env::set_var("MCP_TOOLS_JSON", temp.path().join("tools.json"));
+        std::env::set_var("MCP_PROMPTS_JSON", temp.path().join("prompts.json"));
+        std::env::set_var("MCP_SERVER_JSON", temp.path().join("server.json"));
+    }
+    let registry = Arc::new(Registry::from_env());
+    registry.initial_load().await.unwrap();
+    let handler = Arc::new(McpOdooHandler::new(
+        OdooClientPool::from_env().unwrap(),
+        registry,
+    ));
+    let security = SecurityConfig {
+        sessions: SessionLimits {
+            event_buffer_size: 2,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let app = create_app_with_security(handler.clone(), AuthConfig::disabled(), security);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    let init = reqwest::Client::new()
+        .post(format!("{base}/mcp"))
+        .json(&json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}}))
+        .send()
+        .await
+        .unwrap();
+    let session = init.headers()["mcp-session-id"]
+        .to_str()
+        .unwrap()
+        .to_string();
+
+    // Three events while no stream is open; the buffer keeps the last two.
+    for n in 1..=3 {
+        handler.notify("notifications/message", Some(json!({"data": n})));
+    }
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let body = read_stream(&base, &session, &format!("{session}:2"), "\"data\":3").await;
+    assert!(body.contains(&format!("id: {session}:3")), "{body}");
+    assert!(!body.contains("\"data\":2"), "{body}");
+    assert!(!body.contains("event: gap"), "{body}");
+
+    let body = read_stream(&base, &session, &format!("{session}:0"), "\"data\":3").await;
+    let gap = body.find("event: gap").expect("no gap event");
+    let replayed = body
+        .find("\"data\":2")
+        .expect("buffered event not replayed");
+    assert!(gap < replayed, "{body}");
+    assert!(!body.contains("\"data\":1"), "{body}");
+}
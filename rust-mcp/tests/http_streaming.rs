@@ -0,0 +1,153 @@
+//! Integration test for streamed tool calls: with `Accept: text/event-stream`,
+//! POST /mcp answers a tools/call with its progress notifications, then its
+//! result, as SSE events.
+
+mod common;
+
+use axum::http::{HeaderName, HeaderValue};
+use axum_test::TestServer;
+use common::{minimal_prompts_json, minimal_server_json};
+use rust_mcp::mcp::McpOdooHandler;
+use rust_mcp::mcp::http::{AuthConfig, create_app};
+use rust_mcp::mcp::registry::Registry;
+use rust_mcp::mcp::tools::OdooClientPool;
+use serde_json::{Value, json};
+use std::sync::Arc;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn create_batch_tools_json() -> String {
+    json!({
+        "tools": [{
+            "name": "odoo_create_batch",
+            "description": "Create records",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "instance": {"type": "string"},
+                    "model": {"type": "string"},
+                    "values": {"type": "array"}
+                },
+                "required": ["instance", "model", "values"]
+            },
+            "op": {
+                "type": "create_batch",
+                "map": {"instance": "/instance", "model": "/model", "values": "/values"}
+            }
+        }]
+    })
+    .to_string()
+}
+
+/// The JSON payloads of the `message` events of an SSE body.
+fn sse_messages(body: &str) -> Vec<Value> {
+    body.lines()
+        .filter_map(|line| line.strip_prefix("data:"))
+        .map(|data| serde_json::from_str(data.trim()).unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_tool_call_streams_progress_then_result() {
+    let odoo = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/json/2/res.partner/create"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([42])))
+        .expect(4)
+        .mount(&odoo)
+        .await;
+
+    let temp = tempfile::TempDir::new().unwrap();
+    std::fs::write(temp.path().join("tools.json"), create_batch_tools_json()).unwrap();
+    std::fs::write(temp.path().join("prompts.json"), minimal_prompts_json()).unwrap();
+    std::fs::write(temp.path().join("server.json"), minimal_server_json()).unwrap();
+    unsafe {
+        std::env::set_var("ODOO_URL", odoo.uri());
+        std::env::set_var("ODOO_DB", "test_db");
+        std::env::set_var("ODOO_API_KEY", "test_key");
+        std::env::set_var("ODOO_VERSION_DETECTION", "false");
+        std::env::set_var("MCP_TOOLS_JSON", temp.path().join("tools.json"));
+        std::env::set_var("MCP_PROMPTS_JSON", temp.path().join("prompts.json"));
+        std::env::set_var("MCP_SERVER_JSON", temp.path().join("server.json"));
+    }
+    let registry = Arc::new(Registry::from_env());
+    registry.initial_load().await.unwrap();
+    let handler = Arc::new(McpOdooHandler::new(
+        OdooClientPool::from_env().unwrap(),
+        registry,
+    ));
+    let server =
+        TestServer::new(create_app(handler, AuthConfig::disabled()).into_make_service()).unwrap();
+
+    let call = |id: u64| {
+        json!({"jsonrpc": "2.0", "id": id, "method": "tools/call", "params": {
+            "name": "odoo_create_batch",
+            "arguments": {
+                "instance": "default",
+                "model": "res.partner",
+                "values": [{"name": "a"}, {"name": "b"}]
+            },
+            "_meta": {"progressToken": "batch-1"}
+        }})
+    };
+
+    let response = server
+        .post("/mcp")
+        .add_header(
+            HeaderName::from_static("accept"),
+            HeaderValue::from_static("application/json, text/event-stream"),
+        )
+        .json(&call(1))
+        .await;
+    response.assert_status_ok();
+    assert!(
+        response.headers()["content-type"]
+            .to_str()
+            .unwrap()
+            .starts_with("text/event-stream")
+    );
+    let messages = sse_messages(&response.text());
+    assert_eq!(messages.len(), 3, "{messages:?}");
+    for (i, message) in messages[..2].iter().enumerate() {
+        assert_eq!(message["method"], "notifications/progress");
+        assert_eq!(
+            message["params"],
+            json!({"progressToken": "batch-1", "progress": i + 1, "total": 2})
+        );
+    }
+    assert_eq!(messages[2]["id"], 1);
+    let text = messages[2]["result"]["content"][0]["text"]
+        .as_str()
+        .unwrap();
+    assert_eq!(serde_json::from_str::<Value>(text).unwrap()["count"], 2);
+
+    // Without text/event-stream the same call is a plain JSON response.
+    let response = server
+        .post("/mcp")
+        .add_header(
+            HeaderName::from_static("accept"),
+            HeaderValue::from_static("application/json"),
+        )
+        .json(&call(2))
+        .await;
+    let body: Value = response.json();
+    assert_eq!(body["id"], 2);
+    assert!(body["result"].is_object());
+
+    // A request that cannot be handled still gets a JSON-RPC error with its id.
+    let mut invalid = call(3);
+    invalid["id"] = json!(true);
+    let response = server
+        .post("/mcp")
+        .add_header(
+            HeaderName::from_static("accept"),
+            HeaderValue::from_static("application/json, text/event-stream"),
+        )
+        .json(&invalid)
+        .await;
+    let messages = sse_messages(&response.text());
+    assert_eq!(messages.len(), 1, "{messages:?}");
+    assert_eq!(messages[0]["jsonrpc"], "2.0");
+    assert_eq!(messages[0]["id"], true);
+    assert_eq!(messages[0]["error"]["code"], -32600);
+}
@@ -6,11 +6,13 @@ use axum::http::{HeaderName, HeaderValue};
 use axum_test::TestServer;
 use common::{minimal_prompts_json, minimal_server_json, minimal_tools_json};
 use rust_mcp::mcp::McpOdooHandler;
-use rust_mcp::mcp::http::{AuthConfig, create_app};
+use rust_mcp::mcp::http::{AuthConfig, SecurityConfig, SessionLimits, create_app_with_security};
+use rust_mcp::mcp::principal::load_principals;
 use rust_mcp::mcp::registry::Registry;
 use rust_mcp::mcp::tools::OdooClientPool;
 use serde_json::json;
 use std::sync::Arc;
+use std::time::Duration;
 use tempfile::TempDir;
 
 const MCP_SESSION_HEADER: &str = "mcp-session-id";
@@ -18,6 +20,22 @@ const AUTH_HEADER: &str = "authorization";
 
 /// Setup test environment and create a test server.
 async fn setup_test_server(with_auth: bool) -> (TestServer, TempDir) {
+    let auth = if with_auth {
+        AuthConfig::new(true, Some("test_token".to_string()))
+    } else {
+        AuthConfig::disabled()
+    };
+    setup_test_server_with_auth(auth).await
+}
+
+async fn setup_test_server_with_auth(auth: AuthConfig) -> (TestServer, TempDir) {
+    setup_test_server_with_security(auth, SecurityConfig::default()).await
+}
+
+async fn setup_test_server_with_security(
+    auth: AuthConfig,
+    security: SecurityConfig,
+) -> (TestServer, TempDir) {
     let temp_dir = TempDir::new().unwrap();
 
     // Write minimal config files
@@ -61,13 +79,7 @@ async fn setup_test_server(with_auth: bool) -> (TestServer, TempDir) {
     registry.initial_load().await.unwrap();
     let handler = Arc::new(McpOdooHandler::new(pool, registry));
 
-    let auth = if with_auth {
-        AuthConfig::new(true, Some("test_token".to_string()))
-    } else {
-        AuthConfig::disabled()
-    };
-
-    let app = create_app(handler, auth);
+    let app = create_app_with_security(handler, auth, security);
     let server = TestServer::new(app.into_make_service()).unwrap();
 
     (server, temp_dir)
@@ -460,3 +472,352 @@ async fn test_notification_returns_accepted() {
 
 // NOTE: Legacy messages endpoint test is commented out because
 // it requires SSE session which can cause test hangs.
+
+// ============================================================================
+// Metrics Endpoint Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_metrics_endpoint() {
+    let (server, _temp) = setup_test_server(false).await;
+
+    server
+        .post("/mcp")
+        .json(&json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {}
+        }))
+        .await
+        .assert_status_ok();
+
+    let response = server.get("/metrics").await;
+    response.assert_status_ok();
+    let content_type = response
+        .headers()
+        .get("content-type")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_string();
+    assert!(content_type.starts_with("text/plain"));
+
+    let text = response.text();
+    assert!(text.contains("# TYPE odoo_mcp_tool_calls_total counter"));
+    assert!(text.contains("# TYPE odoo_mcp_tool_call_duration_seconds histogram"));
+    assert!(text.contains("# TYPE odoo_mcp_odoo_requests_total counter"));
+    assert!(text.contains("odoo_mcp_metadata_cache_lookups_total{result=\"hit\"}"));
+    assert!(text.contains("odoo_mcp_registry_reloads_total{result=\"success\"}"));
+    assert!(text.contains("odoo_mcp_http_sessions 1\n"));
+    assert!(text.contains("odoo_mcp_sse_subscribers 0\n"));
+}
+
+#[tokio::test]
+async fn test_metrics_requires_auth_when_enabled() {
+    let (server, _temp) = setup_test_server(true).await;
+
+    server.get("/metrics").await.assert_status_unauthorized();
+
+    let response = server
+        .get("/metrics")
+        .add_header(
+            HeaderName::from_static(AUTH_HEADER),
+            HeaderValue::from_static("Bearer test_token"),
+        )
+        .await;
+    response.assert_status_ok();
+}
+
+// ============================================================================
+// Named Token Tests
+// ============================================================================
+
+async fn setup_named_tokens_server() -> (TestServer, TempDir) {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("tokens.json");
+    std::fs::write(
+        &path,
+        json!({
+            "reader": {"token": "reader_token", "instances": ["def*"], "readOnly": true},
+            "prod": {"token": "prod_token", "instances": ["prod-*"], "tools": ["read"]},
+            "writer": {"token": "writer_token", "tools": ["write"]},
+            "expired": {"token": "old_token", "expiresAt": "2020-01-01T00:00:00Z"}
+        })
+        .to_string(),
+    )
+    .unwrap();
+    let principals = load_principals(&path).unwrap();
+    setup_test_server_with_auth(AuthConfig::with_principals(principals)).await
+}
+
+/// Initialize a session with `token`, then send `method` in it.
+async fn call_as(
+    server: &TestServer,
+    token: &str,
+    method: &str,
+    params: serde_json::Value,
+) -> serde_json::Value {
+    let bearer = HeaderValue::from_str(&format!("Bearer {token}")).unwrap();
+    let init = server
+        .post("/mcp")
+        .add_header(HeaderName::from_static(AUTH_HEADER), bearer.clone())
+        .json(&json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}}))
+        .await;
+    init.assert_status_ok();
+    let session_id = init.headers()[MCP_SESSION_HEADER].clone();
+    server
+        .post("/mcp")
+        .add_header(HeaderName::from_static(AUTH_HEADER), bearer.clone())
+        .add_header(
+            HeaderName::from_static(MCP_SESSION_HEADER),
+            session_id.clone(),
+        )
+        .json(&json!({"jsonrpc": "2.0", "method": "initialized"}))
+        .await;
+    let response = server
+        .post("/mcp")
+        .add_header(HeaderName::from_static(AUTH_HEADER), bearer)
+        .add_header(HeaderName::from_static(MCP_SESSION_HEADER), session_id)
+        .json(&json!({"jsonrpc": "2.0", "id": 2, "method": method, "params": params}))
+        .await;
+    response.assert_status_ok();
+    response.json()
+}
+
+fn tool_names(body: &serde_json::Value) -> Vec<&str> {
+    body["result"]["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| t["name"].as_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_named_tokens_filter_tools_list() {
+    let (server, _temp) = setup_named_tokens_server().await;
+
+    let body = call_as(&server, "reader_token", "tools/list", json!({})).await;
+    assert_eq!(tool_names(&body), vec!["odoo_search"]);
+
+    // odoo_search is a read tool, outside a write-only scope.
+    let body = call_as(&server, "writer_token", "tools/list", json!({})).await;
+    assert!(tool_names(&body).is_empty());
+}
+
+#[tokio::test]
+async fn test_named_tokens_enforce_tool_and_instance_on_call() {
+    let (server, _temp) = setup_named_tokens_server().await;
+
+    let args = json!({"name": "odoo_search", "arguments": {"instance": "default", "model": "res.partner"}});
+    let body = call_as(&server, "prod_token", "tools/call", args.clone()).await;
+    assert_eq!(body["result"]["isError"], true);
+    let text = body["result"]["content"][0]["text"].as_str().unwrap();
+    assert!(text.contains("Instance not permitted"), "{text}");
+
+    let body = call_as(&server, "writer_token", "tools/call", args).await;
+    let text = body["result"]["content"][0]["text"].as_str().unwrap();
+    assert!(text.contains("Tool not permitted"), "{text}");
+}
+
+#[tokio::test]
+async fn test_named_tokens_scope_resources() {
+    let (server, _temp) = setup_named_tokens_server().await;
+
+    let body = call_as(&server, "reader_token", "resources/list", json!({})).await;
+    let uris: Vec<&str> = body["result"]["resources"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["uri"].as_str().unwrap())
+        .collect();
+    assert_eq!(uris, vec!["odoo://instances", "odoo://default/models"]);
+
+    let body = call_as(&server, "prod_token", "resources/list", json!({})).await;
+    assert_eq!(body["result"]["resources"].as_array().unwrap().len(), 1);
+
+    let body = call_as(
+        &server,
+        "prod_token",
+        "resources/read",
+        json!({"uri": "odoo://instances"}),
+    )
+    .await;
+    let text = body["result"]["contents"][0]["text"].as_str().unwrap();
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(text).unwrap(),
+        json!([])
+    );
+
+    let body = call_as(
+        &server,
+        "prod_token",
+        "resources/read",
+        json!({"uri": "odoo://default/models"}),
+    )
+    .await;
+    let message = body["error"]["message"].as_str().unwrap();
+    assert!(message.contains("not permitted"), "{message}");
+}
+
+#[tokio::test]
+async fn test_named_tokens_reject_expired_and_unknown() {
+    let (server, _temp) = setup_named_tokens_server().await;
+
+    for (token, description) in [("old_token", "expired"), ("test_token", "invalid")] {
+        let response = server
+            .post("/mcp")
+            .add_header(
+                HeaderName::from_static(AUTH_HEADER),
+                HeaderValue::from_str(&format!("Bearer {token}")).unwrap(),
+            )
+            .json(&json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}}))
+            .await;
+        response.assert_status_unauthorized();
+        let body: serde_json::Value = response.json();
+        assert!(
+            body["error_description"]
+                .as_str()
+                .unwrap()
+                .contains(description)
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_sessions_not_shared_between_principals() {
+    let (server, _temp) = setup_named_tokens_server().await;
+    let bearer = |token: &str| HeaderValue::from_str(&format!("Bearer {token}")).unwrap();
+
+    let init = server
+        .post("/mcp")
+        .add_header(HeaderName::from_static(AUTH_HEADER), bearer("reader_token"))
+        .json(&json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}}))
+        .await;
+    let session_id = init.headers()[MCP_SESSION_HEADER].clone();
+    let ping = |token: &str| {
+        server
+            .post("/mcp")
+            .add_header(HeaderName::from_static(AUTH_HEADER), bearer(token))
+            .add_header(
+                HeaderName::from_static(MCP_SESSION_HEADER),
+                session_id.clone(),
+            )
+            .json(&json!({"jsonrpc": "2.0", "id": 2, "method": "ping"}))
+    };
+
+    // Another principal cannot use the session, nor its Odoo credentials.
+    ping("writer_token").await.assert_status_not_found();
+    ping("reader_token").await.assert_status_ok();
+}
+
+#[tokio::test]
+async fn test_sse_stream_and_delete_need_own_session() {
+    let (server, _temp) = setup_named_tokens_server().await;
+    let bearer = |token: &str| HeaderValue::from_str(&format!("Bearer {token}")).unwrap();
+
+    let init = server
+        .post("/mcp")
+        .add_header(HeaderName::from_static(AUTH_HEADER), bearer("reader_token"))
+        .json(&json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}}))
+        .await;
+    let session_id = init.headers()[MCP_SESSION_HEADER].clone();
+    let session = HeaderName::from_static(MCP_SESSION_HEADER);
+
+    let stream = server
+        .get("/mcp")
+        .add_header(HeaderName::from_static(AUTH_HEADER), bearer("writer_token"))
+        .add_header(session.clone(), session_id.clone())
+        .add_header(
+            HeaderName::from_static("last-event-id"),
+            HeaderValue::from_static("0"),
+        )
+        .await;
+    stream.assert_status_not_found();
+    // No shared fallback stream, nor streams for unknown sessions.
+    server
+        .get("/mcp")
+        .add_header(HeaderName::from_static(AUTH_HEADER), bearer("reader_token"))
+        .await
+        .assert_status_bad_request();
+    server
+        .get("/mcp")
+        .add_header(HeaderName::from_static(AUTH_HEADER), bearer("reader_token"))
+        .add_header(session.clone(), HeaderValue::from_static("unknown"))
+        .await
+        .assert_status_not_found();
+
+    let delete = |token: &str| {
+        server
+            .delete("/mcp")
+            .add_header(HeaderName::from_static(AUTH_HEADER), bearer(token))
+            .add_header(session.clone(), session_id.clone())
+    };
+    delete("writer_token").await.assert_status_not_found();
+    delete("reader_token").await.assert_status_ok();
+    delete("reader_token").await.assert_status_not_found();
+}
+
+// ============================================================================
+// Health Endpoint Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_health_reports_unknown_without_probing() {
+    let (server, _temp) = setup_test_server(false).await;
+
+    let response = server.get("/health").await;
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+
+    // Nothing has been sent to Odoo yet: neither reachable nor unreachable.
+    assert_eq!(body["status"], "unknown");
+    let instance = &body["instances"]["default"];
+    assert!(instance["reachable"].is_null());
+    assert_eq!(instance["state"], "unknown");
+    assert!(instance["server_version"].is_null());
+}
+
+#[tokio::test]
+async fn test_sessions_expire_and_are_capped_per_principal() {
+    let security = SecurityConfig {
+        sessions: SessionLimits {
+            idle_ttl: Some(Duration::from_secs(1)),
+            max_age: None,
+            max_per_principal: Some(2),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let (server, _temp) = setup_test_server_with_security(AuthConfig::disabled(), security).await;
+
+    let mut sessions = Vec::new();
+    for id in 1..=3 {
+        let response = server
+            .post("/mcp")
+            .json(&json!({"jsonrpc": "2.0", "id": id, "method": "initialize", "params": {}}))
+            .await;
+        sessions.push(response.headers()[MCP_SESSION_HEADER].clone());
+    }
+    let ping = |session: &HeaderValue| {
+        server
+            .post("/mcp")
+            .add_header(HeaderName::from_static(MCP_SESSION_HEADER), session.clone())
+            .json(&json!({"jsonrpc": "2.0", "id": 9, "method": "ping"}))
+    };
+
+    // The third session closed the least recently used one.
+    ping(&sessions[0]).await.assert_status_not_found();
+    ping(&sessions[2]).await.assert_status_ok();
+    let health: serde_json::Value = server.get("/health").await.json();
+    assert_eq!(health["sessions"]["active"], 2);
+    assert_eq!(health["sessions"]["evicted"], 1);
+
+    // Idle sessions are refused, then swept.
+    tokio::time::sleep(Duration::from_millis(2500)).await;
+    ping(&sessions[2]).await.assert_status_not_found();
+    let health: serde_json::Value = server.get("/health").await.json();
+    assert_eq!(health["sessions"]["active"], 0);
+    assert_eq!(health["sessions"]["expired"], 2);
+}
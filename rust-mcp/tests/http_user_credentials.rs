@@ -0,0 +1,144 @@
+//! Integration test for per-user Odoo credentials on the HTTP transport: the
+//! client's API key reaches Odoo instead of the service user's.
+
+mod common;
+
+use axum::http::{HeaderName, HeaderValue};
+use axum_test::TestServer;
+use common::{minimal_prompts_json, minimal_server_json, minimal_tools_json};
+use rust_mcp::mcp::McpOdooHandler;
+use rust_mcp::mcp::http::{
+    AuthConfig, SecurityConfig, UserCredentialsMode, create_app_with_security,
+};
+use rust_mcp::mcp::registry::Registry;
+use rust_mcp::mcp::tools::OdooClientPool;
+use serde_json::{Value, json};
+use std::sync::Arc;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn server(handler: Arc<McpOdooHandler>, mode: UserCredentialsMode) -> TestServer {
+    let security = SecurityConfig {
+        user_credentials: mode,
+        ..Default::default()
+    };
+    let app = create_app_with_security(handler, AuthConfig::disabled(), security);
+    TestServer::new(app.into_make_service()).unwrap()
+}
+
+async fn initialize(
+    server: &TestServer,
+    headers: &[(&'static str, &str)],
+    params: Value,
+) -> axum_test::TestResponse {
+    let mut request = server.post("/mcp");
+    for (name, value) in headers {
+        request = request.add_header(
+            HeaderName::from_static(name),
+            HeaderValue::from_str(value).unwrap(),
+        );
+    }
+    request
+        .json(&json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": params}))
+        .await
+}
+
+/// Initialize, confirm, and search partners in the new session.
+async fn search_as(server: &TestServer, headers: &[(&'static str, &str)], params: Value) -> Value {
+    let init = initialize(server, headers, params).await;
+    init.assert_status_ok();
+    let session = init.headers()["mcp-session-id"].clone();
+    server
+        .post("/mcp")
+        .add_header(HeaderName::from_static("mcp-session-id"), session.clone())
+        .json(&json!({"jsonrpc": "2.0", "method": "initialized"}))
+        .await;
+    server
+        .post("/mcp")
+        .add_header(HeaderName::from_static("mcp-session-id"), session)
+        .json(
+            &json!({"jsonrpc": "2.0", "id": 2, "method": "tools/call", "params": {
+                "name": "odoo_search",
+                "arguments": {"instance": "default", "model": "res.partner"}
+            }}),
+        )
+        .await
+        .json()
+}
+
+/// Record ids of an `odoo_search` result.
+fn result_ids(body: &Value) -> Value {
+    let text = body["result"]["content"][0]["text"]
+        .as_str()
+        .unwrap_or("{}");
+    serde_json::from_str::<Value>(text).unwrap()["ids"].clone()
+}
+
+#[tokio::test]
+async fn test_sessions_call_odoo_with_client_credentials() {
+    let odoo = MockServer::start().await;
+    for (key, ids) in [("service_key", json!([1])), ("alice_key", json!([7]))] {
+        Mock::given(method("POST"))
+            .and(path("/json/2/res.partner/search"))
+            .and(header("authorization", format!("bearer {key}").as_str()))
+            .respond_with(ResponseTemplate::new(200).set_body_json(ids))
+            .mount(&odoo)
+            .await;
+    }
+
+    let temp = tempfile::TempDir::new().unwrap();
+    std::fs::write(temp.path().join("tools.json"), minimal_tools_json()).unwrap();
+    std::fs::write(temp.path().join("prompts.json"), minimal_prompts_json()).unwrap();
+    std::fs::write(temp.path().join("server.json"), minimal_server_json()).unwrap();
+    unsafe {
+        std::env::set_var(
+            "ODOO_INSTANCES",
+            json!({"default": {"url": odoo.uri(), "apiKey": "service_key", "version": "19"}})
+                .to_string(),
+        );
+        std::env::set_var("ODOO_VERSION_DETECTION", "false");
+        std::env::set_var("MCP_TOOLS_JSON", temp.path().join("tools.json"));
+        std::env::set_var("MCP_PROMPTS_JSON", temp.path().join("prompts.json"));
+        std::env::set_var("MCP_SERVER_JSON", temp.path().join("server.json"));
+    }
+
+    let pool = OdooClientPool::from_env().unwrap();
+    let registry = Arc::new(Registry::from_env());
+    registry.initial_load().await.unwrap();
+    let handler = Arc::new(McpOdooHandler::new(pool.clone(), registry));
+
+    // Optional: the service user without client credentials, the client's own
+    // key from a header or from the initialize params otherwise.
+    let optional = server(handler.clone(), UserCredentialsMode::Optional);
+    let body = search_as(&optional, &[], json!({})).await;
+    assert_eq!(result_ids(&body), json!([1]), "{body}");
+    let body = search_as(&optional, &[("x-odoo-api-key", "alice_key")], json!({})).await;
+    assert_eq!(result_ids(&body), json!([7]), "{body}");
+    let body = search_as(
+        &optional,
+        &[],
+        json!({"odooCredentials": {"apiKey": "alice_key"}}),
+    )
+    .await;
+    assert_eq!(result_ids(&body), json!([7]), "{body}");
+    // Both sessions of the same user share one client.
+    assert_eq!(pool.user_client_count().await, 1);
+
+    // Incomplete credentials are rejected up front.
+    initialize(&optional, &[("x-odoo-login", "alice")], json!({}))
+        .await
+        .assert_status_bad_request();
+
+    // Required: sessions without credentials are refused.
+    let required = server(handler.clone(), UserCredentialsMode::Required);
+    let response = initialize(&required, &[], json!({})).await;
+    response.assert_status_bad_request();
+    assert!(response.text().contains("credentials required"));
+    let body = search_as(&required, &[("x-odoo-api-key", "alice_key")], json!({})).await;
+    assert_eq!(result_ids(&body), json!([7]), "{body}");
+
+    // Off: client credentials are ignored.
+    let off = server(handler, UserCredentialsMode::Off);
+    let body = search_as(&off, &[("x-odoo-api-key", "alice_key")], json!({})).await;
+    assert_eq!(result_ids(&body), json!([1]), "{body}");
+}
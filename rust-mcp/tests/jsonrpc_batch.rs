@@ -0,0 +1,138 @@
+//! Integration tests for JSON-RPC batches on the HTTP and WebSocket transports.
+
+mod common;
+
+use std::sync::Arc;
+
+use axum::http::{HeaderName, HeaderValue};
+use axum_test::TestServer;
+use common::{minimal_prompts_json, minimal_server_json, minimal_tools_json};
+use futures::{SinkExt, StreamExt};
+use rust_mcp::mcp::McpOdooHandler;
+use rust_mcp::mcp::http::{AuthConfig, create_app};
+use rust_mcp::mcp::registry::Registry;
+use rust_mcp::mcp::runtime::ServerCompat;
+use rust_mcp::mcp::tools::OdooClientPool;
+use rust_mcp::mcp::ws::WsTransport;
+use serde_json::{Value, json};
+use tempfile::TempDir;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+async fn handler(temp: &TempDir) -> Arc<McpOdooHandler> {
+    std::fs::write(temp.path().join("tools.json"), minimal_tools_json()).unwrap();
+    std::fs::write(temp.path().join("prompts.json"), minimal_prompts_json()).unwrap();
+    std::fs::write(temp.path().join("server.json"), minimal_server_json()).unwrap();
+    unsafe {
+        std::env::set_var("ODOO_URL", "http://localhost:8069");
+        std::env::set_var("ODOO_API_KEY", "test_key");
+        std::env::set_var("MCP_TOOLS_JSON", temp.path().join("tools.json"));
+        std::env::set_var("MCP_PROMPTS_JSON", temp.path().join("prompts.json"));
+        std::env::set_var("MCP_SERVER_JSON", temp.path().join("server.json"));
+    }
+    let registry = Arc::new(Registry::from_env());
+    registry.initial_load().await.unwrap();
+    Arc::new(McpOdooHandler::new(
+        OdooClientPool::from_env().unwrap(),
+        registry,
+    ))
+}
+
+fn batch() -> Value {
+    json!([
+        {"jsonrpc": "2.0", "id": 2, "method": "tools/list", "params": {}},
+        {"jsonrpc": "2.0", "method": "notifications/cancelled", "params": {}},
+        {"jsonrpc": "2.0", "id": "three", "method": "ping"},
+        {"jsonrpc": "2.0", "id": 4}
+    ])
+}
+
+/// Responses of `batch()` keyed by id.
+fn by_id(responses: &Value) -> std::collections::HashMap<String, Value> {
+    let responses = responses.as_array().expect("batch answered with an array");
+    assert_eq!(responses.len(), 3, "{responses:?}");
+    responses
+        .iter()
+        .map(|r| (r["id"].to_string(), r.clone()))
+        .collect()
+}
+
+#[tokio::test]
+async fn test_http_batch() {
+    let temp = TempDir::new().unwrap();
+    let app = create_app(handler(&temp).await, AuthConfig::disabled());
+    let server = TestServer::new(app.into_make_service()).unwrap();
+
+    let init = server
+        .post("/mcp")
+        .json(&json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}}))
+        .await;
+    let session =
+        HeaderValue::from_str(init.headers()["mcp-session-id"].to_str().unwrap()).unwrap();
+    let post = |body: Value| {
+        server
+            .post("/mcp")
+            .add_header(HeaderName::from_static("mcp-session-id"), session.clone())
+            .json(&body)
+    };
+    post(json!({"jsonrpc": "2.0", "method": "initialized"})).await;
+
+    let response = post(batch()).await;
+    response.assert_status_ok();
+    let responses = by_id(&response.json());
+    assert_eq!(responses["2"]["result"]["tools"][0]["name"], "odoo_search");
+    assert_eq!(responses["\"three\""]["result"], json!({}));
+    assert!(responses["4"]["error"].is_object());
+
+    post(json!([{"jsonrpc": "2.0", "method": "notifications/cancelled"}]))
+        .await
+        .assert_status(axum::http::StatusCode::ACCEPTED);
+
+    let response = post(json!([])).await;
+    response.assert_status_bad_request();
+    assert_eq!(response.json::<Value>()["error"]["code"], -32600);
+
+    let response = post(json!([
+        {"jsonrpc": "2.0", "id": 5, "method": "initialize", "params": {}}
+    ]))
+    .await;
+    assert_eq!(response.json::<Value>()[0]["error"]["code"], -32600);
+}
+
+#[tokio::test]
+async fn test_websocket_batch() {
+    let temp = TempDir::new().unwrap();
+    let handler = handler(&temp).await;
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+        let server = ServerCompat::new(Arc::new(WsTransport::from_stream(ws)), handler);
+        server.start().await.unwrap();
+    });
+
+    let (mut ws, _) = tokio_tungstenite::connect_async(format!("ws://{addr}"))
+        .await
+        .unwrap();
+    let mut send = async |body: Value| {
+        ws.send(WsMessage::Text(body.to_string())).await.unwrap();
+    };
+    send(json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}})).await;
+    send(json!({"jsonrpc": "2.0", "method": "initialized"})).await;
+    send(batch()).await;
+
+    let mut replies = Vec::new();
+    while replies.len() < 2 {
+        let Some(Ok(WsMessage::Text(text))) = ws.next().await else {
+            panic!("connection closed");
+        };
+        replies.push(serde_json::from_str::<Value>(&text).unwrap());
+    }
+    // Single messages keep the SDK's tagged format.
+    assert_eq!(replies[0]["type"], "response");
+    assert_eq!(replies[0]["id"], 1);
+    let responses = by_id(&replies[1]);
+    assert_eq!(responses["2"]["result"]["tools"][0]["name"], "odoo_search");
+    assert_eq!(responses["\"three\""]["result"], json!({}));
+    assert_eq!(responses["4"]["error"]["code"], -32600);
+}
@@ -0,0 +1,161 @@
+//! Integration tests for the response size budget applied to tool results.
+
+mod common;
+
+use std::sync::Arc;
+
+use base64::Engine;
+use common::{MockOdooServer, minimal_prompts_json, minimal_server_json};
+use mcp_rust_sdk::server::ServerHandler;
+use rust_mcp::mcp::McpOdooHandler;
+use rust_mcp::mcp::registry::Registry;
+use rust_mcp::mcp::tools::OdooClientPool;
+use serde_json::{Value, json};
+
+fn budget_tools_json() -> String {
+    json!({
+        "tools": [
+            {
+                "name": "odoo_generate_report",
+                "description": "Generate PDF report",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "instance": {"type": "string"},
+                        "reportName": {"type": "string"},
+                        "ids": {"type": "array", "items": {"type": "integer"}}
+                    },
+                    "required": ["instance", "reportName", "ids"]
+                },
+                "op": {
+                    "type": "generate_report",
+                    "map": {"instance": "/instance", "reportName": "/reportName", "ids": "/ids"}
+                }
+            },
+            {
+                "name": "odoo_search_read",
+                "description": "Search and read records",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "instance": {"type": "string"},
+                        "model": {"type": "string"},
+                        "fields": {"type": "array", "items": {"type": "string"}},
+                        "format": {"type": "string"}
+                    },
+                    "required": ["instance", "model"]
+                },
+                "op": {
+                    "type": "search_read",
+                    "map": {
+                        "instance": "/instance",
+                        "model": "/model",
+                        "fields": "/fields",
+                        "format": "/format"
+                    }
+                }
+            }
+        ]
+    })
+    .to_string()
+}
+
+async fn handler(odoo: &MockOdooServer, temp: &tempfile::TempDir) -> McpOdooHandler {
+    std::fs::write(temp.path().join("tools.json"), budget_tools_json()).unwrap();
+    std::fs::write(temp.path().join("prompts.json"), minimal_prompts_json()).unwrap();
+    std::fs::write(temp.path().join("server.json"), minimal_server_json()).unwrap();
+    unsafe {
+        std::env::set_var("ODOO_URL", odoo.uri());
+        std::env::set_var("ODOO_DB", "test_db");
+        std::env::set_var("ODOO_API_KEY", "test_key");
+        std::env::set_var("ODOO_VERSION_DETECTION", "false");
+        std::env::set_var("ODOO_RESPONSE_MAX_CHARS", "20000");
+        std::env::set_var("MCP_TOOLS_JSON", temp.path().join("tools.json"));
+        std::env::set_var("MCP_PROMPTS_JSON", temp.path().join("prompts.json"));
+        std::env::set_var("MCP_SERVER_JSON", temp.path().join("server.json"));
+    }
+    let registry = Arc::new(Registry::from_env());
+    registry.initial_load().await.unwrap();
+    McpOdooHandler::new(OdooClientPool::from_env().unwrap(), registry)
+}
+
+fn payload(result: &Value) -> Value {
+    serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap()
+}
+
+#[tokio::test]
+async fn test_large_report_returned_whole() {
+    let odoo = MockOdooServer::start().await;
+    // ~200 KB of PDF, far above the 20,000 character budget once encoded.
+    let pdf: Vec<u8> = (0..200_000).map(|i| (i % 251) as u8).collect();
+    odoo.mock_report_pdf(pdf.clone()).await;
+    let temp = tempfile::TempDir::new().unwrap();
+    let handler = handler(&odoo, &temp).await;
+
+    let result = handler
+        .handle_method(
+            "tools/call",
+            Some(json!({
+                "name": "odoo_generate_report",
+                "arguments": {
+                    "instance": "default",
+                    "reportName": "sale.report_saleorder",
+                    "ids": (1..=300).collect::<Vec<i64>>()
+                }
+            })),
+        )
+        .await
+        .unwrap();
+    assert_ne!(result["isError"], true, "{result}");
+    let payload = payload(&result);
+    assert!(payload.get("truncated").is_none());
+    assert_eq!(payload["record_ids"].as_array().unwrap().len(), 300);
+    let decoded = base64::engine::general_purpose::STANDARD
+        .decode(payload["pdf_base64"].as_str().unwrap())
+        .unwrap();
+    assert_eq!(decoded, pdf);
+}
+
+#[tokio::test]
+async fn test_budget_measured_in_requested_format() {
+    let odoo = MockOdooServer::start().await;
+    // Pretty JSON of these rows is well over 20,000 characters; CSV is not.
+    let rows: Vec<Value> = (1..=400)
+        .map(|i| json!({"id": i, "name": format!("Partner {i}"), "ref": format!("P{i:04}")}))
+        .collect();
+    odoo.mock_search_read("res.partner", json!(rows)).await;
+    odoo.mock_fields_get(
+        "res.partner",
+        json!({"name": {"type": "char"}, "ref": {"type": "char"}}),
+    )
+    .await;
+    let temp = tempfile::TempDir::new().unwrap();
+    let handler = handler(&odoo, &temp).await;
+
+    let call = |format: &str| {
+        json!({
+            "name": "odoo_search_read",
+            "arguments": {
+                "instance": "default",
+                "model": "res.partner",
+                "fields": ["name", "ref"],
+                "format": format
+            }
+        })
+    };
+    let csv = handler
+        .handle_method("tools/call", Some(call("csv")))
+        .await
+        .unwrap();
+    let text = csv["content"][0]["text"].as_str().unwrap();
+    assert!(!text.contains("truncated"), "{text}");
+    assert_eq!(text.lines().filter(|l| l.contains("Partner")).count(), 400);
+
+    let pretty = handler
+        .handle_method("tools/call", Some(call("json")))
+        .await
+        .unwrap();
+    let payload = payload(&pretty);
+    assert!(payload["records"].as_array().unwrap().len() < 400);
+    assert!(payload.get("truncated").is_some());
+}
@@ -0,0 +1,138 @@
+//! Integration tests for relational expansion with batched reads.
+
+mod common;
+
+use async_trait::async_trait;
+use common::MockOdooServer;
+use rust_mcp::mcp::domain::FieldsSource;
+use rust_mcp::mcp::expand::ExpandPlan;
+use rust_mcp::odoo::config::OdooInstanceConfig;
+use rust_mcp::odoo::unified_client::OdooClient;
+use serde_json::{Map, Value, json};
+use std::collections::HashMap;
+
+struct StaticFields(HashMap<String, Map<String, Value>>);
+
+#[async_trait]
+impl FieldsSource for StaticFields {
+    async fn fields(&self, model: &str) -> Option<Map<String, Value>> {
+        self.0.get(model).cloned()
+    }
+}
+
+fn source() -> StaticFields {
+    let mut models = HashMap::new();
+    let order = json!({
+        "name": {"type": "char"},
+        "partner_id": {"type": "many2one", "relation": "res.partner"},
+        "tag_ids": {"type": "many2many", "relation": "crm.tag"}
+    });
+    let partner = json!({
+        "email": {"type": "char"},
+        "country_id": {"type": "many2one", "relation": "res.country"}
+    });
+    let tag = json!({ "name": {"type": "char"} });
+    models.insert("sale.order".to_string(), order.as_object().unwrap().clone());
+    models.insert(
+        "res.partner".to_string(),
+        partner.as_object().unwrap().clone(),
+    );
+    models.insert("crm.tag".to_string(), tag.as_object().unwrap().clone());
+    StaticFields(models)
+}
+
+fn create_client(url: &str) -> OdooClient {
+    OdooClient::new(&OdooInstanceConfig {
+        url: url.to_string(),
+        db: Some("test_db".to_string()),
+        api_key: Some("test_api_key".to_string()),
+        username: None,
+        password: None,
+        version: Some("19".to_string()),
+        timeout_ms: Some(5000),
+        max_retries: Some(0),
+        protocol: None,
+        transport: Default::default(),
+        extra: HashMap::new(),
+    })
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_expand_records_many2one_and_many2many() {
+    let mock = MockOdooServer::start().await;
+    mock.mock_read(
+        "res.partner",
+        json!([
+            {"id": 7, "display_name": "Azure Interior", "email": "azure@example.com",
+             "country_id": [21, "Belgium"]}
+        ]),
+    )
+    .await;
+    mock.mock_read(
+        "crm.tag",
+        json!([
+            {"id": 1, "display_name": "VIP", "name": "VIP"},
+            {"id": 2, "display_name": "New", "name": "New"}
+        ]),
+    )
+    .await;
+
+    let fields = vec!["name".to_string()];
+    let plan = ExpandPlan::build(
+        &json!(["partner_id.email", "partner_id.country_id", "tag_ids.name"]),
+        "sale.order",
+        Some(&fields),
+        &source(),
+        3,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        plan.read_fields(),
+        Some(vec![
+            "name".to_string(),
+            "partner_id".to_string(),
+            "tag_ids".to_string()
+        ])
+    );
+
+    let mut records = json!([
+        {"id": 1, "name": "S00001", "partner_id": [7, "Azure Interior"], "tag_ids": [2, 1]},
+        {"id": 2, "name": "S00002", "partner_id": false, "tag_ids": []}
+    ]);
+    let client = create_client(&mock.uri());
+    plan.expand_records(&client, &mut records, None)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        records[0]["partner_id"],
+        json!({"id": 7, "display_name": "Azure Interior", "email": "azure@example.com",
+               "country_id": [21, "Belgium"]})
+    );
+    assert_eq!(records[0]["tag_ids"][0]["name"], "New");
+    assert_eq!(records[0]["tag_ids"][1]["name"], "VIP");
+    assert_eq!(records[1]["partner_id"], json!(false));
+    assert_eq!(records[1]["tag_ids"], json!([]));
+}
+
+#[tokio::test]
+async fn test_expand_records_keeps_unreadable_many2one() {
+    let mock = MockOdooServer::start().await;
+    mock.mock_read("res.partner", json!([])).await;
+
+    let plan = ExpandPlan::build(&json!("partner_id"), "sale.order", None, &source(), 3)
+        .await
+        .unwrap();
+    let mut records = json!([{"id": 1, "partner_id": [7, "Azure Interior"]}]);
+    let client = create_client(&mock.uri());
+    plan.expand_records(&client, &mut records, None)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        records[0]["partner_id"],
+        json!({"id": 7, "display_name": "Azure Interior"})
+    );
+}
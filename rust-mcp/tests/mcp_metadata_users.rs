@@ -0,0 +1,128 @@
+//! Integration test for model metadata with per-user credentials: Odoo filters
+//! `fields_get` by the caller's groups, so each user gets their own cache.
+
+mod common;
+
+use std::sync::Arc;
+
+use common::{minimal_prompts_json, minimal_server_json};
+use rust_mcp::mcp::McpOdooHandler;
+use rust_mcp::mcp::registry::Registry;
+use rust_mcp::mcp::tools::OdooClientPool;
+use rust_mcp::odoo::config::UserCredentials;
+use serde_json::{Value, json};
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn metadata_tools_json() -> String {
+    json!({
+        "tools": [{
+            "name": "odoo_get_model_metadata",
+            "description": "Get model metadata",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "instance": {"type": "string"},
+                    "model": {"type": "string"}
+                },
+                "required": ["instance", "model"]
+            },
+            "op": {
+                "type": "get_model_metadata",
+                "map": {"instance": "/instance", "model": "/model"}
+            }
+        }]
+    })
+    .to_string()
+}
+
+/// Field names of an `odoo_get_model_metadata` result.
+async fn fields_seen(
+    handler: &McpOdooHandler,
+    credentials: Option<&UserCredentials>,
+) -> Vec<String> {
+    let result = handler
+        .handle_method_as(
+            None,
+            credentials,
+            "tools/call",
+            Some(json!({
+                "name": "odoo_get_model_metadata",
+                "arguments": {"instance": "default", "model": "res.partner"}
+            })),
+        )
+        .await
+        .unwrap();
+    let text = result["content"][0]["text"].as_str().unwrap();
+    let metadata: Value = serde_json::from_str(text).unwrap();
+    let mut names: Vec<String> = metadata["model"]["fields"]
+        .as_object()
+        .unwrap_or_else(|| panic!("{result}"))
+        .keys()
+        .cloned()
+        .collect();
+    names.sort();
+    names
+}
+
+#[tokio::test]
+async fn test_users_do_not_share_model_metadata() {
+    let odoo = MockServer::start().await;
+    let fields = [
+        (
+            "service_key",
+            json!({"name": {"type": "char"}, "credit_limit": {"type": "float"}}),
+        ),
+        ("alice_key", json!({"name": {"type": "char"}})),
+    ];
+    for (key, fields) in fields {
+        Mock::given(method("POST"))
+            .and(path("/json/2/res.partner/fields_get"))
+            .and(header("authorization", format!("bearer {key}").as_str()))
+            .respond_with(ResponseTemplate::new(200).set_body_json(fields))
+            // Each user's metadata is fetched once, then cached.
+            .expect(1)
+            .mount(&odoo)
+            .await;
+    }
+    Mock::given(method("POST"))
+        .and(path("/json/2/ir.model/search_read"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([{"name": "Contact"}])))
+        .mount(&odoo)
+        .await;
+
+    let temp = tempfile::TempDir::new().unwrap();
+    std::fs::write(temp.path().join("tools.json"), metadata_tools_json()).unwrap();
+    std::fs::write(temp.path().join("prompts.json"), minimal_prompts_json()).unwrap();
+    std::fs::write(temp.path().join("server.json"), minimal_server_json()).unwrap();
+    unsafe {
+        std::env::set_var(
+            "ODOO_INSTANCES",
+            json!({"default": {"url": odoo.uri(), "apiKey": "service_key", "version": "19"}})
+                .to_string(),
+        );
+        std::env::set_var("ODOO_VERSION_DETECTION", "false");
+        std::env::set_var("MCP_TOOLS_JSON", temp.path().join("tools.json"));
+        std::env::set_var("MCP_PROMPTS_JSON", temp.path().join("prompts.json"));
+        std::env::set_var("MCP_SERVER_JSON", temp.path().join("server.json"));
+    }
+    let registry = Arc::new(Registry::from_env());
+    registry.initial_load().await.unwrap();
+    let handler = McpOdooHandler::new(OdooClientPool::from_env().unwrap(), registry);
+    let alice = UserCredentials::from_parts(Some("alice_key".to_string()), None, None)
+        .unwrap()
+        .unwrap();
+
+    // The restricted user goes first: the service user must not get their view.
+    assert_eq!(fields_seen(&handler, Some(&alice)).await, vec!["name"]);
+    assert_eq!(
+        fields_seen(&handler, None).await,
+        vec!["credit_limit", "name"]
+    );
+    // And the other way round, now both are cached.
+    assert_eq!(fields_seen(&handler, Some(&alice)).await, vec!["name"]);
+    assert_eq!(
+        fields_seen(&handler, None).await,
+        vec!["credit_limit", "name"]
+    );
+}
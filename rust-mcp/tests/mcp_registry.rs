@@ -21,6 +21,7 @@ mod tests {
                 }
             }),
             guards: None,
+            response_budget: None,
         };
 
         assert_eq!(tool.name, "search_partners");
@@ -93,6 +94,7 @@ mod tests {
             guards: Some(ToolGuards {
                 requires_env_true: Some("ADMIN_MODE".to_string()),
             }),
+            response_budget: None,
         };
 
         assert_eq!(tool.name, "admin_tool");
@@ -0,0 +1,93 @@
+//! Integration tests for the per-instance circuit breaker.
+
+use rust_mcp::odoo::breaker::{BreakerState, CircuitBreaker};
+use rust_mcp::odoo::config::OdooInstanceConfig;
+use rust_mcp::odoo::types::OdooError;
+use rust_mcp::odoo::unified_client::OdooClient;
+use serde_json::json;
+use std::collections::HashMap;
+use std::time::Duration;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn create_client(url: &str, breaker: CircuitBreaker) -> OdooClient {
+    OdooClient::new(&OdooInstanceConfig {
+        url: url.to_string(),
+        db: Some("test_db".to_string()),
+        api_key: Some("test_api_key".to_string()),
+        username: None,
+        password: None,
+        version: Some("19".to_string()),
+        timeout_ms: Some(5000),
+        max_retries: Some(0),
+        protocol: None,
+        transport: Default::default(),
+        extra: HashMap::new(),
+    })
+    .unwrap()
+    .with_instance_name("prod")
+    .with_circuit_breaker(breaker)
+}
+
+#[tokio::test]
+async fn test_breaker_opens_and_fails_fast() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/json/2/res.partner/search_count"))
+        .respond_with(ResponseTemplate::new(503).set_body_string("Service Unavailable"))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let client = create_client(
+        &server.uri(),
+        CircuitBreaker::new(2, Duration::from_secs(60)),
+    );
+    for _ in 0..2 {
+        let err = client.search_count("res.partner", None, None).await;
+        assert!(matches!(err, Err(OdooError::Api { status: 503, .. })));
+    }
+
+    // Third call never reaches the server.
+    let err = client
+        .search_count("res.partner", None, None)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, OdooError::Unavailable(_)));
+    assert_eq!(err.category(), "instance_unavailable");
+    assert!(err.to_string().contains("'prod'"));
+
+    let snapshot = client.breaker().snapshot();
+    assert_eq!(snapshot.state, BreakerState::Open);
+    assert_eq!(snapshot.consecutive_failures, 2);
+    assert!(snapshot.last_error.unwrap().contains("503"));
+}
+
+#[tokio::test]
+async fn test_business_errors_do_not_open_breaker() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/json/2/res.partner/search_count"))
+        .respond_with(ResponseTemplate::new(403).set_body_json(json!({
+            "name": "odoo.exceptions.AccessError",
+            "message": "You are not allowed to access 'Contact' (res.partner) records.",
+        })))
+        .mount(&server)
+        .await;
+
+    let client = create_client(
+        &server.uri(),
+        CircuitBreaker::new(1, Duration::from_secs(60)),
+    );
+    for _ in 0..3 {
+        let err = client
+            .search_count("res.partner", None, None)
+            .await
+            .unwrap_err();
+        assert!(!matches!(err, OdooError::Unavailable(_)));
+    }
+
+    let snapshot = client.breaker().snapshot();
+    assert_eq!(snapshot.state, BreakerState::Closed);
+    assert!(snapshot.last_success.is_some());
+}
@@ -4,10 +4,11 @@ mod common;
 
 use rust_mcp::odoo::config::OdooInstanceConfig;
 use rust_mcp::odoo::legacy_client::OdooLegacyClient;
+use rust_mcp::odoo::types::OdooErrorKind;
 use serde_json::json;
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicUsize, Ordering};
-use wiremock::matchers::{method, path};
+use wiremock::matchers::{header, method, path};
 use wiremock::{Mock, MockServer, Respond, ResponseTemplate};
 
 fn create_legacy_config(url: &str) -> OdooInstanceConfig {
@@ -20,6 +21,8 @@ fn create_legacy_config(url: &str) -> OdooInstanceConfig {
         version: Some("18".to_string()),
         timeout_ms: Some(5000),
         max_retries: Some(0), // No retries for faster tests
+        protocol: None,
+        transport: Default::default(),
         extra: HashMap::new(),
     }
 }
@@ -421,6 +424,75 @@ async fn test_legacy_rpc_error() {
     assert!(result.is_err());
 }
 
+#[tokio::test]
+async fn test_legacy_rpc_error_is_typed() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/jsonrpc"))
+        .respond_with(AuthThenDataResponder {
+            call_count: AtomicUsize::new(0),
+            auth_response: jsonrpc_success(json!(1)),
+            data_response: json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "error": {
+                    "code": 200,
+                    "message": "Odoo Server Error",
+                    "data": {
+                        "name": "odoo.exceptions.AccessError",
+                        "message": "You are not allowed to access 'Contact' (res.partner) records.",
+                        "arguments": [],
+                        "context": {},
+                        "debug": "Traceback (most recent call last): ..."
+                    }
+                }
+            }),
+        })
+        .mount(&server)
+        .await;
+
+    let config = create_legacy_config(&server.uri());
+    let client = OdooLegacyClient::new(&config).unwrap();
+
+    let err = client
+        .search("res.partner", None, None, None, None, None)
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.kind(), Some(OdooErrorKind::AccessError));
+    assert_eq!(err.category(), "access_error");
+    assert_eq!(
+        err.message(),
+        "You are not allowed to access 'Contact' (res.partner) records."
+    );
+    assert_eq!(
+        err.traceback(),
+        Some("Traceback (most recent call last): ...")
+    );
+}
+
+#[tokio::test]
+async fn test_legacy_auth_failure_is_access_denied() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/jsonrpc"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(jsonrpc_success(json!(false))))
+        .mount(&server)
+        .await;
+
+    let config = create_legacy_config(&server.uri());
+    let client = OdooLegacyClient::new(&config).unwrap();
+
+    let err = client
+        .search("res.partner", None, None, None, None, None)
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.kind(), Some(OdooErrorKind::AccessDenied));
+}
+
 #[tokio::test]
 async fn test_legacy_network_error() {
     // Use invalid URL to trigger network error
@@ -433,6 +505,8 @@ async fn test_legacy_network_error() {
         version: Some("18".to_string()),
         timeout_ms: Some(1000),
         max_retries: Some(0), // No retries to speed up test
+        protocol: None,
+        transport: Default::default(),
         extra: HashMap::new(),
     };
 
@@ -471,3 +545,245 @@ async fn test_legacy_uid_caching() {
 
     assert_eq!(result1, vec![1, 2]);
 }
+
+/// Responds with each template in turn, repeating the last one.
+struct Sequence {
+    calls: AtomicUsize,
+    responses: Vec<ResponseTemplate>,
+}
+
+impl Respond for Sequence {
+    fn respond(&self, _request: &wiremock::Request) -> ResponseTemplate {
+        let n = self.calls.fetch_add(1, Ordering::SeqCst);
+        self.responses[n.min(self.responses.len() - 1)].clone()
+    }
+}
+
+fn sequence(responses: Vec<ResponseTemplate>) -> Sequence {
+    Sequence {
+        calls: AtomicUsize::new(0),
+        responses,
+    }
+}
+
+/// `/web/session/authenticate` success that sets a session cookie.
+fn web_session_ok() -> ResponseTemplate {
+    ResponseTemplate::new(200)
+        .insert_header("set-cookie", "session_id=abc123; Path=/; HttpOnly")
+        .set_body_json(jsonrpc_success(json!({
+            "uid": 7,
+            "user_context": {"lang": "fr_FR", "tz": "Europe/Paris", "uid": 7},
+            "user_companies": {"current_company": 3, "allowed_companies": {}}
+        })))
+}
+
+#[tokio::test]
+async fn test_legacy_web_session_login_exposes_user() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/web/session/authenticate"))
+        .respond_with(web_session_ok())
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/jsonrpc"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(jsonrpc_success(json!([1]))))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let client = OdooLegacyClient::new(&create_legacy_config(&server.uri())).unwrap();
+
+    let session = client.session().await.unwrap();
+    assert_eq!(session.uid, 7);
+    assert_eq!(session.company_id, Some(3));
+    assert_eq!(session.lang.as_deref(), Some("fr_FR"));
+    assert!(session.web_session);
+
+    for _ in 0..2 {
+        client
+            .search("res.partner", None, None, None, None, None)
+            .await
+            .unwrap();
+    }
+
+    let requests = server.received_requests().await.unwrap();
+    let call: serde_json::Value = requests.last().unwrap().body_json().unwrap();
+    assert_eq!(call["params"]["args"][1], 7);
+}
+
+#[tokio::test]
+async fn test_legacy_api_key_falls_back_to_rpc_login() {
+    let server = MockServer::start().await;
+
+    // Odoo 14+ refuses API keys for web logins.
+    Mock::given(method("POST"))
+        .and(path("/web/session/authenticate"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(jsonrpc_error(200, "Access Denied")))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/jsonrpc"))
+        .respond_with(AuthThenDataResponder::new(json!([1, 2])))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let client = OdooLegacyClient::new(&create_legacy_config(&server.uri())).unwrap();
+    let ids = client
+        .search("res.partner", None, None, None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(ids, vec![1, 2]);
+    let session = client.session().await.unwrap();
+    assert_eq!(session.uid, 1);
+    assert!(!session.web_session);
+}
+
+#[tokio::test]
+async fn test_legacy_blocked_web_login_falls_back_to_rpc_login() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/web/session/authenticate"))
+        .respond_with(ResponseTemplate::new(403).set_body_string("Forbidden"))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/jsonrpc"))
+        .respond_with(AuthThenDataResponder::new(json!([3])))
+        .mount(&server)
+        .await;
+
+    let client = OdooLegacyClient::new(&create_legacy_config(&server.uri())).unwrap();
+    let ids = client
+        .search("res.partner", None, None, None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(ids, vec![3]);
+}
+
+#[tokio::test]
+async fn test_legacy_relogin_once_on_session_expired() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/web/session/authenticate"))
+        .respond_with(web_session_ok())
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/jsonrpc"))
+        .respond_with(sequence(vec![
+            ResponseTemplate::new(200).set_body_json(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "error": {
+                    "code": 100,
+                    "message": "Odoo Session Expired",
+                    "data": {"name": "odoo.http.SessionExpiredException", "message": "Session expired"}
+                }
+            })),
+            ResponseTemplate::new(200).set_body_json(jsonrpc_success(json!([4, 5]))),
+        ]))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let client = OdooLegacyClient::new(&create_legacy_config(&server.uri())).unwrap();
+    let ids = client
+        .search("res.partner", None, None, None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(ids, vec![4, 5]);
+}
+
+#[tokio::test]
+async fn test_legacy_relogin_gives_up_after_one_attempt() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/web/session/authenticate"))
+        .respond_with(web_session_ok())
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/jsonrpc"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(jsonrpc_error(200, "Access Denied")))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let client = OdooLegacyClient::new(&create_legacy_config(&server.uri())).unwrap();
+    let err = client
+        .search("res.partner", None, None, None, None, None)
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), Some(OdooErrorKind::AccessDenied));
+}
+
+#[tokio::test]
+async fn test_legacy_report_reuses_session_cookie() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/web/session/authenticate"))
+        .respond_with(web_session_ok())
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/report/pdf/sale.report_saleorder/1"))
+        .and(header("cookie", "session_id=abc123"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(b"%PDF-1.4".to_vec()))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let client = OdooLegacyClient::new(&create_legacy_config(&server.uri())).unwrap();
+    for _ in 0..2 {
+        let pdf = client
+            .download_report_pdf("sale.report_saleorder", &[1])
+            .await
+            .unwrap();
+        assert_eq!(pdf, b"%PDF-1.4");
+    }
+}
+
+#[tokio::test]
+async fn test_legacy_report_relogin_after_redirect_to_login() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/web/session/authenticate"))
+        .respond_with(web_session_ok())
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/report/pdf/sale.report_saleorder/1"))
+        .respond_with(sequence(vec![
+            ResponseTemplate::new(303).insert_header("location", "/web/login?redirect=%2Freport"),
+            ResponseTemplate::new(200).set_body_bytes(b"%PDF-1.4".to_vec()),
+        ]))
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/web/login"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("<html>login</html>"))
+        .mount(&server)
+        .await;
+
+    let client = OdooLegacyClient::new(&create_legacy_config(&server.uri())).unwrap();
+    let pdf = client
+        .download_report_pdf("sale.report_saleorder", &[1])
+        .await
+        .unwrap();
+    assert_eq!(pdf, b"%PDF-1.4");
+}
@@ -5,6 +5,7 @@ mod common;
 use common::{MockOdooServer, responses};
 use rust_mcp::odoo::client::OdooHttpClient;
 use rust_mcp::odoo::config::OdooInstanceConfig;
+use rust_mcp::odoo::types::OdooErrorKind;
 use serde_json::json;
 use std::collections::HashMap;
 use wiremock::matchers::{header, method, path_regex};
@@ -20,6 +21,8 @@ fn create_config(url: &str) -> OdooInstanceConfig {
         version: Some("19".to_string()),
         timeout_ms: Some(5000),
         max_retries: Some(3),
+        protocol: None,
+        transport: Default::default(),
         extra: HashMap::new(),
     }
 }
@@ -369,6 +372,62 @@ async fn test_api_error_500_server_error() {
     assert!(result.is_err());
 }
 
+#[tokio::test]
+async fn test_api_error_body_is_typed() {
+    let mock = MockOdooServer::start().await;
+    Mock::given(method("POST"))
+        .and(path_regex(r"/json/2/res\.partner/create"))
+        .respond_with(ResponseTemplate::new(422).set_body_json(json!({
+            "name": "odoo.exceptions.ValidationError",
+            "message": "The VAT number is not valid.",
+            "arguments": ["The VAT number is not valid."],
+            "context": {},
+            "debug": "Traceback (most recent call last): ..."
+        })))
+        .mount(&mock.server)
+        .await;
+
+    let config = create_config(&mock.uri());
+    let client = OdooHttpClient::new(&config).unwrap();
+
+    let err = client
+        .create("res.partner", json!({"vat": "x"}), None)
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.kind(), Some(OdooErrorKind::ValidationError));
+    assert_eq!(err.message(), "The VAT number is not valid.");
+    assert!(err.hint().is_some());
+    assert!(err.to_string().contains("422"));
+}
+
+#[tokio::test]
+async fn test_api_error_unknown_model_is_missing_model() {
+    let mock = MockOdooServer::start().await;
+    Mock::given(method("POST"))
+        .and(path_regex(r"/json/2/res\.partnr/search"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(json!({
+            "name": "werkzeug.exceptions.NotFound",
+            "message": "404 Not Found: the model 'res.partnr' does not exist",
+            "arguments": [],
+            "context": {},
+            "debug": ""
+        })))
+        .mount(&mock.server)
+        .await;
+
+    let config = create_config(&mock.uri());
+    let client = OdooHttpClient::new(&config).unwrap();
+
+    let err = client
+        .search("res.partnr", None, None, None, None, None)
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.kind(), Some(OdooErrorKind::MissingModel));
+    assert_eq!(err.traceback(), None);
+}
+
 // ============================================================================
 // Header Validation Tests
 // ============================================================================
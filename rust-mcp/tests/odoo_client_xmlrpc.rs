@@ -0,0 +1,241 @@
+//! Integration tests for the XML-RPC transport of the legacy client, against a
+//! stub `/xmlrpc/2/*` server.
+
+use rust_mcp::odoo::config::{OdooInstanceConfig, OdooProtocol};
+use rust_mcp::odoo::types::OdooErrorKind;
+use rust_mcp::odoo::unified_client::OdooClient;
+use serde_json::json;
+use std::collections::HashMap;
+use wiremock::matchers::{body_string_contains, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn create_client(url: &str) -> OdooClient {
+    OdooClient::new(&OdooInstanceConfig {
+        url: url.to_string(),
+        db: Some("test_db".to_string()),
+        api_key: None,
+        username: Some("admin".to_string()),
+        password: Some("admin123".to_string()),
+        version: Some("14".to_string()),
+        timeout_ms: Some(5000),
+        max_retries: Some(0),
+        protocol: Some(OdooProtocol::XmlRpc),
+        transport: Default::default(),
+        extra: HashMap::new(),
+    })
+    .unwrap()
+}
+
+fn xml(body: &str) -> ResponseTemplate {
+    ResponseTemplate::new(200)
+        .insert_header("content-type", "text/xml")
+        .set_body_string(format!(
+            "<?xml version='1.0'?>\n<methodResponse>{body}</methodResponse>"
+        ))
+}
+
+fn value(inner: &str) -> ResponseTemplate {
+    xml(&format!(
+        "<params><param><value>{inner}</value></param></params>"
+    ))
+}
+
+async fn mount_login(server: &MockServer) {
+    Mock::given(method("POST"))
+        .and(path("/xmlrpc/2/common"))
+        .and(header("content-type", "text/xml; charset=utf-8"))
+        .and(body_string_contains(
+            "<methodName>authenticate</methodName>",
+        ))
+        .respond_with(value("<int>2</int>"))
+        .expect(1)
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn test_xmlrpc_search_read_decodes_values() {
+    let server = MockServer::start().await;
+    mount_login(&server).await;
+    Mock::given(method("POST"))
+        .and(path("/xmlrpc/2/object"))
+        .and(body_string_contains("<methodName>execute_kw</methodName>"))
+        .and(body_string_contains("<string>search_read</string>"))
+        .respond_with(value(
+            "<array><data><value><struct>\
+               <member><name>id</name><value><int>7</int></value></member>\
+               <member><name>name</name><value><string>ACME &amp; Co</string></value></member>\
+               <member><name>partner_id</name><value><array><data>\
+                 <value><int>3</int></value><value><string>Parent</string></value>\
+               </data></array></value></member>\
+               <member><name>email</name><value><boolean>0</boolean></value></member>\
+               <member><name>comment</name><value><nil/></value></member>\
+               <member><name>write_date</name><value><dateTime.iso8601>20240115T10:30:00</dateTime.iso8601></value></member>\
+               <member><name>image_128</name><value><base64>iVBORw0K\nGgo=</base64></value></member>\
+             </struct></value></data></array>",
+        ))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = create_client(&server.uri());
+    let records = client
+        .search_read(
+            "res.partner",
+            Some(json!([["is_company", "=", true]])),
+            None,
+            Some(5),
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        records,
+        json!([{
+            "id": 7,
+            "name": "ACME & Co",
+            "partner_id": [3, "Parent"],
+            "email": false,
+            "comment": null,
+            "write_date": "2024-01-15 10:30:00",
+            "image_128": "iVBORw0KGgo="
+        }])
+    );
+
+    let requests = server.received_requests().await.unwrap();
+    let call = String::from_utf8(requests.last().unwrap().body.clone()).unwrap();
+    // [db, uid, password, model, method, args, kwargs]
+    assert!(call.contains(
+        "<param><value><string>test_db</string></value></param>\
+         <param><value><int>2</int></value></param>\
+         <param><value><string>admin123</string></value></param>\
+         <param><value><string>res.partner</string></value></param>"
+    ));
+    assert!(call.contains("<member><name>limit</name><value><int>5</int></value></member>"));
+    assert!(call.contains("<value><boolean>1</boolean></value>"));
+}
+
+#[tokio::test]
+async fn test_xmlrpc_write_sends_nil_and_returns_bool() {
+    let server = MockServer::start().await;
+    mount_login(&server).await;
+    Mock::given(method("POST"))
+        .and(path("/xmlrpc/2/object"))
+        .and(body_string_contains("<string>write</string>"))
+        .and(body_string_contains(
+            "<member><name>parent_id</name><value><nil/></value></member>",
+        ))
+        .respond_with(value("<boolean>1</boolean>"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = create_client(&server.uri());
+    let ok = client
+        .write("res.partner", vec![7], json!({"parent_id": null}), None)
+        .await
+        .unwrap();
+    assert!(ok);
+}
+
+#[tokio::test]
+async fn test_xmlrpc_fault_is_typed() {
+    let server = MockServer::start().await;
+    mount_login(&server).await;
+    Mock::given(method("POST"))
+        .and(path("/xmlrpc/2/object"))
+        .respond_with(xml(
+            "<fault><value><struct>\
+               <member><name>faultCode</name><value><int>4</int></value></member>\
+               <member><name>faultString</name><value><string>You are not allowed to access 'Contact' (res.partner) records.</string></value></member>\
+             </struct></value></fault>",
+        ))
+        .mount(&server)
+        .await;
+
+    let client = create_client(&server.uri());
+    let err = client
+        .search("res.partner", None, None, None, None, None)
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), Some(OdooErrorKind::AccessError));
+    assert_eq!(
+        err.message(),
+        "You are not allowed to access 'Contact' (res.partner) records."
+    );
+}
+
+#[tokio::test]
+async fn test_xmlrpc_rejected_login() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/xmlrpc/2/common"))
+        .respond_with(value("<boolean>0</boolean>"))
+        .mount(&server)
+        .await;
+
+    let client = create_client(&server.uri());
+    let err = client
+        .search("res.partner", None, None, None, None, None)
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), Some(OdooErrorKind::AccessDenied));
+}
+
+#[tokio::test]
+async fn test_xmlrpc_report_opens_web_session() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/web/session/authenticate"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("set-cookie", "session_id=abc123; Path=/; HttpOnly")
+                .set_body_json(json!({"jsonrpc": "2.0", "id": 1, "result": {"uid": 2}})),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/report/pdf/sale.report_saleorder/1"))
+        .and(header("cookie", "session_id=abc123"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(b"%PDF-1.4".to_vec()))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let client = create_client(&server.uri());
+    for _ in 0..2 {
+        let pdf = client
+            .download_report_pdf("sale.report_saleorder", &[1])
+            .await
+            .unwrap();
+        assert_eq!(pdf, b"%PDF-1.4");
+    }
+}
+
+#[tokio::test]
+async fn test_xmlrpc_report_without_web_session_route() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/web/session/authenticate"))
+        .respond_with(ResponseTemplate::new(404).set_body_string("Not Found"))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/report/pdf/sale.report_saleorder/1"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(b"%PDF-1.4".to_vec()))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let client = create_client(&server.uri());
+    let err = client
+        .download_report_pdf("sale.report_saleorder", &[1])
+        .await
+        .unwrap_err();
+    assert_ne!(err.kind(), Some(OdooErrorKind::SessionExpired));
+    assert!(err.message().contains("Reports need an Odoo web session"));
+}
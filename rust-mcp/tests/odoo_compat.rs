@@ -0,0 +1,224 @@
+//! Integration tests for the version shims of OdooClient: the same calls go to
+//! the methods each server version provides.
+
+use rust_mcp::odoo::config::OdooInstanceConfig;
+use rust_mcp::odoo::unified_client::OdooClient;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use wiremock::matchers::{body_string_contains, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn legacy_client(url: &str, version: &str) -> OdooClient {
+    OdooClient::new(&OdooInstanceConfig {
+        url: url.to_string(),
+        db: Some("test_db".to_string()),
+        api_key: None,
+        username: Some("admin".to_string()),
+        password: Some("admin123".to_string()),
+        version: Some(version.to_string()),
+        timeout_ms: Some(5000),
+        max_retries: Some(0),
+        protocol: None,
+        transport: Default::default(),
+        extra: HashMap::new(),
+    })
+    .unwrap()
+}
+
+fn jsonrpc_success(result: Value) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(json!({"jsonrpc": "2.0", "id": 1, "result": result}))
+}
+
+async fn legacy_server() -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/web/session/authenticate"))
+        .respond_with(jsonrpc_success(json!({"uid": 2})))
+        .mount(&server)
+        .await;
+    server
+}
+
+/// Answer `execute_kw` calls of `odoo_method` with `result`, exactly once.
+async fn mount_method(server: &MockServer, odoo_method: &str, result: Value) {
+    Mock::given(method("POST"))
+        .and(path("/jsonrpc"))
+        .and(body_string_contains(format!("\"{odoo_method}\"")))
+        .respond_with(jsonrpc_success(result))
+        .expect(1)
+        .mount(server)
+        .await;
+}
+
+/// `args` of the last `execute_kw` call: [db, uid, password, model, method, args, kwargs].
+async fn last_call(server: &MockServer) -> Value {
+    let requests = server.received_requests().await.unwrap();
+    let body: Value = requests.last().unwrap().body_json().unwrap();
+    body["params"]["args"].clone()
+}
+
+#[tokio::test]
+async fn test_name_get_reads_display_name_on_17() {
+    let server = legacy_server().await;
+    mount_method(
+        &server,
+        "read",
+        json!([{"id": 1, "display_name": "ACME"}, {"id": 2, "display_name": "ACME, Bob"}]),
+    )
+    .await;
+
+    let client = legacy_client(&server.uri(), "17.0");
+    let names = client
+        .name_get("res.partner", vec![1, 2], None)
+        .await
+        .unwrap();
+    assert_eq!(names, json!([[1, "ACME"], [2, "ACME, Bob"]]));
+    let call = last_call(&server).await;
+    assert_eq!(call[5], json!([[1, 2]]));
+    assert_eq!(call[6]["fields"], json!(["display_name"]));
+}
+
+#[tokio::test]
+async fn test_name_get_unchanged_on_16() {
+    let server = legacy_server().await;
+    mount_method(&server, "name_get", json!([[1, "ACME"]])).await;
+
+    let client = legacy_client(&server.uri(), "16.0");
+    let names = client.name_get("res.partner", vec![1], None).await.unwrap();
+    assert_eq!(names, json!([[1, "ACME"]]));
+}
+
+#[tokio::test]
+async fn test_check_access_uses_has_access_on_18() {
+    let server = legacy_server().await;
+    Mock::given(method("POST"))
+        .and(path("/jsonrpc"))
+        .and(body_string_contains("\"has_access\""))
+        .respond_with(jsonrpc_success(json!(true)))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let client = legacy_client(&server.uri(), "18.0");
+    let result = client
+        .check_access("res.partner", "write", Some(vec![5]), None)
+        .await
+        .unwrap();
+    assert_eq!(result["has_access"], true);
+    assert_eq!(result["model_level"], true);
+    assert_eq!(result["record_level"], true);
+    let call = last_call(&server).await;
+    assert_eq!(call[5], json!([[5]]));
+    assert_eq!(call[6], json!({"operation": "write"}));
+}
+
+#[tokio::test]
+async fn test_check_access_reports_denial_on_18() {
+    let server = legacy_server().await;
+    mount_method(&server, "has_access", json!(false)).await;
+
+    let client = legacy_client(&server.uri(), "18.0");
+    let result = client
+        .check_access("res.partner", "unlink", None, None)
+        .await
+        .unwrap();
+    assert_eq!(result["has_access"], false);
+    assert_eq!(result["record_level"], Value::Null);
+}
+
+#[tokio::test]
+async fn test_check_access_uses_access_rights_on_17() {
+    let server = legacy_server().await;
+    mount_method(&server, "check_access_rights", json!(true)).await;
+    mount_method(&server, "check_access_rule", json!(null)).await;
+
+    let client = legacy_client(&server.uri(), "17.0");
+    let result = client
+        .check_access("res.partner", "read", Some(vec![1]), None)
+        .await
+        .unwrap();
+    assert_eq!(result["has_access"], true);
+}
+
+#[tokio::test]
+async fn test_read_group_uses_formatted_read_group_on_19() {
+    let server = legacy_server().await;
+    mount_method(
+        &server,
+        "fields_get",
+        json!({"amount_total": {"aggregator": "sum"}}),
+    )
+    .await;
+    mount_method(
+        &server,
+        "formatted_read_group",
+        json!([{
+            "partner_id": [3, "ACME"],
+            "amount_total:sum": 150.0,
+            "__count": 2,
+            "__extra_domain": [["partner_id", "=", 3]]
+        }]),
+    )
+    .await;
+
+    let client = legacy_client(&server.uri(), "19.0");
+    let groups = client
+        .read_group(
+            "sale.order",
+            Some(json!([["state", "=", "sale"]])),
+            vec!["partner_id".to_string(), "amount_total".to_string()],
+            vec!["partner_id".to_string()],
+            None,
+            Some(5),
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        groups,
+        json!([{
+            "partner_id": [3, "ACME"],
+            "amount_total": 150.0,
+            "partner_id_count": 2,
+            "__domain": [["state", "=", "sale"], ["partner_id", "=", 3]]
+        }])
+    );
+    let call = last_call(&server).await;
+    assert_eq!(call[5], json!([]));
+    assert_eq!(
+        call[6],
+        json!({
+            "domain": [["state", "=", "sale"]],
+            "groupby": ["partner_id"],
+            "aggregates": ["amount_total:sum", "__count"],
+            "limit": 5
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_read_group_unchanged_on_18() {
+    let server = legacy_server().await;
+    let groups = json!([{"partner_id": [3, "ACME"], "partner_id_count": 2}]);
+    mount_method(&server, "read_group", groups.clone()).await;
+
+    let client = legacy_client(&server.uri(), "18.0");
+    let result = client
+        .read_group(
+            "sale.order",
+            None,
+            vec!["partner_id".to_string()],
+            vec!["partner_id".to_string()],
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(result, groups);
+}
@@ -15,6 +15,8 @@ mod tests {
             version: Some("19".to_string()),
             timeout_ms: Some(30_000),
             max_retries: Some(3),
+            protocol: None,
+            transport: Default::default(),
             extra: HashMap::new(),
         };
 
@@ -36,6 +38,8 @@ mod tests {
             version: None,
             timeout_ms: None,
             max_retries: None,
+            protocol: None,
+            transport: Default::default(),
             extra: HashMap::new(),
         };
 
@@ -59,6 +63,8 @@ mod tests {
                 version: Some("19".to_string()),
                 timeout_ms: Some(30_000),
                 max_retries: Some(5),
+                protocol: None,
+                transport: Default::default(),
                 extra: HashMap::new(),
             },
         );
@@ -85,6 +91,8 @@ mod tests {
                 version: Some("19".to_string()),
                 timeout_ms: Some(20_000),
                 max_retries: Some(2),
+                protocol: None,
+                transport: Default::default(),
                 extra: HashMap::new(),
             },
         );
@@ -100,6 +108,8 @@ mod tests {
                 version: Some("19".to_string()),
                 timeout_ms: Some(25_000),
                 max_retries: Some(3),
+                protocol: None,
+                transport: Default::default(),
                 extra: HashMap::new(),
             },
         );
@@ -121,6 +131,8 @@ mod tests {
             db: None,
             timeout_ms: None,
             max_retries: None,
+            protocol: None,
+            transport: Default::default(),
             extra: HashMap::new(),
         };
 
@@ -138,6 +150,8 @@ mod tests {
             db: None,
             timeout_ms: None,
             max_retries: None,
+            protocol: None,
+            transport: Default::default(),
             extra: HashMap::new(),
         };
 
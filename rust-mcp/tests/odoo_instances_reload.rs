@@ -0,0 +1,84 @@
+//! Integration test for hot-reloading the instances file: the pool diffs the
+//! configurations and clients are told the instance list changed.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use rust_mcp::mcp::{McpOdooHandler, registry::Registry, tools::OdooClientPool};
+use serde_json::json;
+
+fn write_instances(path: &std::path::Path, instances: serde_json::Value) {
+    std::fs::write(path, serde_json::to_string_pretty(&instances).unwrap()).unwrap();
+}
+
+#[tokio::test]
+async fn test_instances_file_reload() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("instances.json");
+    write_instances(
+        &path,
+        json!({
+            "prod": {"url": "https://prod.example.com", "apiKey": "k1"},
+            "staging": {"url": "https://staging.example.com", "apiKey": "k2"}
+        }),
+    );
+    unsafe {
+        std::env::remove_var("ODOO_INSTANCES");
+        std::env::set_var("ODOO_INSTANCES_JSON", &path);
+    }
+
+    let pool = OdooClientPool::from_env().unwrap();
+    let mut changes = pool.subscribe_changes();
+    let handler = Arc::new(McpOdooHandler::new(
+        pool.clone(),
+        Arc::new(Registry::from_env()),
+    ));
+
+    // Unchanged file: nothing to do.
+    assert!(pool.reload().await.unwrap().is_empty());
+
+    write_instances(
+        &path,
+        json!({
+            "prod": {"url": "https://prod.example.com", "apiKey": "rotated"},
+            "dev": {"url": "http://localhost:8069", "apiKey": "k3"}
+        }),
+    );
+    let diff = handler.reload_instances().await.unwrap();
+    assert_eq!(diff.added, vec!["dev"]);
+    assert_eq!(diff.removed, vec!["staging"]);
+    assert_eq!(diff.changed, vec!["prod"]);
+    assert_eq!(changes.recv().await.unwrap(), diff);
+
+    let mut names = handler.instance_names();
+    names.sort();
+    assert_eq!(names, vec!["dev", "prod"]);
+    let err = match pool.get("staging").await {
+        Ok(_) => panic!("removed instance still available"),
+        Err(e) => e.to_string(),
+    };
+    assert!(err.contains("Unknown Odoo instance 'staging'"), "{err}");
+
+    // A broken file keeps the last good configuration.
+    std::fs::write(&path, "{ not json").unwrap();
+    assert!(pool.reload().await.is_err());
+    assert_eq!(handler.instance_names().len(), 2);
+
+    // Saving the file is picked up by the watcher and announced to clients.
+    let mut notifications = handler.subscribe_notifications();
+    handler.start_watchers();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    write_instances(
+        &path,
+        json!({"prod": {"url": "https://prod.example.com", "apiKey": "rotated"}}),
+    );
+    let notification = tokio::time::timeout(Duration::from_secs(10), notifications.recv())
+        .await
+        .expect("no notification after saving the instances file")
+        .unwrap();
+    assert_eq!(
+        notification["method"],
+        "notifications/resources/list_changed"
+    );
+    assert_eq!(handler.instance_names(), vec!["prod"]);
+}
@@ -0,0 +1,275 @@
+//! Integration tests for method-aware retries and idempotent creates.
+
+use rust_mcp::odoo::config::OdooInstanceConfig;
+use rust_mcp::odoo::unified_client::OdooClient;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+fn create_client(url: &str, max_retries: usize) -> OdooClient {
+    OdooClient::new(&OdooInstanceConfig {
+        url: url.to_string(),
+        db: Some("test_db".to_string()),
+        api_key: Some("test_api_key".to_string()),
+        username: None,
+        password: None,
+        version: Some("19".to_string()),
+        timeout_ms: Some(5000),
+        max_retries: Some(max_retries),
+        protocol: None,
+        transport: Default::default(),
+        extra: HashMap::new(),
+    })
+    .unwrap()
+}
+
+/// Responds with each template in turn, repeating the last one.
+struct Sequence {
+    calls: AtomicUsize,
+    responses: Vec<ResponseTemplate>,
+}
+
+impl Sequence {
+    fn new(responses: Vec<ResponseTemplate>) -> Self {
+        Self {
+            calls: AtomicUsize::new(0),
+            responses,
+        }
+    }
+}
+
+impl Respond for Sequence {
+    fn respond(&self, _request: &Request) -> ResponseTemplate {
+        let n = self.calls.fetch_add(1, Ordering::SeqCst);
+        self.responses[n.min(self.responses.len() - 1)].clone()
+    }
+}
+
+fn ok(body: Value) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(body)
+}
+
+fn unavailable() -> ResponseTemplate {
+    ResponseTemplate::new(503).set_body_string("Service Unavailable")
+}
+
+#[tokio::test]
+async fn test_read_is_retried_on_server_error() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/json/2/res.partner/search"))
+        .respond_with(Sequence::new(vec![unavailable(), ok(json!([1, 2]))]))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let client = create_client(&server.uri(), 3);
+    let ids = client
+        .search("res.partner", None, None, None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(ids, vec![1, 2]);
+}
+
+#[tokio::test]
+async fn test_create_is_not_retried_on_server_error() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/json/2/res.partner/create"))
+        .respond_with(unavailable())
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = create_client(&server.uri(), 3);
+    let result = client
+        .create("res.partner", json!({"name": "ACME"}), None)
+        .await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_named_method_is_not_retried_on_server_error() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/json/2/res.partner/message_post"))
+        .respond_with(unavailable())
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = create_client(&server.uri(), 3);
+    let result = client
+        .call_named(
+            "res.partner",
+            "message_post",
+            Some(vec![1]),
+            serde_json::Map::new(),
+            None,
+        )
+        .await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_retry_after_is_honored_on_429() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/json/2/res.partner/search_count"))
+        .respond_with(Sequence::new(vec![
+            ResponseTemplate::new(429).insert_header("retry-after", "1"),
+            ok(json!(5)),
+        ]))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let client = create_client(&server.uri(), 1);
+    let started = Instant::now();
+    let count = client
+        .search_count("res.partner", None, None)
+        .await
+        .unwrap();
+    assert_eq!(count, 5);
+    // Plain backoff for the first retry is at most 250ms.
+    assert!(started.elapsed() >= Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn test_idempotent_create_checks_before_resending() {
+    let server = MockServer::start().await;
+    // Nothing found before the first attempt; the timed-out create did commit.
+    Mock::given(method("POST"))
+        .and(path("/json/2/res.partner/search"))
+        .respond_with(Sequence::new(vec![ok(json!([])), ok(json!([42]))]))
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/json/2/res.partner/create"))
+        .respond_with(ResponseTemplate::new(504))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = create_client(&server.uri(), 2);
+    let (id, created) = client
+        .create_idempotent(
+            "res.partner",
+            json!({"name": "ACME"}),
+            None,
+            "ref",
+            "order-123",
+        )
+        .await
+        .unwrap();
+    assert_eq!((id, created), (42, false));
+
+    let requests = server.received_requests().await.unwrap();
+    let create: Value = requests
+        .iter()
+        .find(|r| r.url.path().ends_with("/create"))
+        .unwrap()
+        .body_json()
+        .unwrap();
+    assert_eq!(create["vals_list"][0]["ref"], "order-123");
+    let search: Value = requests[0].body_json().unwrap();
+    assert_eq!(search["domain"], json!([["ref", "=", "order-123"]]));
+    assert_eq!(search["context"]["active_test"], false);
+}
+
+#[tokio::test]
+async fn test_idempotent_create_returns_existing_record() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/json/2/res.partner/search"))
+        .respond_with(ok(json!([7])))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/json/2/res.partner/create"))
+        .respond_with(ok(json!([8])))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let client = create_client(&server.uri(), 0);
+    let result = client
+        .create_idempotent(
+            "res.partner",
+            json!({"name": "ACME"}),
+            None,
+            "ref",
+            "order-123",
+        )
+        .await
+        .unwrap();
+    assert_eq!(result, (7, false));
+}
+
+#[tokio::test]
+async fn test_idempotent_create_finds_archived_record_on_legacy() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/web/session/authenticate"))
+        .respond_with(ok(json!({"jsonrpc": "2.0", "id": 1, "result": {"uid": 2}})))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/jsonrpc"))
+        .respond_with(ok(json!({"jsonrpc": "2.0", "id": 1, "result": [9]})))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = OdooClient::new(&OdooInstanceConfig {
+        url: server.uri(),
+        db: Some("test_db".to_string()),
+        api_key: None,
+        username: Some("admin".to_string()),
+        password: Some("admin".to_string()),
+        version: Some("17".to_string()),
+        timeout_ms: Some(5000),
+        max_retries: Some(0),
+        protocol: None,
+        transport: Default::default(),
+        extra: HashMap::new(),
+    })
+    .unwrap();
+    let result = client
+        .create_idempotent(
+            "res.partner",
+            json!({"name": "ACME"}),
+            None,
+            "ref",
+            "order-123",
+        )
+        .await
+        .unwrap();
+    assert_eq!(result, (9, false));
+
+    // The lookup must see archived records, so its context reaches Odoo.
+    let requests = server.received_requests().await.unwrap();
+    let search: Value = requests.last().unwrap().body_json().unwrap();
+    let args = &search["params"]["args"];
+    assert_eq!(args[4], "search");
+    assert_eq!(args[6]["context"]["active_test"], false);
+}
+
+#[tokio::test]
+async fn test_idempotent_create_requires_single_record() {
+    let client = create_client("http://127.0.0.1:1", 0);
+    let result = client
+        .create_idempotent(
+            "res.partner",
+            json!([{"name": "A"}, {"name": "B"}]),
+            None,
+            "ref",
+            "k",
+        )
+        .await;
+    assert!(result.is_err());
+}
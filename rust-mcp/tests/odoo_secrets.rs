@@ -0,0 +1,49 @@
+//! Integration test for secret references in instance credentials.
+
+use rust_mcp::odoo::config::load_odoo_env;
+use serde_json::json;
+
+#[test]
+fn test_load_odoo_env_resolves_secret_references() {
+    let dir = tempfile::tempdir().unwrap();
+    let password_file = dir.path().join("legacy_password");
+    std::fs::write(&password_file, "s3cret\n").unwrap();
+
+    let instances = json!({
+        "prod": {
+            "url": "https://odoo.example.com",
+            "apiKey": "env:SECRETS_IT_PROD_KEY",
+            "headers": {"CF-Access-Client-Secret": "env:SECRETS_IT_CF", "X-Env": "prod"}
+        },
+        "legacy": {
+            "url": "http://legacy:8069",
+            "db": "legacy",
+            "version": "16",
+            "username": "admin",
+            "password": format!("file:{}", password_file.display())
+        }
+    });
+    unsafe {
+        std::env::remove_var("ODOO_INSTANCES_JSON");
+        std::env::set_var("ODOO_INSTANCES", instances.to_string());
+        std::env::set_var("SECRETS_IT_PROD_KEY", "prod-key");
+        std::env::set_var("SECRETS_IT_CF", "cf-secret");
+    }
+
+    let env = load_odoo_env().unwrap();
+    let prod = &env.instances["prod"];
+    assert_eq!(prod.api_key.as_deref(), Some("prod-key"));
+    assert_eq!(
+        prod.transport.headers["CF-Access-Client-Secret"],
+        "cf-secret"
+    );
+    assert_eq!(prod.transport.headers["X-Env"], "prod");
+    assert_eq!(env.instances["legacy"].password.as_deref(), Some("s3cret"));
+
+    // A missing secret fails loading and names the field, not a value.
+    unsafe { std::env::remove_var("SECRETS_IT_PROD_KEY") };
+    let err = load_odoo_env().unwrap_err().to_string();
+    assert!(err.contains("apiKey of instance 'prod'"), "{err}");
+    assert!(err.contains("SECRETS_IT_PROD_KEY"), "{err}");
+    assert!(!err.contains("cf-secret"));
+}
@@ -0,0 +1,111 @@
+//! Integration tests for per-instance transport settings: extra headers and an
+//! explicit HTTP proxy.
+
+use rust_mcp::odoo::config::{OdooInstanceConfig, OdooTransportConfig};
+use rust_mcp::odoo::unified_client::OdooClient;
+use serde_json::json;
+use std::collections::HashMap;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn test_extra_headers_sent_with_json2_calls_and_reports() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/json/2/res.partner/search"))
+        .and(header("cf-access-client-id", "mcp"))
+        .and(header("authorization", "bearer test_api_key"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([1])))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/report/pdf/sale.report_saleorder/1"))
+        .and(header("cf-access-client-id", "mcp"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "application/pdf")
+                .set_body_bytes(b"%PDF-1.4".to_vec()),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = OdooClient::new(&OdooInstanceConfig {
+        url: server.uri(),
+        db: None,
+        api_key: Some("test_api_key".to_string()),
+        username: None,
+        password: None,
+        version: Some("19".to_string()),
+        timeout_ms: Some(5000),
+        max_retries: Some(0),
+        protocol: None,
+        transport: OdooTransportConfig {
+            headers: HashMap::from([("CF-Access-Client-Id".to_string(), "mcp".to_string())]),
+            ..Default::default()
+        },
+        extra: HashMap::new(),
+    })
+    .unwrap();
+
+    let ids = client
+        .search("res.partner", None, None, None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(ids, vec![1]);
+    let pdf = client
+        .download_report_pdf("sale.report_saleorder", &[1])
+        .await
+        .unwrap();
+    assert!(pdf.starts_with(b"%PDF"));
+}
+
+#[tokio::test]
+async fn test_legacy_calls_go_through_proxy() {
+    // The mock plays the proxy: requests for odoo.internal arrive here.
+    let proxy = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/web/session/authenticate"))
+        .and(header("host", "odoo.internal:8069"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "jsonrpc": "2.0", "id": 1, "result": {"uid": 2}
+        })))
+        .expect(1)
+        .mount(&proxy)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/jsonrpc"))
+        .and(header("host", "odoo.internal:8069"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "jsonrpc": "2.0", "id": 1, "result": 3
+        })))
+        .expect(1)
+        .mount(&proxy)
+        .await;
+
+    let client = OdooClient::new(&OdooInstanceConfig {
+        url: "http://odoo.internal:8069".to_string(),
+        db: Some("test_db".to_string()),
+        api_key: None,
+        username: Some("admin".to_string()),
+        password: Some("admin".to_string()),
+        version: Some("17".to_string()),
+        timeout_ms: Some(5000),
+        max_retries: Some(0),
+        protocol: None,
+        transport: OdooTransportConfig {
+            proxy: Some(proxy.uri()),
+            no_proxy: Some("localhost".to_string()),
+            ..Default::default()
+        },
+        extra: HashMap::new(),
+    })
+    .unwrap();
+
+    let count = client
+        .search_count("res.partner", None, None)
+        .await
+        .unwrap();
+    assert_eq!(count, 3);
+}
@@ -0,0 +1,111 @@
+//! Integration tests for server version detection in the client pool.
+
+use rust_mcp::mcp::resources::read_resource;
+use rust_mcp::mcp::tools::OdooClientPool;
+use rust_mcp::odoo::version::Edition;
+use serde_json::json;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn version_server(version_info: serde_json::Value) -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/web/webclient/version_info"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": version_info
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    server
+}
+
+#[tokio::test]
+async fn test_pool_detects_version_and_picks_client() {
+    // Configured as 18, actually 19: JSON-2 is used.
+    let modern = version_server(json!({
+        "server_version": "19.0+e",
+        "server_version_info": [19, 0, 0, "final", 0, "e"]
+    }))
+    .await;
+    // No version configured, both credentials present, actually 16: JSON-RPC.
+    let legacy = version_server(json!({
+        "server_version": "16.0",
+        "server_version_info": [16, 0, 0, "final", 0, ""]
+    }))
+    .await;
+    // /web routes blocked: falls back to common.version over /jsonrpc.
+    let proxied = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/jsonrpc"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": {"server_version": "saas~17.2+e", "server_version_info": ["saas~17", 2, 0, "final", 0, "e"]}
+        })))
+        .expect(1)
+        .mount(&proxied)
+        .await;
+
+    let instances = json!({
+        "modern": {
+            "url": modern.uri(), "db": "db", "version": "18",
+            "apiKey": "key", "username": "admin", "password": "admin"
+        },
+        "legacy": {
+            "url": legacy.uri(), "db": "db",
+            "apiKey": "key", "username": "admin", "password": "admin"
+        },
+        "proxied": {
+            "url": proxied.uri(), "db": "db", "username": "admin", "password": "admin"
+        },
+        "down": {"url": "http://127.0.0.1:1", "apiKey": "key", "timeout_ms": 500}
+    });
+    unsafe {
+        std::env::remove_var("ODOO_INSTANCES_JSON");
+        std::env::set_var("ODOO_INSTANCES", instances.to_string());
+    }
+    let pool = OdooClientPool::from_env().unwrap();
+
+    let client = pool.get("modern").await.unwrap();
+    assert_eq!(client.api(), "json2");
+    let detected = pool.detected_version("modern").unwrap();
+    assert_eq!(detected.major, 19);
+    assert_eq!(detected.edition, Edition::Enterprise);
+    assert_eq!(pool.instance_version("modern"), Some(19));
+
+    let client = pool.get("legacy").await.unwrap();
+    assert_eq!(client.api(), "jsonrpc");
+    assert_eq!(
+        pool.detected_version("legacy").unwrap().edition,
+        Edition::Community
+    );
+
+    pool.get("proxied").await.unwrap();
+    let detected = pool.detected_version("proxied").unwrap();
+    assert_eq!((detected.major, detected.minor), (17, 2));
+
+    // Probe fails: configured settings are used and nothing is recorded.
+    let client = pool.get("down").await.unwrap();
+    assert_eq!(client.api(), "json2");
+    assert!(pool.detected_version("down").is_none());
+
+    // The client is cached, so the probe ran once per instance.
+    pool.get("modern").await.unwrap();
+
+    let resource = read_resource(&pool, "odoo://instances").await.unwrap();
+    let listed: serde_json::Value =
+        serde_json::from_str(resource["contents"][0]["text"].as_str().unwrap()).unwrap();
+    let modern = listed
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|i| i["name"] == "modern")
+        .unwrap();
+    assert_eq!(modern["configured_version"], "18");
+    assert_eq!(modern["server_version"]["server_version"], "19.0+e");
+    assert_eq!(modern["server_version"]["edition"], "enterprise");
+    assert_eq!(modern["api"], "json2");
+}
@@ -0,0 +1,104 @@
+//! Integration test for reloading tools.json and prompts.json: only changes of
+//! what clients see are announced, as tools/prompts list_changed notifications.
+
+mod common;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use common::{minimal_prompts_json, minimal_server_json};
+use rust_mcp::mcp::registry::{Registry, RegistryChanges};
+use rust_mcp::mcp::{McpOdooHandler, tools::OdooClientPool};
+use serde_json::{Value, json};
+
+const GUARD: &str = "REGISTRY_RELOAD_TEST_WRITE_TOOLS";
+
+fn tools(search_description: &str) -> String {
+    json!({
+        "tools": [
+            {
+                "name": "odoo_search",
+                "description": search_description,
+                "inputSchema": {"type": "object", "properties": {}},
+                "op": {"type": "search", "map": {}}
+            },
+            {
+                "name": "odoo_create",
+                "description": "Create a record",
+                "guards": {"requiresEnvTrue": GUARD},
+                "inputSchema": {"type": "object", "properties": {}},
+                "op": {"type": "create", "map": {}}
+            }
+        ]
+    })
+    .to_string()
+}
+
+#[tokio::test]
+async fn test_registry_reload_announces_list_changes() {
+    let dir = tempfile::tempdir().unwrap();
+    let tools_path = dir.path().join("tools.json");
+    let prompts_path = dir.path().join("prompts.json");
+    std::fs::write(&tools_path, tools("Search records")).unwrap();
+    std::fs::write(&prompts_path, minimal_prompts_json()).unwrap();
+    std::fs::write(dir.path().join("server.json"), minimal_server_json()).unwrap();
+    unsafe {
+        std::env::set_var("ODOO_URL", "http://localhost:8069");
+        std::env::set_var("ODOO_API_KEY", "test_key");
+        std::env::set_var("MCP_TOOLS_JSON", &tools_path);
+        std::env::set_var("MCP_PROMPTS_JSON", &prompts_path);
+        std::env::set_var("MCP_SERVER_JSON", dir.path().join("server.json"));
+        std::env::remove_var(GUARD);
+    }
+    let registry = Arc::new(Registry::from_env());
+    registry.initial_load().await.unwrap();
+    let handler = Arc::new(McpOdooHandler::new(
+        OdooClientPool::from_env().unwrap(),
+        registry.clone(),
+    ));
+    let mut notifications = handler.subscribe_notifications();
+    handler.start_watchers();
+
+    // Reformatting changes nothing clients see.
+    let pretty: Value = serde_json::from_str(&tools("Search records")).unwrap();
+    std::fs::write(&tools_path, serde_json::to_string_pretty(&pretty).unwrap()).unwrap();
+    assert!(registry.reload().await.unwrap().is_empty());
+
+    std::fs::write(&tools_path, tools("Search any model")).unwrap();
+    let changes = registry.reload().await.unwrap();
+    assert_eq!(
+        changes,
+        RegistryChanges {
+            tools: true,
+            prompts: false
+        }
+    );
+
+    // A guard enabling a tool shows on the next reload, even with the same files.
+    unsafe { std::env::set_var(GUARD, "true") };
+    assert!(registry.reload().await.unwrap().tools);
+    assert!(registry.reload().await.unwrap().is_empty());
+    unsafe { std::env::remove_var(GUARD) };
+
+    let prompts = minimal_prompts_json().replace("A test prompt", "An edited prompt");
+    assert_ne!(prompts, minimal_prompts_json());
+    std::fs::write(&prompts_path, prompts).unwrap();
+    let changes = registry.reload().await.unwrap();
+    assert!(changes.tools && changes.prompts, "{changes:?}");
+
+    let mut methods = Vec::new();
+    while let Ok(Ok(notification)) =
+        tokio::time::timeout(Duration::from_millis(500), notifications.recv()).await
+    {
+        methods.push(notification["method"].as_str().unwrap().to_string());
+    }
+    assert_eq!(
+        methods,
+        vec![
+            "notifications/tools/list_changed",
+            "notifications/tools/list_changed",
+            "notifications/tools/list_changed",
+            "notifications/prompts/list_changed",
+        ]
+    );
+}
@@ -0,0 +1,189 @@
+//! Integration test for resource subscriptions: sessions subscribed to the
+//! same record share one watch, are told when its `write_date` changes, and
+//! their subscriptions go away with them.
+
+mod common;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use common::{minimal_prompts_json, minimal_server_json, minimal_tools_json};
+use rust_mcp::mcp::McpOdooHandler;
+use rust_mcp::mcp::http::{AuthConfig, create_app};
+use rust_mcp::mcp::registry::Registry;
+use rust_mcp::mcp::tools::OdooClientPool;
+use serde_json::{Value, json};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const URI: &str = "odoo://default/record/res.partner/7";
+
+async fn post(base: &str, session: Option<&str>, body: Value) -> reqwest::Response {
+    let mut request = reqwest::Client::new()
+        .post(format!("{base}/mcp"))
+        .header("accept", "application/json")
+        .json(&body);
+    if let Some(session) = session {
+        request = request.header("mcp-session-id", session);
+    }
+    request.send().await.unwrap()
+}
+
+/// Initialize a session and subscribe it to [`URI`].
+async fn subscribed_session(base: &str) -> String {
+    let init = post(
+        base,
+        None,
+        json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}}),
+    )
+    .await;
+    let session = init.headers()["mcp-session-id"]
+        .to_str()
+        .unwrap()
+        .to_string();
+    post(
+        base,
+        Some(&session),
+        json!({"jsonrpc": "2.0", "method": "notifications/initialized"}),
+    )
+    .await;
+    let subscribed: Value = post(
+        base,
+        Some(&session),
+        json!({"jsonrpc": "2.0", "id": 2, "method": "resources/subscribe", "params": {"uri": URI}}),
+    )
+    .await
+    .json()
+    .await
+    .unwrap();
+    assert_eq!(subscribed["result"], json!({}), "{subscribed}");
+    session
+}
+
+/// Read a session's SSE stream until `until` shows up (or a timeout).
+async fn read_stream(base: &str, session: &str, until: &str) -> String {
+    let mut response = reqwest::Client::new()
+        .get(format!("{base}/mcp"))
+        .header("accept", "text/event-stream")
+        .header("mcp-session-id", session)
+        .send()
+        .await
+        .unwrap();
+    let mut body = String::new();
+    let _ = tokio::time::timeout(Duration::from_secs(10), async {
+        while let Some(chunk) = response.chunk().await.unwrap() {
+            body.push_str(&String::from_utf8_lossy(&chunk));
+            if body.contains(until) {
+                break;
+            }
+        }
+    })
+    .await;
+    body
+}
+
+#[tokio::test]
+async fn test_subscribed_sessions_notified_of_record_changes() {
+    let odoo = MockServer::start().await;
+    // Subscribing and the first poll see the record unchanged, later polls
+    // see it written.
+    Mock::given(method("POST"))
+        .and(path("/json/2/res.partner/search_read"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!([{"id": 7, "write_date": "2025-01-01 10:00:00"}])),
+        )
+        .up_to_n_times(3)
+        .with_priority(1)
+        .mount(&odoo)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/json/2/res.partner/search_read"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!([{"id": 7, "write_date": "2025-01-01 10:05:00"}])),
+        )
+        .mount(&odoo)
+        .await;
+
+    let temp = tempfile::TempDir::new().unwrap();
+    std::fs::write(temp.path().join("tools.json"), minimal_tools_json()).unwrap();
+    std::fs::write(temp.path().join("prompts.json"), minimal_prompts_json()).unwrap();
+    std::fs::write(temp.path().join("server.json"), minimal_server_json()).unwrap();
+    unsafe {
+        std::env::set_var("ODOO_URL", odoo.uri());
+        std::env::set_var("ODOO_DB", "test_db");
+        std::env::set_var("ODOO_API_KEY", "test_key");
+        std::env::set_var("ODOO_VERSION_DETECTION", "false");
+        std::env::set_var("MCP_RESOURCE_POLL_SECS", "1");
+        std::env::set_var("MCP_TOOLS_JSON", temp.path().join("tools.json"));
+        std::env::set_var("MCP_PROMPTS_JSON", temp.path().join("prompts.json"));
+        std::env::set_var("MCP_SERVER_JSON", temp.path().join("server.json"));
+    }
+    let registry = Arc::new(Registry::from_env());
+    registry.initial_load().await.unwrap();
+    let handler = Arc::new(McpOdooHandler::new(
+        OdooClientPool::from_env().unwrap(),
+        registry,
+    ));
+    let app = create_app(handler.clone(), AuthConfig::disabled());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    let first = subscribed_session(&base).await;
+    let second = subscribed_session(&base).await;
+    // Both sessions watch one URI.
+    assert_eq!(handler.watched_resources(), 1);
+
+    let (first_events, second_events) = tokio::join!(
+        read_stream(&base, &first, "notifications/resources/updated"),
+        read_stream(&base, &second, "notifications/resources/updated"),
+    );
+    for events in [&first_events, &second_events] {
+        assert!(
+            events.contains("notifications/resources/updated"),
+            "{events}"
+        );
+        assert!(events.contains(URI), "{events}");
+    }
+
+    // Ending a session drops its subscription; the URI stays watched for the other.
+    let deleted = reqwest::Client::new()
+        .delete(format!("{base}/mcp"))
+        .header("mcp-session-id", &first)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(deleted.status(), 200);
+    assert_eq!(handler.watched_resources(), 1);
+
+    let unsubscribed: Value = post(
+        &base,
+        Some(&second),
+        json!({"jsonrpc": "2.0", "id": 3, "method": "resources/unsubscribe", "params": {"uri": URI}}),
+    )
+    .await
+    .json()
+    .await
+    .unwrap();
+    assert_eq!(unsubscribed["result"], json!({}));
+    assert_eq!(handler.watched_resources(), 0);
+
+    // Only records and searches can be watched.
+    let refused: Value = post(
+        &base,
+        Some(&second),
+        json!({"jsonrpc": "2.0", "id": 4, "method": "resources/subscribe", "params": {"uri": "odoo://instances"}}),
+    )
+    .await
+    .json()
+    .await
+    .unwrap();
+    assert!(
+        refused["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("does not support subscriptions")
+    );
+}